@@ -2,8 +2,10 @@ import {PublicKey, SystemProgram, SYSVAR_RENT_PUBKEY, TransactionInstruction,} f
 import BN from "bn.js";
 import {Schema, serialize} from "borsh";
 import {ASSOCIATED_TOKEN_PROGRAM_ID, Token, TOKEN_PROGRAM_ID,} from "@solana/spl-token";
-import {NEIGHBORHOOD_SIZE, NEIGHBORHOOD_METADATA_SEED, SPACE_METADATA_SEED, SPACE_PROGRAM_ID, SELL_DELEGATE_SEED,} from "../constants";
+import {METADATA_PROGRAM_ID, NEIGHBORHOOD_SIZE, NEIGHBORHOOD_METADATA_SEED, SPACE_METADATA_SEED, SPACE_PROGRAM_ID, SELL_DELEGATE_SEED, LISTING_SEED, RENT_ACCOUNT_SEED, RENT_PROGRAM_ID,} from "../constants";
 import {correct_negative_serialization, twoscomplement_i2u} from "../utils/borsh";
+import {getTreasury} from "./update_treasury";
+import {decodeMetadata} from "./metadata";
 
 export const ACCEPT_OFFER_INSTRUCTION_ID = 4;
 export class AcceptOfferInstructionData {
@@ -44,6 +46,7 @@ export class AcceptOfferArgs{
   mint: PublicKey;
   price: number;
   seller: PublicKey;
+  payment_mint?: PublicKey;
 
   constructor(args: {
     x: number;
@@ -51,12 +54,14 @@ export class AcceptOfferArgs{
     mint: PublicKey;
     price: number;
     seller: PublicKey;
+    payment_mint?: PublicKey;
   }) {
     this.x = args.x;
     this.y = args.y;
     this.mint = args.mint;
     this.price = args.price;
     this.seller = args.seller;
+    this.payment_mint = args.payment_mint;
   }
 }
 
@@ -83,6 +88,7 @@ export const acceptOfferInstruction = async (
   );
 
   const neighborhoodCreator = await server.getNeighborhoodCreator(connection, n_x, n_y);
+  const treasury = await getTreasury(connection, base);
 
   const space_x = twoscomplement_i2u(x);
   const space_y = twoscomplement_i2u(y);
@@ -97,12 +103,41 @@ export const acceptOfferInstruction = async (
       SPACE_PROGRAM_ID
     );
 
+  // space metadata layout: bump u8, mint, price u64, space_x i64, space_y i64, escrow_seller, payment_mint
+  const payment_mint: PublicKey = change.payment_mint
+    ? change.payment_mint
+    : new PublicKey((await connection.getAccountInfo(space_metadata_account)).data.slice(89, 121));
+
   const [sell_delegate_account,] =
       await PublicKey.findProgramAddress(
       [base.toBuffer(), Buffer.from(SELL_DELEGATE_SEED)],
       SPACE_PROGRAM_ID
     );
 
+  // only read by the program for listings with terms beyond a fixed price
+  const [listing_account,] =
+      await PublicKey.findProgramAddress(
+      [
+        base.toBuffer(),
+        Buffer.from(LISTING_SEED),
+        Buffer.from(space_x),
+        Buffer.from(space_y),
+      ],
+      SPACE_PROGRAM_ID
+    );
+
+  // the rent program's account for the space, read to refuse the sale while the space is rented out
+  const [rent_account,] =
+      await PublicKey.findProgramAddress(
+      [
+        base.toBuffer(),
+        Buffer.from(RENT_ACCOUNT_SEED),
+        Buffer.from(space_x),
+        Buffer.from(space_y),
+      ],
+      RENT_PROGRAM_ID
+    );
+
   const alice_space_ATA = await Token.getAssociatedTokenAddress(
     ASSOCIATED_TOKEN_PROGRAM_ID,
     TOKEN_PROGRAM_ID,
@@ -119,6 +154,27 @@ export const acceptOfferInstruction = async (
     false
   );
 
+  // get metadata metaplex, royalties are paid after the primary sale to each verified creator with a share
+  const [metaplexMetadata,] = await PublicKey.findProgramAddress(
+    [
+      Buffer.from("metadata"),
+      METADATA_PROGRAM_ID.toBytes(),
+      mint.toBytes()
+    ],
+    METADATA_PROGRAM_ID
+  );
+  const metaplexMetadataData = decodeMetadata((await connection.getAccountInfo(metaplexMetadata)).data);
+  let royaltyCreators: PublicKey[] = [];
+  if (
+    metaplexMetadataData.primarySaleHappened &&
+    metaplexMetadataData.data.sellerFeeBasisPoints > 0 &&
+    metaplexMetadataData.data.creators
+  ) {
+    royaltyCreators = metaplexMetadataData.data.creators
+      .filter(creator => creator.verified && creator.share > 0)
+      .map(creator => new PublicKey(creator.address));
+  }
+
   const args = new AcceptOfferInstructionData({
     x,
     y,
@@ -195,9 +251,56 @@ export const acceptOfferInstruction = async (
       pubkey: SYSVAR_RENT_PUBKEY,
       isSigner: false,
       isWritable: false,
+    },    {
+      pubkey: treasury,
+      isSigner: false,
+      isWritable: true,
+    },
+    {
+      pubkey: metaplexMetadata,
+      isSigner: false,
+      isWritable: false,
+    },
+    {
+      pubkey: listing_account,
+      isSigner: false,
+      isWritable: true,
+    },
+    {
+      pubkey: rent_account,
+      isSigner: false,
+      isWritable: false,
     },
+    ...royaltyCreators.map(creator => ({
+      pubkey: creator,
+      isSigner: false,
+      isWritable: true,
+    })),
   ];
 
+  // listings priced in an SPL token are paid between ATAs for the payment mint, following the royalty creators
+  if (!payment_mint.equals(PublicKey.default)) {
+    const paymentATA = (owner: PublicKey) => Token.getAssociatedTokenAddress(
+      ASSOCIATED_TOKEN_PROGRAM_ID,
+      TOKEN_PROGRAM_ID,
+      payment_mint,
+      owner,
+      false
+    );
+    keys.push({
+      pubkey: payment_mint,
+      isSigner: false,
+      isWritable: false,
+    });
+    for (const owner of [wallet.publicKey, seller, neighborhoodCreator, treasury, ...royaltyCreators]) {
+      keys.push({
+        pubkey: await paymentATA(owner),
+        isSigner: false,
+        isWritable: true,
+      });
+    }
+  }
+
   let data = Buffer.from(serialize(AcceptOfferInstructionData.schema, args));
   // borsh JS sucks, need to be able to serialize negative numbers
   data = correct_negative_serialization(data, 1, 9, space_x);
@@ -2,8 +2,9 @@ import {PublicKey, SystemProgram, TransactionInstruction} from "@solana/web3.js"
 import BN from "bn.js";
 import {Schema, serialize} from "borsh";
 import {ASSOCIATED_TOKEN_PROGRAM_ID, Token, TOKEN_PROGRAM_ID} from "@solana/spl-token";
-import {SPACE_METADATA_SEED, SPACE_PROGRAM_ID, RENT_ACCOUNT_SEED, RENT_PROGRAM_ID} from "../constants";
+import {NEIGHBORHOOD_SIZE, NEIGHBORHOOD_METADATA_SEED, SPACE_METADATA_SEED, SPACE_PROGRAM_ID, RENT_ACCOUNT_SEED, RENT_ESCROW_SEED, RENT_PROGRAM_ID} from "../constants";
 import {correct_negative_serialization, twoscomplement_i2u} from "../utils/borsh";
+import {getTreasury} from "./update_treasury";
 
 export class AcceptRentInstructionData {
   instruction: number = 1;
@@ -101,6 +102,41 @@ export const acceptRentInstruction = async (
       RENT_PROGRAM_ID
     );
 
+  const [rent_escrow,] =
+    await PublicKey.findProgramAddress(
+      [
+        base.toBuffer(),
+        Buffer.from(RENT_ESCROW_SEED),
+        Buffer.from(space_x),
+        Buffer.from(space_y),
+      ],
+      RENT_PROGRAM_ID
+    );
+
+  const n_x = Math.floor(x / NEIGHBORHOOD_SIZE);
+  const n_y = Math.floor(y / NEIGHBORHOOD_SIZE);
+  const [neighborhoodMetadata,] = await PublicKey.findProgramAddress(
+    [
+      base.toBuffer(),
+      Buffer.from(NEIGHBORHOOD_METADATA_SEED),
+      Buffer.from(twoscomplement_i2u(n_x)),
+      Buffer.from(twoscomplement_i2u(n_y)),
+    ],
+    SPACE_PROGRAM_ID
+  );
+
+  const neighborhoodCreator = await server.getNeighborhoodCreator(connection, n_x, n_y);
+  const treasury = await getTreasury(connection, base);
+
+  // an expired rental left unsettled has its escrow settled into this one, paying its lessor and lessee
+  let previous_lessor = renter;
+  let previous_lessee = wallet.publicKey;
+  const rentEscrowData = await connection.getAccountInfo(rent_escrow);
+  if (rentEscrowData && rentEscrowData.data.length > 0) {
+    previous_lessor = new PublicKey(rentEscrowData.data.slice(1, 33));
+    previous_lessee = new PublicKey(rentEscrowData.data.slice(33, 65));
+  }
+
   const renter_space_ATA = await Token.getAssociatedTokenAddress(
     ASSOCIATED_TOKEN_PROGRAM_ID,
     TOKEN_PROGRAM_ID,
@@ -151,6 +187,35 @@ export const acceptRentInstruction = async (
       pubkey: SystemProgram.programId,
       isSigner: false,
       isWritable: false,
+    },    {
+      pubkey: neighborhoodMetadata,
+      isSigner: false,
+      isWritable: false,
+    },
+    {
+      pubkey: neighborhoodCreator,
+      isSigner: false,
+      isWritable: true,
+    },
+    {
+      pubkey: treasury,
+      isSigner: false,
+      isWritable: true,
+    },
+    {
+      pubkey: rent_escrow,
+      isSigner: false,
+      isWritable: true,
+    },
+    {
+      pubkey: previous_lessor,
+      isSigner: false,
+      isWritable: true,
+    },
+    {
+      pubkey: previous_lessee,
+      isSigner: false,
+      isWritable: true,
     },
   ];
 
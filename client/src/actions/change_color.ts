@@ -7,10 +7,14 @@ import {
     SPACE_PROGRAM_ID,
     NEIGHBORHOOD_FRAME_BASE_SEED,
     NEIGHBORHOOD_FRAME_POINTER_SEED,
+    NEIGHBORHOOD_COLOR_CONFIG_SEED,
     NEIGHBORHOOD_METADATA_SEED,
     NEIGHBORHOOD_SIZE,
+    RENT_ACCOUNT_SEED,
+    RENT_PROGRAM_ID,
 } from "../constants";
 import {correct_negative_serialization, twoscomplement_i2u} from "../utils/borsh";
+import {getTreasury} from "./update_treasury";
 import BN from 'bn.js';
 
 export const CHANGE_COLOR_INSTRUCTION_ID = 2;
@@ -114,7 +118,7 @@ export const changeColorInstruction = async (
   const [spaceATA,] =
     await PublicKey.findProgramAddress(
       [
-        wallet.publicKey.toBuffer(),
+        owner.toBuffer(),
         TOKEN_PROGRAM_ID.toBuffer(),
         mint.toBuffer(),
       ],
@@ -155,6 +159,16 @@ export const changeColorInstruction = async (
       ],
       SPACE_PROGRAM_ID
     );
+  const [neighborhoodColorConfig,] =
+    await PublicKey.findProgramAddress(
+      [
+        base.toBuffer(),
+        Buffer.from(NEIGHBORHOOD_COLOR_CONFIG_SEED),
+        Buffer.from(n_x_bytes),
+        Buffer.from(n_y_bytes),
+      ],
+      COLOR_PROGRAM_ID
+    );
 
   var colorCluster;
   if (!colorCluster_input) {
@@ -174,6 +188,30 @@ export const changeColorInstruction = async (
     timeCluster = timeCluster_input;
   }
 
+  // the current lessee edits like the owner, passing the rent account, other non-owners
+  // pay the changer fee to the owner, neighborhood creator and treasury
+  let rentAccount: PublicKey | null = null;
+  if (!owner.equals(wallet.publicKey)) {
+    const [rentAccountKey,] = await PublicKey.findProgramAddress(
+      [
+        base.toBuffer(),
+        Buffer.from(RENT_ACCOUNT_SEED),
+        Buffer.from(space_x_bytes),
+        Buffer.from(space_y_bytes),
+      ],
+      RENT_PROGRAM_ID
+    );
+    const rentAccountData = await connection.getAccountInfo(rentAccountKey);
+    if (rentAccountData) {
+      const rentEnd = new BN(rentAccountData.data.slice(65, 73), 'le');
+      const lessee = new PublicKey(rentAccountData.data.slice(73, 105));
+      if (lessee.equals(wallet.publicKey) && rentEnd.gtn(Date.now() / 1000)) {
+        rentAccount = rentAccountKey;
+      }
+    }
+  }
+  const payingFee = !owner.equals(wallet.publicKey) && !rentAccount;
+
   const keys = [
     {
       pubkey: base,
@@ -208,7 +246,7 @@ export const changeColorInstruction = async (
     {
       pubkey: owner,
       isSigner: false,
-      isWritable: false,
+      isWritable: payingFee,
     },
     {
       pubkey: spaceATA,
@@ -230,7 +268,36 @@ export const changeColorInstruction = async (
       isSigner: false,
       isWritable: false,
     },
+    {
+      pubkey: neighborhoodColorConfig,
+      isSigner: false,
+      isWritable: false,
+    },
   ];
+  if (rentAccount) {
+    keys.push(
+      {
+        pubkey: rentAccount,
+        isSigner: false,
+        isWritable: false,
+      },
+    );
+  }
+  else if (payingFee) {
+    const neighborhoodMetadataData = await connection.getAccountInfo(neighborhoodMetadata);
+    keys.push(
+      {
+        pubkey: new PublicKey(neighborhoodMetadataData.data.slice(1, 33)),
+        isSigner: false,
+        isWritable: true,
+      },
+      {
+        pubkey: await getTreasury(connection, base),
+        isSigner: false,
+        isWritable: true,
+      },
+    );
+  }
   let args = new ChangeColorInstructionData({
     x: 0, // hardcode 0 for u16 case
     y: 0, // hardcode 0 for u16 case
@@ -1,8 +1,8 @@
-import {PublicKey, TransactionInstruction,} from "@solana/web3.js";
+import {PublicKey, SystemProgram, TransactionInstruction,} from "@solana/web3.js";
 import BN from "bn.js";
 import {Schema, serialize} from "borsh";
 import {ASSOCIATED_TOKEN_PROGRAM_ID, Token, TOKEN_PROGRAM_ID,} from "@solana/spl-token";
-import {SPACE_METADATA_SEED, SPACE_PROGRAM_ID, SELL_DELEGATE_SEED,} from "../constants";
+import {AUCTION_SEED, LISTING_SEED, SPACE_METADATA_SEED, SPACE_PROGRAM_ID, SELL_DELEGATE_SEED, RENT_ACCOUNT_SEED, RENT_PROGRAM_ID,} from "../constants";
 import {correct_negative_serialization, twoscomplement_i2u} from "../utils/borsh";
 
 export const CHANGE_OFFER_INSTRUCTION_ID = 3;
@@ -12,6 +12,8 @@ export class ChangeOfferInstructionData {
   y: number;
   price: BN;
   create: boolean;
+  payment_mint: Buffer;
+  expires_at: BN;
 
   static schema: Schema = new Map([
     [
@@ -24,6 +26,8 @@ export class ChangeOfferInstructionData {
           ["y", "u64"],
           ["price", "u64"],
           ["create", "u8"],
+          ["payment_mint", [32]],
+          ["expires_at", "u64"],
         ],
       },
     ],
@@ -34,11 +38,17 @@ export class ChangeOfferInstructionData {
     y: number;
     price: number;
     create: boolean;
+    payment_mint?: PublicKey;
+    expires_at?: number;
   }) {
     this.x = args.x;
     this.y = args.y;
     this.price = new BN(Math.floor(args.price));
     this.create = args.create;
+    // all zeros lists the space in lamports
+    this.payment_mint = args.payment_mint ? args.payment_mint.toBuffer() : Buffer.alloc(32);
+    // 0 for a listing that does not expire
+    this.expires_at = new BN(args.expires_at ? Math.floor(args.expires_at) : 0);
   }
 }
 
@@ -48,18 +58,27 @@ export class ChangeOfferArgs{
   mint: PublicKey;
   price: number;
   create: boolean;
+  payment_mint?: PublicKey;
+  expires_at?: number;
+  listing_seller?: PublicKey;
   constructor(args: {
     x: number;
     y: number;
     mint: PublicKey;
     price: number;
     create: boolean;
+    payment_mint?: PublicKey;
+    expires_at?: number;
+    listing_seller?: PublicKey;
   }) {
     this.x = args.x;
     this.y = args.y;
     this.mint = args.mint;
     this.price = args.price;
     this.create = args.create;
+    this.payment_mint = args.payment_mint;
+    this.expires_at = args.expires_at;
+    this.listing_seller = args.listing_seller;
   }
 }
 
@@ -68,7 +87,7 @@ export const changeOfferInstruction = async (
   base: PublicKey,
   change: ChangeOfferArgs,
 ) => {
-  const {x, y, mint, price, create} = change;
+  const {x, y, mint, price, create, payment_mint, expires_at, listing_seller} = change;
 
   const space_x = twoscomplement_i2u(x);
   const space_y = twoscomplement_i2u(y);
@@ -89,6 +108,41 @@ export const changeOfferInstruction = async (
       SPACE_PROGRAM_ID
     );
 
+  const [listing_account,] =
+    await PublicKey.findProgramAddress(
+      [
+        base.toBuffer(),
+        Buffer.from(LISTING_SEED),
+        Buffer.from(space_x),
+        Buffer.from(space_y),
+      ],
+      SPACE_PROGRAM_ID
+    );
+
+  // the rent program's account for the space, read to refuse listing while the space is rented out
+  const [rent_account,] =
+    await PublicKey.findProgramAddress(
+      [
+        base.toBuffer(),
+        Buffer.from(RENT_ACCOUNT_SEED),
+        Buffer.from(space_x),
+        Buffer.from(space_y),
+      ],
+      RENT_PROGRAM_ID
+    );
+
+  // read to refuse listing while an auction of the space has not been settled
+  const [auction_account,] =
+    await PublicKey.findProgramAddress(
+      [
+        base.toBuffer(),
+        Buffer.from(AUCTION_SEED),
+        Buffer.from(space_x),
+        Buffer.from(space_y),
+      ],
+      SPACE_PROGRAM_ID
+    );
+
   const spaceATA = await Token.getAssociatedTokenAddress(
     ASSOCIATED_TOKEN_PROGRAM_ID,
     TOKEN_PROGRAM_ID,
@@ -102,6 +156,8 @@ export const changeOfferInstruction = async (
     y,
     price,
     create,
+    payment_mint,
+    expires_at,
   });
 
   const keys = [
@@ -118,7 +174,7 @@ export const changeOfferInstruction = async (
     {
       pubkey: wallet.publicKey,
       isSigner: true,
-      isWritable: false,
+      isWritable: true,
     },
     {
       pubkey: spaceATA,
@@ -135,6 +191,32 @@ export const changeOfferInstruction = async (
       isSigner: false,
       isWritable: false,
     },
+    {
+      pubkey: listing_account,
+      isSigner: false,
+      isWritable: true,
+    },
+    {
+      pubkey: SystemProgram.programId,
+      isSigner: false,
+      isWritable: false,
+    },
+    {
+      pubkey: rent_account,
+      isSigner: false,
+      isWritable: false,
+    },
+    {
+      pubkey: auction_account,
+      isSigner: false,
+      isWritable: false,
+    },
+    // the seller recorded in the listing account gets its rent back, the wallet unless the space changed hands
+    {
+      pubkey: listing_seller ? listing_seller : wallet.publicKey,
+      isSigner: false,
+      isWritable: true,
+    },
   ];
 
   let data = Buffer.from(serialize(ChangeOfferInstructionData.schema, args));
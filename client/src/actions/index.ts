@@ -7,4 +7,5 @@ export * from "./init_space_metadata";
 export * from "./send_transaction";
 export * from "./accept_offer";
 export * from "./set_rent";
-export * from "./accept_rent";
\ No newline at end of file
+export * from "./accept_rent";
+export * from "./update_treasury";
\ No newline at end of file
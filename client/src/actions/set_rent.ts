@@ -14,6 +14,7 @@ export class SetRentInstructionData {
   max_duration: BN;
   max_timestamp: BN;
   create: boolean;
+  first_refusal_period: BN;
 
   static schema: Schema = new Map([
     [
@@ -29,6 +30,7 @@ export class SetRentInstructionData {
           ["max_duration", "u64"],
           ["max_timestamp", "u64"],
           ["create", "u8"],
+          ["first_refusal_period", "u64"],
         ],
       },
     ],
@@ -42,6 +44,7 @@ export class SetRentInstructionData {
     max_duration: number;
     max_timestamp: number;
     create: boolean;
+    first_refusal_period?: number;
   }) {
     this.x = args.x;
     this.y = args.y;
@@ -50,6 +53,8 @@ export class SetRentInstructionData {
     this.max_duration = new BN(Math.floor(args.max_duration));
     this.max_timestamp = new BN(Math.floor(args.max_timestamp));
     this.create = args.create;
+    // 0 for no right of first refusal once a rental ends
+    this.first_refusal_period = new BN(args.first_refusal_period ? Math.floor(args.first_refusal_period) : 0);
   }
 }
 
@@ -62,6 +67,7 @@ export class SetRentArgs{
   max_duration: number;
   max_timestamp: number;
   create: boolean;
+  first_refusal_period?: number;
   constructor(args: {
     x: number;
     y: number;
@@ -71,6 +77,7 @@ export class SetRentArgs{
     max_duration: number;
     max_timestamp: number;
     create: boolean;
+    first_refusal_period?: number;
   }) {
     this.x = args.x;
     this.y = args.y;
@@ -80,6 +87,7 @@ export class SetRentArgs{
     this.max_duration = args.max_duration;
     this.max_timestamp = args.max_timestamp;
     this.create = args.create;
+    this.first_refusal_period = args.first_refusal_period;
   }
 }
 
@@ -88,7 +96,7 @@ export const setRentInstruction = async (
   base: PublicKey,
   change: SetRentArgs,
 ) => {
-  const {x, y, mint, price, min_duration, max_duration, max_timestamp, create} = change;
+  const {x, y, mint, price, min_duration, max_duration, max_timestamp, create, first_refusal_period} = change;
 
   const space_x = twoscomplement_i2u(x);
   const space_y = twoscomplement_i2u(y);
@@ -131,6 +139,7 @@ export const setRentInstruction = async (
     max_duration,
     max_timestamp,
     create,
+    first_refusal_period,
   });
 
   const keys = [
@@ -0,0 +1,78 @@
+import {PublicKey, TransactionInstruction,} from "@solana/web3.js";
+import {Schema, serialize} from "borsh";
+import {SPACE_PROGRAM_ID} from "../constants";
+
+// base layout: neighborhood_count u64, authority, authority_privileges bool, max_marketplace_fee_bps u16, treasury
+export const getTreasury = async (
+    connection,
+    base: PublicKey,
+  ) => {
+    const baseData = await connection.getAccountInfo(base);
+    const treasury = new PublicKey(baseData.data.slice(43, 75));
+    // bases initialized before the treasury was stored pay the authority
+    if (treasury.equals(PublicKey.default)) {
+      return new PublicKey(baseData.data.slice(8, 40));
+    }
+    return treasury;
+  };
+
+export class UpdateTreasuryArgs {
+    instruction: number = 16;
+    treasury_fee_share_bps: number;
+
+    static schema: Schema = new Map([
+      [
+        UpdateTreasuryArgs,
+        {
+          kind: "struct",
+          fields: [
+            ["instruction", "u8"],
+            ["treasury_fee_share_bps", "u16"],
+          ],
+        },
+      ],
+    ]);
+
+    constructor(args: {
+      treasury_fee_share_bps: number;
+    }) {
+      this.treasury_fee_share_bps = args.treasury_fee_share_bps;
+    }
+  }
+
+  export const updateTreasuryInstruction = async (
+    wallet : any,
+    base: PublicKey,
+    authority: PublicKey,
+    treasury: PublicKey,
+    treasury_fee_share_bps: number,
+  ) => {
+
+    const keys = [
+      {
+        pubkey: base,
+        isSigner: false,
+        isWritable: true,
+      },
+      {
+        pubkey: authority,
+        isSigner: true,
+        isWritable: false,
+      },
+      {
+        pubkey: treasury,
+        isSigner: false,
+        isWritable: false,
+      },
+    ];
+    const args = new UpdateTreasuryArgs({treasury_fee_share_bps});
+    const data = Buffer.from(serialize(UpdateTreasuryArgs.schema, args));
+    let Ix =
+      [new TransactionInstruction({
+        keys,
+        programId: SPACE_PROGRAM_ID,
+        data,
+      })];
+
+    return Ix;
+  };
@@ -16,12 +16,16 @@ export const RPC = process.env.REACT_APP_RPC;
 export const NEIGHBORHOOD_LIST_SEED = "neighborhood_list";
 export const NEIGHBORHOOD_FRAME_BASE_SEED = "neighborhood_frame_base";
 export const NEIGHBORHOOD_FRAME_POINTER_SEED = "neighborhood_frame_pointer";
+export const NEIGHBORHOOD_COLOR_CONFIG_SEED = "neighborhood_color_config";
 export const NEIGHBORHOOD_METADATA_SEED = "neighborhood_metadata";
 export const SELL_DELEGATE_SEED = "sell_delegate"
+export const LISTING_SEED = "listing";
+export const AUCTION_SEED = "auction";
 export const SPACE_METADATA_SEED = "space_metadata";
 export const VOUCHER_MINT_SEED = "voucher_mint";
 export const VOUCHER_SINK_SEED = "voucher_sink";
 export const RENT_ACCOUNT_SEED = "rent_account";
+export const RENT_ESCROW_SEED = "rent_escrow";
 
 export const BATCH_TX_SIZE = 40;
 export const BATCH_LOAD_PRICE_SIZE = 30;
@@ -1,5 +1,25 @@
 use borsh::{BorshDeserialize, BorshSerialize};
-use solana_program::program_error::ProgramError;
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_program,
+};
+use spl_associated_token_account::get_associated_token_address;
+use std::str::FromStr;
+use extend_common::utils::get_neighborhood_xy;
+
+use crate::{
+    pda::{
+        find_color_config_address,
+        find_frame_base_address,
+        find_frame_pointer_address,
+        find_neighborhood_metadata_address,
+        find_rent_account_address,
+        find_space_metadata_address,
+    },
+    state::{RENT_PID, SPACE_PID},
+};
 
 #[repr(C)]
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
@@ -35,6 +55,7 @@ pub struct ChangeColorBriefArgs {
 pub struct MakeEditableArgs {
     pub space_x: i64,
     pub space_y: i64,
+    pub editable: bool,
 }
 
 #[repr(C)]
@@ -42,20 +63,63 @@ pub struct MakeEditableArgs {
 pub struct MakeEditableBriefArgs {
     pub space_x: i16,
     pub space_y: i16,
+    pub editable: bool,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct SpaceColor {
+    pub space_x: i64,
+    pub space_y: i64,
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct ChangeColorBatchArgs {
+    pub neighborhood_x: i64,
+    pub neighborhood_y: i64,
+    pub frame: u64,
+    pub colors: Vec<SpaceColor>,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct ChangeColorRegionArgs {
+    pub space_x: i64,
+    pub space_y: i64,
+    pub width: u16,
+    pub height: u16,
+    pub frame: u64,
+    pub payload: Vec<u8>,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct SetColorConfigArgs {
+    pub neighborhood_x: i64,
+    pub neighborhood_y: i64,
+    pub inactivity_threshold_owner: u64,
+    pub inactivity_threshold_arbitrary: u64,
+    pub arbitrary_changer_fee: u64,
 }
 
 pub enum ColorInstruction {
 
 
     /*
-    Init metadata account (PDA) for a given mint (PDA corresponding to (x,y))
+    Init frame i for a neighborhood, creating the frame base and time cluster on the first frame
     Accounts expected:
     0. Base account
-    1. account of the color frame cluster
-    2. [Signer] fee payer
-    3. The system program
-    
-    time cluster account
+    1. [Writable] frame account
+    2. [Writable] neighborhood frame base
+    3. [Writable] neighborhood frame pointer
+    4. neighborhood metadata
+    5. [Signer, Writable] fee payer
+    6. system program
+    7. [Writable] time cluster account
     */
     InitFrame,
 
@@ -63,30 +127,81 @@ pub enum ColorInstruction {
     Change color at stage i
     Accounts expected:
     0. Base account
-    1. [Writable] Color cluster account
-    2. space metadata
-    2. neighborhood metadata
-    3. [Writable] Owner
-    4. Ata of owner
-    time cluster account
-    [Writable, signer] fee payer
-    system program
-
+    1. [Writable] frame account
+    2. neighborhood frame base
+    3. neighborhood frame pointer
+    4. neighborhood metadata
+    5. space metadata
+    6. [Writable] owner
+    7. ATA of owner
+    8. [Writable] time cluster account
+    9. [Signer, Writable] fee payer
+    10. system program
+    11. neighborhood color config
+    Only if fee payer is the space's current lessee:
+    12. rent account
+    Only if fee payer is any other non-owner:
+    12. [Writable] neighborhood creator
+    13. [Writable] treasury
     */
     ChangeColor,
     ChangeColorBrief,
 
     /*
-    Makes owned space color-editable
+    Makes owned space color-editable by anyone, or locks it again
     Accounts expected:
     0. Base account
     1. space metadata
     2. [Signer] owner
     3. space_ata
     4. [Writable] time cluster account
+    5. neighborhood frame base
+    */
+    MakeEditable,
+    MakeEditableBrief,
+
+    /*
+    Change colors of several owned spaces in one neighborhood at stage i
+    Accounts expected:
+    0. Base account
+    1. [Writable] frame account
+    2. neighborhood frame base
+    3. neighborhood frame pointer
+    4. [Signer] owner
+    5. [Writable] time cluster account
+    6. neighborhood color config
+    7+2k. space metadata of colors[k]
+    8+2k. ATA of owner for colors[k]
+    */
+    ChangeColorBatch,
+
+    /*
+    Change colors of a width x height rectangle of owned spaces with origin (space_x, space_y),
+    all within one neighborhood, at stage i. The payload is a list of (count, r, g, b) runs
+    covering the rectangle x-major: (x, y), (x, y + 1), ..., (x + 1, y), ...
+    Accounts expected:
+    0. Base account
+    1. [Writable] frame account
+    2. neighborhood frame base
+    3. neighborhood frame pointer
+    4. [Signer] owner
+    5. [Writable] time cluster account
+    6. neighborhood color config
+    7+2k. space metadata of the k-th space of the rectangle, x-major
+    8+2k. ATA of owner for the k-th space of the rectangle, x-major
+    */
+    ChangeColorRegion,
+
+    /*
+    Create or update the inactivity thresholds and changer fee of a neighborhood, signed by its creator
+    Accounts expected:
+    0. Base account
+    1. neighborhood metadata
+    2. [Writable] neighborhood color config
+    3. [Signer, Writable] neighborhood creator
+    4. system program
     */
-    // MakeEditable,
-    // MakeEditableBrief,
+    SetColorConfig,
 }
 
 impl ColorInstruction {
@@ -95,9 +210,516 @@ impl ColorInstruction {
             0 => Self::InitFrame,
             1 => Self::ChangeColor,
             2 => Self::ChangeColorBrief,
-            // 3 => Self::MakeEditable,
-            // 4 => Self::MakeEditableBrief,
+            3 => Self::MakeEditable,
+            4 => Self::MakeEditableBrief,
+            5 => Self::ChangeColorBatch,
+            6 => Self::ChangeColorRegion,
+            7 => Self::SetColorConfig,
             _ => return Err(ProgramError::InvalidInstructionData),
         })
     }
+
+    pub fn pack(&self) -> u8 {
+        match self {
+            Self::InitFrame => 0,
+            Self::ChangeColor => 1,
+            Self::ChangeColorBrief => 2,
+            Self::MakeEditable => 3,
+            Self::MakeEditableBrief => 4,
+            Self::ChangeColorBatch => 5,
+            Self::ChangeColorRegion => 6,
+            Self::SetColorConfig => 7,
+        }
+    }
+}
+
+fn build_instruction<T: BorshSerialize>(
+    program_id: &Pubkey,
+    instruction: ColorInstruction,
+    args: &T,
+    accounts: Vec<AccountMeta>,
+) -> Instruction {
+    let mut data = vec![instruction.pack()];
+    data.extend(args.try_to_vec().unwrap());
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn init_frame(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    frame: &Pubkey,
+    time_cluster: &Pubkey,
+    fee_payer: &Pubkey,
+    neighborhood_x: i64,
+    neighborhood_y: i64,
+    frame_index: u64,
+) -> Instruction {
+    let space_program_id = Pubkey::from_str(SPACE_PID).unwrap();
+    let (neighborhood_frame_base, _) =
+        find_frame_base_address(program_id, base, neighborhood_x, neighborhood_y);
+    let (neighborhood_frame_pointer, _) =
+        find_frame_pointer_address(program_id, base, neighborhood_x, neighborhood_y, frame_index);
+    let (neighborhood_metadata, _) =
+        find_neighborhood_metadata_address(&space_program_id, base, neighborhood_x, neighborhood_y);
+    build_instruction(
+        program_id,
+        ColorInstruction::InitFrame,
+        &InitFrameArgs {
+            neighborhood_x,
+            neighborhood_y,
+        },
+        vec![
+            AccountMeta::new_readonly(*base, false),
+            AccountMeta::new(*frame, false),
+            AccountMeta::new(neighborhood_frame_base, false),
+            AccountMeta::new(neighborhood_frame_pointer, false),
+            AccountMeta::new_readonly(neighborhood_metadata, false),
+            AccountMeta::new(*fee_payer, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new(*time_cluster, false),
+        ],
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn change_color_accounts(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    frame: &Pubkey,
+    time_cluster: &Pubkey,
+    space_mint: &Pubkey,
+    owner: &Pubkey,
+    fee_payer: &Pubkey,
+    space_x: i64,
+    space_y: i64,
+    frame_index: u64,
+) -> Vec<AccountMeta> {
+    let space_program_id = Pubkey::from_str(SPACE_PID).unwrap();
+    let (neighborhood_x, neighborhood_y) = get_neighborhood_xy(space_x, space_y);
+    let (neighborhood_frame_base, _) =
+        find_frame_base_address(program_id, base, neighborhood_x, neighborhood_y);
+    let (neighborhood_frame_pointer, _) =
+        find_frame_pointer_address(program_id, base, neighborhood_x, neighborhood_y, frame_index);
+    let (neighborhood_metadata, _) =
+        find_neighborhood_metadata_address(&space_program_id, base, neighborhood_x, neighborhood_y);
+    let (space_metadata, _) =
+        find_space_metadata_address(&space_program_id, base, space_x, space_y);
+    let (neighborhood_color_config, _) =
+        find_color_config_address(program_id, base, neighborhood_x, neighborhood_y);
+    vec![
+        AccountMeta::new_readonly(*base, false),
+        AccountMeta::new(*frame, false),
+        AccountMeta::new_readonly(neighborhood_frame_base, false),
+        AccountMeta::new_readonly(neighborhood_frame_pointer, false),
+        AccountMeta::new_readonly(neighborhood_metadata, false),
+        AccountMeta::new_readonly(space_metadata, false),
+        AccountMeta::new(*owner, false),
+        AccountMeta::new_readonly(get_associated_token_address(owner, space_mint), false),
+        AccountMeta::new(*time_cluster, false),
+        AccountMeta::new(*fee_payer, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(neighborhood_color_config, false),
+    ]
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn change_color(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    frame: &Pubkey,
+    time_cluster: &Pubkey,
+    space_mint: &Pubkey,
+    owner: &Pubkey,
+    fee_payer: &Pubkey,
+    space_x: i64,
+    space_y: i64,
+    frame_index: u64,
+    r: u8,
+    g: u8,
+    b: u8,
+) -> Instruction {
+    build_instruction(
+        program_id,
+        ColorInstruction::ChangeColor,
+        &ChangeColorArgs {
+            space_x,
+            space_y,
+            frame: frame_index,
+            r,
+            g,
+            b,
+        },
+        change_color_accounts(
+            program_id,
+            base,
+            frame,
+            time_cluster,
+            space_mint,
+            owner,
+            fee_payer,
+            space_x,
+            space_y,
+            frame_index,
+        ),
+    )
+}
+
+// change the color of a space the fee payer does not own, paying the changer fee
+#[allow(clippy::too_many_arguments)]
+pub fn change_color_paying_fee(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    frame: &Pubkey,
+    time_cluster: &Pubkey,
+    space_mint: &Pubkey,
+    owner: &Pubkey,
+    fee_payer: &Pubkey,
+    neighborhood_creator: &Pubkey,
+    treasury: &Pubkey,
+    space_x: i64,
+    space_y: i64,
+    frame_index: u64,
+    r: u8,
+    g: u8,
+    b: u8,
+) -> Instruction {
+    let mut instruction = change_color(
+        program_id,
+        base,
+        frame,
+        time_cluster,
+        space_mint,
+        owner,
+        fee_payer,
+        space_x,
+        space_y,
+        frame_index,
+        r,
+        g,
+        b,
+    );
+    instruction.accounts.extend(vec![
+        AccountMeta::new(*neighborhood_creator, false),
+        AccountMeta::new(*treasury, false),
+    ]);
+    instruction
+}
+
+// change the color of a space rented by the fee payer
+#[allow(clippy::too_many_arguments)]
+pub fn change_color_as_lessee(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    frame: &Pubkey,
+    time_cluster: &Pubkey,
+    space_mint: &Pubkey,
+    owner: &Pubkey,
+    lessee: &Pubkey,
+    space_x: i64,
+    space_y: i64,
+    frame_index: u64,
+    r: u8,
+    g: u8,
+    b: u8,
+) -> Instruction {
+    let mut instruction = change_color(
+        program_id,
+        base,
+        frame,
+        time_cluster,
+        space_mint,
+        owner,
+        lessee,
+        space_x,
+        space_y,
+        frame_index,
+        r,
+        g,
+        b,
+    );
+    let (rent_account, _) =
+        find_rent_account_address(&Pubkey::from_str(RENT_PID).unwrap(), base, space_x, space_y);
+    instruction.accounts.push(AccountMeta::new_readonly(rent_account, false));
+    instruction
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn change_color_brief(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    frame: &Pubkey,
+    time_cluster: &Pubkey,
+    space_mint: &Pubkey,
+    owner: &Pubkey,
+    fee_payer: &Pubkey,
+    space_x: i16,
+    space_y: i16,
+    frame_index: u8,
+    r: u8,
+    g: u8,
+    b: u8,
+) -> Instruction {
+    build_instruction(
+        program_id,
+        ColorInstruction::ChangeColorBrief,
+        &ChangeColorBriefArgs {
+            space_x,
+            space_y,
+            frame: frame_index,
+            r,
+            g,
+            b,
+        },
+        change_color_accounts(
+            program_id,
+            base,
+            frame,
+            time_cluster,
+            space_mint,
+            owner,
+            fee_payer,
+            space_x as i64,
+            space_y as i64,
+            frame_index as u64,
+        ),
+    )
+}
+
+fn make_editable_accounts(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    time_cluster: &Pubkey,
+    space_mint: &Pubkey,
+    owner: &Pubkey,
+    space_x: i64,
+    space_y: i64,
+) -> Vec<AccountMeta> {
+    let space_program_id = Pubkey::from_str(SPACE_PID).unwrap();
+    let (neighborhood_x, neighborhood_y) = get_neighborhood_xy(space_x, space_y);
+    let (neighborhood_frame_base, _) =
+        find_frame_base_address(program_id, base, neighborhood_x, neighborhood_y);
+    let (space_metadata, _) =
+        find_space_metadata_address(&space_program_id, base, space_x, space_y);
+    vec![
+        AccountMeta::new_readonly(*base, false),
+        AccountMeta::new_readonly(space_metadata, false),
+        AccountMeta::new_readonly(*owner, true),
+        AccountMeta::new_readonly(get_associated_token_address(owner, space_mint), false),
+        AccountMeta::new(*time_cluster, false),
+        AccountMeta::new_readonly(neighborhood_frame_base, false),
+    ]
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn make_editable(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    time_cluster: &Pubkey,
+    space_mint: &Pubkey,
+    owner: &Pubkey,
+    space_x: i64,
+    space_y: i64,
+    editable: bool,
+) -> Instruction {
+    build_instruction(
+        program_id,
+        ColorInstruction::MakeEditable,
+        &MakeEditableArgs {
+            space_x,
+            space_y,
+            editable,
+        },
+        make_editable_accounts(program_id, base, time_cluster, space_mint, owner, space_x, space_y),
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn make_editable_brief(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    time_cluster: &Pubkey,
+    space_mint: &Pubkey,
+    owner: &Pubkey,
+    space_x: i16,
+    space_y: i16,
+    editable: bool,
+) -> Instruction {
+    build_instruction(
+        program_id,
+        ColorInstruction::MakeEditableBrief,
+        &MakeEditableBriefArgs {
+            space_x,
+            space_y,
+            editable,
+        },
+        make_editable_accounts(
+            program_id,
+            base,
+            time_cluster,
+            space_mint,
+            owner,
+            space_x as i64,
+            space_y as i64,
+        ),
+    )
+}
+
+// space_mints[k] is the mint of the space painted by colors[k]
+#[allow(clippy::too_many_arguments)]
+pub fn change_color_batch(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    frame: &Pubkey,
+    time_cluster: &Pubkey,
+    owner: &Pubkey,
+    neighborhood_x: i64,
+    neighborhood_y: i64,
+    frame_index: u64,
+    space_mints: &[Pubkey],
+    colors: Vec<SpaceColor>,
+) -> Instruction {
+    let space_program_id = Pubkey::from_str(SPACE_PID).unwrap();
+    let (neighborhood_frame_base, _) =
+        find_frame_base_address(program_id, base, neighborhood_x, neighborhood_y);
+    let (neighborhood_frame_pointer, _) =
+        find_frame_pointer_address(program_id, base, neighborhood_x, neighborhood_y, frame_index);
+    let (neighborhood_color_config, _) =
+        find_color_config_address(program_id, base, neighborhood_x, neighborhood_y);
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*base, false),
+        AccountMeta::new(*frame, false),
+        AccountMeta::new_readonly(neighborhood_frame_base, false),
+        AccountMeta::new_readonly(neighborhood_frame_pointer, false),
+        AccountMeta::new_readonly(*owner, true),
+        AccountMeta::new(*time_cluster, false),
+        AccountMeta::new_readonly(neighborhood_color_config, false),
+    ];
+    for (space_mint, color) in space_mints.iter().zip(colors.iter()) {
+        let (space_metadata, _) =
+            find_space_metadata_address(&space_program_id, base, color.space_x, color.space_y);
+        accounts.push(AccountMeta::new_readonly(space_metadata, false));
+        accounts.push(AccountMeta::new_readonly(get_associated_token_address(owner, space_mint), false));
+    }
+    build_instruction(
+        program_id,
+        ColorInstruction::ChangeColorBatch,
+        &ChangeColorBatchArgs {
+            neighborhood_x,
+            neighborhood_y,
+            frame: frame_index,
+            colors,
+        },
+        accounts,
+    )
+}
+
+// compress colors into the (count, r, g, b) runs expected by ChangeColorRegion
+pub fn encode_run_length(colors: &[[u8; 3]]) -> Vec<u8> {
+    let mut payload: Vec<u8> = Vec::new();
+    for color in colors {
+        let len = payload.len();
+        if len >= 4 && payload[len - 4] < u8::MAX && payload[len - 3..] == color[..] {
+            payload[len - 4] += 1;
+        } else {
+            payload.push(1);
+            payload.extend_from_slice(color);
+        }
+    }
+    payload
+}
+
+// space_mints lists the mints of the rectangle's spaces x-major, colors likewise
+#[allow(clippy::too_many_arguments)]
+pub fn change_color_region(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    frame: &Pubkey,
+    time_cluster: &Pubkey,
+    owner: &Pubkey,
+    space_x: i64,
+    space_y: i64,
+    width: u16,
+    height: u16,
+    frame_index: u64,
+    space_mints: &[Pubkey],
+    colors: &[[u8; 3]],
+) -> Instruction {
+    let space_program_id = Pubkey::from_str(SPACE_PID).unwrap();
+    let (neighborhood_x, neighborhood_y) = get_neighborhood_xy(space_x, space_y);
+    let (neighborhood_frame_base, _) =
+        find_frame_base_address(program_id, base, neighborhood_x, neighborhood_y);
+    let (neighborhood_frame_pointer, _) =
+        find_frame_pointer_address(program_id, base, neighborhood_x, neighborhood_y, frame_index);
+    let (neighborhood_color_config, _) =
+        find_color_config_address(program_id, base, neighborhood_x, neighborhood_y);
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*base, false),
+        AccountMeta::new(*frame, false),
+        AccountMeta::new_readonly(neighborhood_frame_base, false),
+        AccountMeta::new_readonly(neighborhood_frame_pointer, false),
+        AccountMeta::new_readonly(*owner, true),
+        AccountMeta::new(*time_cluster, false),
+        AccountMeta::new_readonly(neighborhood_color_config, false),
+    ];
+    let spaces = (space_x..space_x + width as i64)
+        .flat_map(|x| (space_y..space_y + height as i64).map(move |y| (x, y)));
+    for ((x, y), space_mint) in spaces.zip(space_mints.iter()) {
+        let (space_metadata, _) = find_space_metadata_address(&space_program_id, base, x, y);
+        accounts.push(AccountMeta::new_readonly(space_metadata, false));
+        accounts.push(AccountMeta::new_readonly(get_associated_token_address(owner, space_mint), false));
+    }
+    build_instruction(
+        program_id,
+        ColorInstruction::ChangeColorRegion,
+        &ChangeColorRegionArgs {
+            space_x,
+            space_y,
+            width,
+            height,
+            frame: frame_index,
+            payload: encode_run_length(colors),
+        },
+        accounts,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn set_color_config(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    creator: &Pubkey,
+    neighborhood_x: i64,
+    neighborhood_y: i64,
+    inactivity_threshold_owner: u64,
+    inactivity_threshold_arbitrary: u64,
+    arbitrary_changer_fee: u64,
+) -> Instruction {
+    let space_program_id = Pubkey::from_str(SPACE_PID).unwrap();
+    let (neighborhood_metadata, _) =
+        find_neighborhood_metadata_address(&space_program_id, base, neighborhood_x, neighborhood_y);
+    let (neighborhood_color_config, _) =
+        find_color_config_address(program_id, base, neighborhood_x, neighborhood_y);
+    build_instruction(
+        program_id,
+        ColorInstruction::SetColorConfig,
+        &SetColorConfigArgs {
+            neighborhood_x,
+            neighborhood_y,
+            inactivity_threshold_owner,
+            inactivity_threshold_arbitrary,
+            arbitrary_changer_fee,
+        },
+        vec![
+            AccountMeta::new_readonly(*base, false),
+            AccountMeta::new_readonly(neighborhood_metadata, false),
+            AccountMeta::new(neighborhood_color_config, false),
+            AccountMeta::new(*creator, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
 }
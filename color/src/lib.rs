@@ -1,6 +1,7 @@
+pub use extend_common::{error, validation_utils};
+
 pub mod entrypoint;
-pub mod error;
 pub mod instruction;
+pub mod pda;
 pub mod processor;
 pub mod state;
-pub mod validation_utils;
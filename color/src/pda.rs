@@ -0,0 +1,121 @@
+use solana_program::pubkey::{Pubkey, PubkeyError};
+
+use crate::state::{
+    NEIGHBORHOOD_COLOR_CONFIG_SEED,
+    NEIGHBORHOOD_FRAME_BASE_SEED,
+    NEIGHBORHOOD_FRAME_POINTER_SEED,
+};
+
+pub use extend_common::pda::*;
+
+pub fn find_frame_base_address(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    neighborhood_x: i64,
+    neighborhood_y: i64,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            &base.to_bytes(),
+            NEIGHBORHOOD_FRAME_BASE_SEED,
+            &neighborhood_x.to_le_bytes(),
+            &neighborhood_y.to_le_bytes(),
+        ],
+        program_id,
+    )
+}
+
+pub fn create_frame_base_address(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    neighborhood_x: i64,
+    neighborhood_y: i64,
+    bump: u8,
+) -> Result<Pubkey, PubkeyError> {
+    Pubkey::create_program_address(
+        &[
+            &base.to_bytes(),
+            NEIGHBORHOOD_FRAME_BASE_SEED,
+            &neighborhood_x.to_le_bytes(),
+            &neighborhood_y.to_le_bytes(),
+            &[bump],
+        ],
+        program_id,
+    )
+}
+
+pub fn find_frame_pointer_address(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    neighborhood_x: i64,
+    neighborhood_y: i64,
+    frame: u64,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            &base.to_bytes(),
+            NEIGHBORHOOD_FRAME_POINTER_SEED,
+            &neighborhood_x.to_le_bytes(),
+            &neighborhood_y.to_le_bytes(),
+            &frame.to_le_bytes(),
+        ],
+        program_id,
+    )
+}
+
+pub fn create_frame_pointer_address(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    neighborhood_x: i64,
+    neighborhood_y: i64,
+    frame: u64,
+    bump: u8,
+) -> Result<Pubkey, PubkeyError> {
+    Pubkey::create_program_address(
+        &[
+            &base.to_bytes(),
+            NEIGHBORHOOD_FRAME_POINTER_SEED,
+            &neighborhood_x.to_le_bytes(),
+            &neighborhood_y.to_le_bytes(),
+            &frame.to_le_bytes(),
+            &[bump],
+        ],
+        program_id,
+    )
+}
+
+pub fn find_color_config_address(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    neighborhood_x: i64,
+    neighborhood_y: i64,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            &base.to_bytes(),
+            NEIGHBORHOOD_COLOR_CONFIG_SEED,
+            &neighborhood_x.to_le_bytes(),
+            &neighborhood_y.to_le_bytes(),
+        ],
+        program_id,
+    )
+}
+
+pub fn create_color_config_address(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    neighborhood_x: i64,
+    neighborhood_y: i64,
+    bump: u8,
+) -> Result<Pubkey, PubkeyError> {
+    Pubkey::create_program_address(
+        &[
+            &base.to_bytes(),
+            NEIGHBORHOOD_COLOR_CONFIG_SEED,
+            &neighborhood_x.to_le_bytes(),
+            &neighborhood_y.to_le_bytes(),
+            &[bump],
+        ],
+        program_id,
+    )
+}
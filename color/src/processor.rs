@@ -6,14 +6,16 @@ use solana_program::{
 
 use crate::{
     instruction::{
-        ColorInstruction, InitFrameArgs, ChangeColorArgs, ChangeColorBriefArgs, MakeEditableArgs, MakeEditableBriefArgs
+        ColorInstruction, InitFrameArgs, ChangeColorArgs, ChangeColorBriefArgs, ChangeColorBatchArgs, ChangeColorRegionArgs, MakeEditableArgs, MakeEditableBriefArgs, SetColorConfigArgs
     },
 };
 
 pub mod init_frame;
 pub mod change_color;
+pub mod change_color_batch;
+pub mod change_color_region;
 pub mod make_editable;
-
+pub mod set_color_config;
 pub mod processor_utils;
 
 pub struct Processor;
@@ -43,16 +45,31 @@ impl Processor {
                 msg!("Instruction: changing color");
                 change_color::process_brief(program_id, accounts, &args)
             }
-            // ColorInstruction::MakeEditable => {
-            //     let args = MakeEditableArgs::try_from_slice(rest)?;
-            //     msg!("Instruction: making editable");
-            //     make_editable::process(program_id, accounts, &args)
-            // }
-            // ColorInstruction::MakeEditableBrief => {
-            //     let args = MakeEditableBriefArgs::try_from_slice(rest)?;
-            //     msg!("Instruction: making editable");
-            //     make_editable::process_brief(program_id, accounts, &args)
-            // }
+            ColorInstruction::MakeEditable => {
+                let args = MakeEditableArgs::try_from_slice(rest)?;
+                msg!("Instruction: making editable");
+                make_editable::process(program_id, accounts, &args)
+            }
+            ColorInstruction::MakeEditableBrief => {
+                let args = MakeEditableBriefArgs::try_from_slice(rest)?;
+                msg!("Instruction: making editable");
+                make_editable::process_brief(program_id, accounts, &args)
+            }
+            ColorInstruction::ChangeColorBatch => {
+                let args = ChangeColorBatchArgs::try_from_slice(rest)?;
+                msg!("Instruction: changing color batch");
+                change_color_batch::process(program_id, accounts, &args)
+            }
+            ColorInstruction::ChangeColorRegion => {
+                let args = ChangeColorRegionArgs::try_from_slice(rest)?;
+                msg!("Instruction: changing color region");
+                change_color_region::process(program_id, accounts, &args)
+            }
+            ColorInstruction::SetColorConfig => {
+                let args = SetColorConfigArgs::try_from_slice(rest)?;
+                msg!("Instruction: setting color config");
+                set_color_config::process(program_id, accounts, &args)
+            }
         }
     }
 }
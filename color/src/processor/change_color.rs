@@ -11,32 +11,29 @@ use solana_program::{
     system_instruction,
     sysvar::{Sysvar},
 };
-use std::{
-    str::FromStr,
-    mem::size_of,
-    convert::TryInto,
-};
+use std::str::FromStr;
+use extend_common::utils::{get_marketplace_fee, get_neighborhood_xy, split_fee};
 
 use crate::{
     error::CustomError,
     instruction::{ChangeColorArgs, ChangeColorBriefArgs},
+    pda::{
+        create_frame_base_address,
+        create_frame_pointer_address,
+        create_neighborhood_metadata_address,
+        create_space_metadata_address,
+    },
+    processor::processor_utils::{get_color_config, get_color_index, get_time_cluster_index, get_time_thresh},
     state::{
-        NEIGHBORHOOD_SIZE,
-        NEIGHBORHOOD_METADATA_SEED,
+        RENT_PID,
         SPACE_PID,
-        SPACE_METADATA_SEED,
-        NEIGHBORHOOD_FRAME_BASE_SEED,
-        NEIGHBORHOOD_FRAME_POINTER_SEED,
         NeighborhoodMetadata,
         SpaceMetadata,
         NeighborhoodFrameBase,
         NeighborhoodFramePointer,
-        INACTIVITY_THRESHOLD_OWNER,
-        INACTIVITY_THRESHOLD_ARBITRARY,
-        ARBITRARY_CHANGER_FEE,
+        Base,
     },
-    processor::processor_utils::{get_neighborhood_xy},
-    validation_utils::{assert_is_ata, assert_keys_equal},
+    validation_utils::{assert_is_ata, assert_keys_equal, assert_owned_by, assert_rent_account},
 };
 
 pub fn process(
@@ -56,6 +53,7 @@ pub fn process(
     let time_cluster = next_account_info(account_info_iter)?;
     let fee_payer = next_account_info(account_info_iter)?;
     let system_program = next_account_info(account_info_iter)?;
+    let neighborhood_color_config = next_account_info(account_info_iter)?;
 
     // check fee payer is signer
     if !fee_payer.is_signer {
@@ -75,48 +73,52 @@ pub fn process(
     let (neighborhood_x, neighborhood_y) = get_neighborhood_xy(args.space_x, args.space_y);
     
     // verify frame base
-    let seeds_neighborhood_frame_base = &[
-        &base.key.to_bytes(),
-        NEIGHBORHOOD_FRAME_BASE_SEED,
-        &neighborhood_x.to_le_bytes(),
-        &neighborhood_y.to_le_bytes(),
-        &[neighborhood_frame_base_data.bump],
-    ];
-    let key = Pubkey::create_program_address(seeds_neighborhood_frame_base, program_id)?;
+    let key = create_frame_base_address(
+        program_id,
+        base.key,
+        neighborhood_x,
+        neighborhood_y,
+        neighborhood_frame_base_data.bump,
+    )?;
     assert_keys_equal(key, *neighborhood_frame_base.key)?;
 
+    // verify time cluster
+    assert_keys_equal(neighborhood_frame_base_data.time_cluster_account, *time_cluster.key)?;
+
+    // verify neighborhood color config, falling back to default parameters if it was never set
+    let (inactivity_threshold_owner, inactivity_threshold_arbitrary, arbitrary_changer_fee) =
+        get_color_config(program_id, base, neighborhood_color_config, neighborhood_x, neighborhood_y)?;
+
     // verify frame pointer
-    let seeds_neighborhood_frame_pointer = &[
-        &base.key.to_bytes(),
-        NEIGHBORHOOD_FRAME_POINTER_SEED,
-        &neighborhood_x.to_le_bytes(),
-        &neighborhood_y.to_le_bytes(),
-        &args.frame.to_le_bytes(),
-        &[neighborhood_frame_pointer_data.bump],
-    ];
-    let key = Pubkey::create_program_address(seeds_neighborhood_frame_pointer, program_id)?;
+    let key = create_frame_pointer_address(
+        program_id,
+        base.key,
+        neighborhood_x,
+        neighborhood_y,
+        args.frame,
+        neighborhood_frame_pointer_data.bump,
+    )?;
     assert_keys_equal(key, *neighborhood_frame_pointer.key)?;
     
     // verify neighborhood metadata
-    let seeds_neighborhood_metadata = &[
-        &base.key.to_bytes(),
-        NEIGHBORHOOD_METADATA_SEED,
-        &neighborhood_x.to_le_bytes(),
-        &neighborhood_y.to_le_bytes(),
-        &[neighborhood_metadata_data.bump],
-    ];
-    let key = Pubkey::create_program_address(seeds_neighborhood_metadata, &Pubkey::from_str(SPACE_PID).unwrap())?;
+    let space_program_id = Pubkey::from_str(SPACE_PID).unwrap();
+    let key = create_neighborhood_metadata_address(
+        &space_program_id,
+        base.key,
+        neighborhood_x,
+        neighborhood_y,
+        neighborhood_metadata_data.bump,
+    )?;
     assert_keys_equal(key, *neighborhood_metadata.key)?;
 
     // verify space metadata
-    let seeds_space_metadata = &[
-        &base.key.to_bytes(),
-        SPACE_METADATA_SEED,
-        &args.space_x.to_le_bytes(),
-        &args.space_y.to_le_bytes(),
-        &[space_metadata_data.bump],
-    ];
-    let key = Pubkey::create_program_address(seeds_space_metadata, &Pubkey::from_str(SPACE_PID).unwrap())?;
+    let key = create_space_metadata_address(
+        &space_program_id,
+        base.key,
+        args.space_x,
+        args.space_y,
+        space_metadata_data.bump,
+    )?;
     assert_keys_equal(key, *space_metadata.key)?;
 
     // check ATAs
@@ -139,52 +141,88 @@ pub fn process(
         *frame.key,
     )?;
 
-    // get indices
-    let n = NEIGHBORHOOD_SIZE as i64;
-    let x_mod = (args.space_x % n + n) % n;
-    let y_mod = (args.space_y % n + n) % n;
-
-    // inactivity checks for non-owner fee payer
+    // a fee payer other than the owner either rents the space, passing its rent account,
+    // or is an arbitrary changer paying the changer fee
     let now_ts = Clock::get().unwrap().unix_timestamp as u64;
+    let is_owner = *fee_payer.key == *owner.key;
+    let mut is_lessee = false;
+    let mut fee_accounts = None;
+    if !is_owner {
+        let next_account = next_account_info(account_info_iter)?;
+        if *next_account.owner == Pubkey::from_str(RENT_PID).unwrap() {
+            let rent_account_data = assert_rent_account(next_account, base.key, args.space_x, args.space_y)?;
+            assert_keys_equal(rent_account_data.lessee, *fee_payer.key)?;
+            if rent_account_data.rent_end <= now_ts {
+                msg!("Error: rental of space has ended");
+                return Err(ProgramError::InvalidAccountData);
+            }
+            is_lessee = true;
+        }
+        else {
+            fee_accounts = Some((next_account, next_account_info(account_info_iter)?));
+        }
+    }
+
+    // inactivity checks for arbitrary changers, the lessee edits like the owner during the rental
     let mut time_cluster_data = time_cluster.data.borrow_mut();
-    let idx_time_start = (8 * n * x_mod + 8 * y_mod) as usize;
+    let idx_time_start = get_time_cluster_index(args.space_x, args.space_y);
     let idx_time_end = idx_time_start + 8;
-    let time_thresh = u64::from_le_bytes( time_cluster_data[idx_time_start..idx_time_end].try_into().expect("incorrect") );
-    if (*fee_payer.key != *owner.key) && (time_thresh > now_ts) {
+    let time_thresh = get_time_thresh(&time_cluster_data, idx_time_start)?;
+    if !is_owner && !is_lessee && (time_thresh > now_ts) {
         msg!("Cannot change unowned space's color until inactivity period");
         return Err(ProgramError::IllegalOwner);
     }
     let thresh_add;
-    if *fee_payer.key == *owner.key {
-        thresh_add = INACTIVITY_THRESHOLD_OWNER as u64;
+    if let Some((neighborhood_creator, treasury)) = fee_accounts {
+        thresh_add = inactivity_threshold_arbitrary;
+
+        //deserialize and verify base, neighborhood creator and treasury
+        assert_owned_by(base, &space_program_id)?;
+        let base_data: Base = try_from_slice_unchecked(&base.data.borrow())?;
+        assert_keys_equal(neighborhood_metadata_data.creator, *neighborhood_creator.key)?;
+        assert_keys_equal(base_data.get_treasury(), *treasury.key)?;
+
+        // transfer fee to owner, less the marketplace fee split between neighborhood creator and treasury
+        let fee = arbitrary_changer_fee;
+        let marketplace_fee = get_marketplace_fee(
+            fee,
+            neighborhood_metadata_data.get_marketplace_fee_bps(),
+            base_data.get_max_marketplace_fee_bps(),
+        )?;
+        let (creator_fee, treasury_fee) = split_fee(marketplace_fee, base_data.treasury_fee_share_bps)?;
+        for (wallet, amount) in [
+            (owner, fee - marketplace_fee),
+            (neighborhood_creator, creator_fee),
+            (treasury, treasury_fee),
+        ] {
+            if amount == 0 {
+                continue;
+            }
+            invoke(
+                &system_instruction::transfer(
+                    fee_payer.key,
+                    wallet.key,
+                    amount,
+                ),
+                &[
+                    fee_payer.clone(),
+                    wallet.clone(),
+                    system_program.clone(),
+                ],
+            )?;
+        }
     }
     else {
-        thresh_add = INACTIVITY_THRESHOLD_ARBITRARY as u64;
-        // transfer fee
-        let fee = ARBITRARY_CHANGER_FEE;
-        invoke(
-            &system_instruction::transfer(
-                fee_payer.key,
-                owner.key,
-                fee,
-            ),
-            &[
-                fee_payer.clone(),
-                owner.clone(),
-                system_program.clone(),
-            ],
-        )?;
+        thresh_add = inactivity_threshold_owner;
     }
 
-    // let fut_thresh_bytes = u64::to_le_bytes(now_ts + thresh_add);
-    // for i in 0..size_of::<u64>(){
-    //     time_cluster_data[idx_time_start + i] = fut_thresh_bytes[i]; 
-    // }
-
+    // lock space until the inactivity period has passed
+    let fut_thresh = now_ts.saturating_add(thresh_add);
+    time_cluster_data[idx_time_start..idx_time_end].copy_from_slice(&fut_thresh.to_le_bytes());
 
     // change color
-    let mut frame_data = frame.data.borrow_mut();    
-    let idx = (3 * n * x_mod + 3 * y_mod) as usize;
+    let mut frame_data = frame.data.borrow_mut();
+    let idx = get_color_index(args.space_x, args.space_y);
     frame_data[idx] = args.r;
     frame_data[idx + 1] = args.g;
     frame_data[idx + 2] = args.b;
@@ -0,0 +1,91 @@
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    clock::Clock,
+    sysvar::Sysvar,
+};
+use extend_common::utils::get_neighborhood_xy;
+
+use crate::{
+    instruction::ChangeColorBatchArgs,
+    processor::processor_utils::{
+        assert_frame,
+        assert_space_owner,
+        get_color_config,
+        get_color_index,
+        get_time_cluster_index,
+        set_time_thresh,
+    },
+    validation_utils::assert_keys_equal,
+};
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: &ChangeColorBatchArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let base = next_account_info(account_info_iter)?;
+    let frame = next_account_info(account_info_iter)?;
+    let neighborhood_frame_base = next_account_info(account_info_iter)?;
+    let neighborhood_frame_pointer = next_account_info(account_info_iter)?;
+    let owner = next_account_info(account_info_iter)?;
+    let time_cluster = next_account_info(account_info_iter)?;
+    let neighborhood_color_config = next_account_info(account_info_iter)?;
+
+    // check owner is signer
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // check frame PDAs, time cluster and color config once for the whole batch
+    let neighborhood_frame_base_data = assert_frame(
+        program_id,
+        base,
+        frame,
+        neighborhood_frame_base,
+        neighborhood_frame_pointer,
+        args.neighborhood_x,
+        args.neighborhood_y,
+        args.frame,
+    )?;
+    assert_keys_equal(neighborhood_frame_base_data.time_cluster_account, *time_cluster.key)?;
+    let (inactivity_threshold_owner, _, _) = get_color_config(
+        program_id,
+        base,
+        neighborhood_color_config,
+        args.neighborhood_x,
+        args.neighborhood_y,
+    )?;
+    let fut_thresh = (Clock::get()?.unix_timestamp as u64).saturating_add(inactivity_threshold_owner);
+
+    let mut frame_data = frame.data.borrow_mut();
+    let mut time_cluster_data = time_cluster.data.borrow_mut();
+    for color in args.colors.iter() {
+        let space_metadata = next_account_info(account_info_iter)?;
+        let space_ata = next_account_info(account_info_iter)?;
+
+        // check space is in the frame's neighborhood
+        let (neighborhood_x, neighborhood_y) = get_neighborhood_xy(color.space_x, color.space_y);
+        if neighborhood_x != args.neighborhood_x || neighborhood_y != args.neighborhood_y {
+            msg!("Error: space ({}, {}) is not in the neighborhood", color.space_x, color.space_y);
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        assert_space_owner(base, space_metadata, space_ata, owner, color.space_x, color.space_y)?;
+
+        // lock space until the owner's inactivity period has passed
+        set_time_thresh(&mut time_cluster_data, get_time_cluster_index(color.space_x, color.space_y), fut_thresh)?;
+
+        // change color
+        let idx = get_color_index(color.space_x, color.space_y);
+        frame_data[idx] = color.r;
+        frame_data[idx + 1] = color.g;
+        frame_data[idx + 2] = color.b;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,132 @@
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    clock::Clock,
+    sysvar::Sysvar,
+};
+use extend_common::utils::get_neighborhood_xy;
+
+use crate::{
+    instruction::ChangeColorRegionArgs,
+    processor::processor_utils::{
+        assert_frame,
+        assert_space_owner,
+        get_color_config,
+        get_color_index,
+        get_time_cluster_index,
+        set_time_thresh,
+    },
+    validation_utils::assert_keys_equal,
+};
+
+// expand runs of (count, r, g, b) into exactly len colors
+fn decode_run_length(payload: &[u8], len: usize) -> Result<Vec<[u8; 3]>, ProgramError> {
+    let runs = payload.chunks_exact(4);
+    if !runs.remainder().is_empty() {
+        msg!("Error: payload is not a list of (count, r, g, b) runs");
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    let mut colors = Vec::with_capacity(len);
+    for run in runs {
+        let count = run[0] as usize;
+        if count == 0 || colors.len() + count > len {
+            msg!("Error: payload does not match region size");
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        for _ in 0..count {
+            colors.push([run[1], run[2], run[3]]);
+        }
+    }
+    if colors.len() != len {
+        msg!("Error: payload does not match region size");
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    Ok(colors)
+}
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: &ChangeColorRegionArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let base = next_account_info(account_info_iter)?;
+    let frame = next_account_info(account_info_iter)?;
+    let neighborhood_frame_base = next_account_info(account_info_iter)?;
+    let neighborhood_frame_pointer = next_account_info(account_info_iter)?;
+    let owner = next_account_info(account_info_iter)?;
+    let time_cluster = next_account_info(account_info_iter)?;
+    let neighborhood_color_config = next_account_info(account_info_iter)?;
+
+    // check owner is signer
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // check region is non-empty and within one neighborhood
+    if args.width == 0 || args.height == 0 {
+        msg!("Error: empty region");
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    let (end_x, end_y) = match (
+        args.space_x.checked_add(args.width as i64 - 1),
+        args.space_y.checked_add(args.height as i64 - 1),
+    ) {
+        (Some(end_x), Some(end_y)) => (end_x, end_y),
+        _ => {
+            msg!("Error: region runs past the edge of the map");
+            return Err(ProgramError::InvalidInstructionData);
+        }
+    };
+    let (neighborhood_x, neighborhood_y) = get_neighborhood_xy(args.space_x, args.space_y);
+    let (end_neighborhood_x, end_neighborhood_y) = get_neighborhood_xy(end_x, end_y);
+    if end_neighborhood_x != neighborhood_x || end_neighborhood_y != neighborhood_y {
+        msg!("Error: region crosses a neighborhood boundary");
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    let neighborhood_frame_base_data = assert_frame(
+        program_id,
+        base,
+        frame,
+        neighborhood_frame_base,
+        neighborhood_frame_pointer,
+        neighborhood_x,
+        neighborhood_y,
+        args.frame,
+    )?;
+    assert_keys_equal(neighborhood_frame_base_data.time_cluster_account, *time_cluster.key)?;
+    let (inactivity_threshold_owner, _, _) =
+        get_color_config(program_id, base, neighborhood_color_config, neighborhood_x, neighborhood_y)?;
+    let fut_thresh = (Clock::get()?.unix_timestamp as u64).saturating_add(inactivity_threshold_owner);
+
+    // check a space account and token account are passed for each space before expanding the payload
+    let len = args.width as usize * args.height as usize;
+    if account_info_iter.len() != 2 * len {
+        msg!("Error: accounts do not match region size");
+        return Err(ProgramError::InvalidArgument);
+    }
+    let colors = decode_run_length(&args.payload, len)?;
+
+    // check ownership of each space, lock it and write its color, x-major like the frame layout
+    let mut frame_data = frame.data.borrow_mut();
+    let mut time_cluster_data = time_cluster.data.borrow_mut();
+    let mut colors_iter = colors.iter();
+    for space_x in args.space_x..=end_x {
+        for space_y in args.space_y..=end_y {
+            let space_metadata = next_account_info(account_info_iter)?;
+            let space_ata = next_account_info(account_info_iter)?;
+            assert_space_owner(base, space_metadata, space_ata, owner, space_x, space_y)?;
+            set_time_thresh(&mut time_cluster_data, get_time_cluster_index(space_x, space_y), fut_thresh)?;
+
+            let color = colors_iter.next().unwrap();
+            let idx = get_color_index(space_x, space_y);
+            frame_data[idx..idx + 3].copy_from_slice(color);
+        }
+    }
+
+    Ok(())
+}
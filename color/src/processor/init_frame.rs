@@ -15,10 +15,14 @@ use std::str::FromStr;
 
 use crate::{
     instruction::InitFrameArgs,
+    pda::{
+        create_neighborhood_metadata_address,
+        find_frame_base_address,
+        find_frame_pointer_address,
+    },
     state::{
         SPACE_PID,
         NEIGHBORHOOD_SIZE,
-        NEIGHBORHOOD_METADATA_SEED,
         NEIGHBORHOOD_FRAME_BASE_SEED,
         NEIGHBORHOOD_FRAME_BASE_RESERVE,
         NEIGHBORHOOD_FRAME_POINTER_SEED,
@@ -55,13 +59,8 @@ pub fn process(
     assert_keys_equal(system_program::id(), *system_program.key)?;
 
     // check PDA of neighborhood frame base account and create it if necessary
-    let seeds_neighborhood_frame_base = &[
-        &base.key.to_bytes(),
-        NEIGHBORHOOD_FRAME_BASE_SEED,
-        &args.neighborhood_x.to_le_bytes(),
-        &args.neighborhood_y.to_le_bytes(),
-    ];
-    let (key, neighborhood_frame_base_bump) = Pubkey::find_program_address(seeds_neighborhood_frame_base, program_id);
+    let (key, neighborhood_frame_base_bump) =
+        find_frame_base_address(program_id, base.key, args.neighborhood_x, args.neighborhood_y);
     assert_keys_equal(key, *neighborhood_frame_base.key)?;
     let seeds_neighborhood_frame_base = &[
         &base.key.to_bytes(),
@@ -137,14 +136,13 @@ pub fn process(
     }
 
     // verify and create neighborhood frame pointer
-    let seeds_neighborhood_frame_pointer = &[
-        &base.key.to_bytes(),
-        NEIGHBORHOOD_FRAME_POINTER_SEED,
-        &args.neighborhood_x.to_le_bytes(),
-        &args.neighborhood_y.to_le_bytes(),
-        &neighborhood_frame_base_data.length.to_le_bytes(),
-    ];
-    let (key, neighborhood_frame_pointer_bump) = Pubkey::find_program_address(seeds_neighborhood_frame_pointer, program_id);
+    let (key, neighborhood_frame_pointer_bump) = find_frame_pointer_address(
+        program_id,
+        base.key,
+        args.neighborhood_x,
+        args.neighborhood_y,
+        neighborhood_frame_base_data.length,
+    );
     assert_keys_equal(key, *neighborhood_frame_pointer.key)?;
     let seeds_neighborhood_frame_pointer = &[
         &base.key.to_bytes(),
@@ -178,15 +176,13 @@ pub fn process(
 
     //deserialize and verify neighborhood metadata
     let neighborhood_metadata_data: NeighborhoodMetadata = try_from_slice_unchecked(&neighborhood_metadata.data.borrow_mut())?;
-    let seeds_neighborhood_metadata = &[
-        &base.key.to_bytes(),
-        NEIGHBORHOOD_METADATA_SEED,
-        &args.neighborhood_x.to_le_bytes(),
-        &args.neighborhood_y.to_le_bytes(),
-        &[neighborhood_metadata_data.bump],
-    ];
-    let key =
-        Pubkey::create_program_address(seeds_neighborhood_metadata, &Pubkey::from_str(SPACE_PID).unwrap())?;
+    let key = create_neighborhood_metadata_address(
+        &Pubkey::from_str(SPACE_PID).unwrap(),
+        base.key,
+        args.neighborhood_x,
+        args.neighborhood_y,
+        neighborhood_metadata_data.bump,
+    )?;
     assert_keys_equal(key, *neighborhood_metadata.key)?;
 
     // write frame pointer
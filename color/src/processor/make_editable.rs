@@ -4,31 +4,22 @@ use solana_program::{
     entrypoint::ProgramResult,
     msg,
     program_error::ProgramError,
-    program_pack::Pack,
     pubkey::Pubkey,
     clock::Clock,
     sysvar::{Sysvar},
 };
-use std::{
-    str::FromStr,
-    mem::size_of,
-    convert::TryInto,
-};
+use extend_common::utils::get_neighborhood_xy;
 
 use crate::{
-    error::CustomError,
     instruction::{MakeEditableArgs, MakeEditableBriefArgs},
-    state::{
-        NEIGHBORHOOD_SIZE,
-        SPACE_PID,
-        SPACE_METADATA_SEED,
-        SpaceMetadata,
-    },
-    validation_utils::{assert_is_ata, assert_keys_equal},
+    pda::create_frame_base_address,
+    processor::processor_utils::{assert_space_owner, get_time_cluster_index, get_time_thresh},
+    state::NeighborhoodFrameBase,
+    validation_utils::assert_keys_equal,
 };
 
 pub fn process(
-    _program_id: &Pubkey,
+    program_id: &Pubkey,
     accounts: &[AccountInfo],
     args: &MakeEditableArgs,
 ) -> ProgramResult {
@@ -38,57 +29,52 @@ pub fn process(
     let owner = next_account_info(account_info_iter)?;
     let space_ata = next_account_info(account_info_iter)?;
     let time_cluster = next_account_info(account_info_iter)?;
+    let neighborhood_frame_base = next_account_info(account_info_iter)?;
 
-    // check fee payer is signer
+    // check owner is signer
     if !owner.is_signer {
         return Err(ProgramError::MissingRequiredSignature);
     }
 
-    //  deserialize and check all PDAs
-    let space_metadata_data: SpaceMetadata =
-        try_from_slice_unchecked(&space_metadata.data.borrow())?;
-
-    // verify space metadata
-    let seeds_space_metadata = &[
-        &base.key.to_bytes(),
-        SPACE_METADATA_SEED,
-        &args.space_x.to_le_bytes(),
-        &args.space_y.to_le_bytes(),
-        &[space_metadata_data.bump],
-    ];
-    let key = Pubkey::create_program_address(seeds_space_metadata, &Pubkey::from_str(SPACE_PID).unwrap())?;
-    assert_keys_equal(key, *space_metadata.key)?;
-
-    // check ATAs
-    assert_is_ata(space_ata, owner.key, &space_metadata_data.mint)?;
-
-    // verify token is owned
-    let space_ata_data = spl_token::state::Account::unpack_from_slice(&space_ata.data.borrow())?;
-    if space_ata_data.amount != 1 {
-        msg!("Error: token account does not own token");
-        return Err(CustomError::MissingTokenOwner.into());
-    }
+    assert_space_owner(base, space_metadata, space_ata, owner, args.space_x, args.space_y)?;
 
-    // get indices
-    let n = NEIGHBORHOOD_SIZE as i64;
-    let x_mod = (args.space_x % n + n) % n;
-    let y_mod = (args.space_y % n + n) % n;
+    // verify time cluster belongs to the space's neighborhood
+    let neighborhood_frame_base_data: NeighborhoodFrameBase =
+        try_from_slice_unchecked(&neighborhood_frame_base.data.borrow())?;
+    let (neighborhood_x, neighborhood_y) = get_neighborhood_xy(args.space_x, args.space_y);
+    let key = create_frame_base_address(
+        program_id,
+        base.key,
+        neighborhood_x,
+        neighborhood_y,
+        neighborhood_frame_base_data.bump,
+    )?;
+    assert_keys_equal(key, *neighborhood_frame_base.key)?;
+    assert_keys_equal(neighborhood_frame_base_data.time_cluster_account, *time_cluster.key)?;
 
-    // inactivity checks for non-owner fee payer
-    let now_ts = Clock::get().unwrap().unix_timestamp as u64;
+    // space is editable by anyone once its threshold has passed
+    let now_ts = Clock::get()?.unix_timestamp as u64;
     let mut time_cluster_data = time_cluster.data.borrow_mut();
-    let idx_time_start = (8 * n * x_mod + 8 * y_mod) as usize;
+    let idx_time_start = get_time_cluster_index(args.space_x, args.space_y);
     let idx_time_end = idx_time_start + 8;
-    let time_thresh = u64::from_le_bytes( time_cluster_data[idx_time_start..idx_time_end].try_into().expect("incorrect") );
-    if time_thresh < now_ts {
-        msg!("Cannot make space editable while already inactive/editable");
-        return Err(ProgramError::InvalidArgument);
-    }
+    let time_thresh = get_time_thresh(&time_cluster_data, idx_time_start)?;
 
-    let new_thresh_bytes = u64::to_le_bytes(now_ts);
-    for i in 0..size_of::<u64>(){
-        time_cluster_data[idx_time_start + i] = new_thresh_bytes[i]; 
+    let new_thresh = if args.editable {
+        if time_thresh <= now_ts {
+            msg!("Cannot make space editable while already inactive/editable");
+            return Err(ProgramError::InvalidArgument);
+        }
+        now_ts
     }
+    else {
+        if time_thresh == u64::MAX {
+            msg!("Cannot make space uneditable while already uneditable");
+            return Err(ProgramError::InvalidArgument);
+        }
+        // back to the state InitFrame leaves every space in
+        u64::MAX
+    };
+    time_cluster_data[idx_time_start..idx_time_end].copy_from_slice(&new_thresh.to_le_bytes());
 
     Ok(())
 }
@@ -102,9 +88,8 @@ pub fn process_brief(
     let args = MakeEditableArgs{
         space_x: args_brief.space_x as i64,
         space_y: args_brief.space_y as i64,
+        editable: args_brief.editable,
     };
     process(program_id, accounts, &args)?;
     Ok(())
 }
-
-
@@ -1,18 +1,191 @@
-use crate::state::NEIGHBORHOOD_SIZE;
+use solana_program::{
+    account_info::AccountInfo,
+    borsh::try_from_slice_unchecked,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+use std::{
+    str::FromStr,
+    convert::TryInto,
+};
 
-pub fn floor_divide(x: i64, y: usize) -> i64 {
-    if x >= 0{
-        return x / y as i64;
+use crate::{
+    error::CustomError,
+    pda::{
+        create_color_config_address,
+        find_color_config_address,
+        create_frame_base_address,
+        create_frame_pointer_address,
+        create_space_metadata_address,
+    },
+    state::{
+        NEIGHBORHOOD_SIZE,
+        SPACE_PID,
+        SpaceMetadata,
+        NeighborhoodFrameBase,
+        NeighborhoodFramePointer,
+        NeighborhoodColorConfig,
+        INACTIVITY_THRESHOLD_OWNER,
+        INACTIVITY_THRESHOLD_ARBITRARY,
+        ARBITRARY_CHANGER_FEE,
+    },
+    validation_utils::{assert_is_ata, assert_keys_equal},
+};
+
+// verify frame base, frame pointer and frame for frame index of a neighborhood
+#[allow(clippy::too_many_arguments)]
+pub fn assert_frame(
+    program_id: &Pubkey,
+    base: &AccountInfo,
+    frame: &AccountInfo,
+    neighborhood_frame_base: &AccountInfo,
+    neighborhood_frame_pointer: &AccountInfo,
+    neighborhood_x: i64,
+    neighborhood_y: i64,
+    frame_index: u64,
+) -> Result<NeighborhoodFrameBase, ProgramError> {
+    let neighborhood_frame_base_data: NeighborhoodFrameBase =
+        try_from_slice_unchecked(&neighborhood_frame_base.data.borrow())?;
+    let neighborhood_frame_pointer_data: NeighborhoodFramePointer =
+        try_from_slice_unchecked(&neighborhood_frame_pointer.data.borrow())?;
+
+    // verify frame base
+    let key = create_frame_base_address(
+        program_id,
+        base.key,
+        neighborhood_x,
+        neighborhood_y,
+        neighborhood_frame_base_data.bump,
+    )?;
+    assert_keys_equal(key, *neighborhood_frame_base.key)?;
+
+    // verify frame pointer
+    let key = create_frame_pointer_address(
+        program_id,
+        base.key,
+        neighborhood_x,
+        neighborhood_y,
+        frame_index,
+        neighborhood_frame_pointer_data.bump,
+    )?;
+    assert_keys_equal(key, *neighborhood_frame_pointer.key)?;
+
+    // verify frame
+    if neighborhood_frame_base_data.length <= frame_index {
+        msg!("Number of frames is less than frame index");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    assert_keys_equal(
+        neighborhood_frame_pointer_data.framekey,
+        *frame.key,
+    )?;
+
+    Ok(neighborhood_frame_base_data)
+}
+
+// verify neighborhood color config and return its (inactivity threshold owner,
+// inactivity threshold arbitrary, arbitrary changer fee), the defaults if it was never set
+pub fn get_color_config(
+    program_id: &Pubkey,
+    base: &AccountInfo,
+    neighborhood_color_config: &AccountInfo,
+    neighborhood_x: i64,
+    neighborhood_y: i64,
+) -> Result<(u64, u64, u64), ProgramError> {
+    if neighborhood_color_config.data_len() == 0 {
+        let (key, _) = find_color_config_address(program_id, base.key, neighborhood_x, neighborhood_y);
+        assert_keys_equal(key, *neighborhood_color_config.key)?;
+        return Ok((INACTIVITY_THRESHOLD_OWNER as u64, INACTIVITY_THRESHOLD_ARBITRARY as u64, ARBITRARY_CHANGER_FEE));
     }
-    else {
-        let mut ans = x / y as i64;
-        if x % y as i64 != 0{
-            ans -= 1;
-        }
-        return ans;
+    let neighborhood_color_config_data: NeighborhoodColorConfig =
+        try_from_slice_unchecked(&neighborhood_color_config.data.borrow())?;
+    let key = create_color_config_address(
+        program_id,
+        base.key,
+        neighborhood_x,
+        neighborhood_y,
+        neighborhood_color_config_data.bump,
+    )?;
+    assert_keys_equal(key, *neighborhood_color_config.key)?;
+    Ok((
+        neighborhood_color_config_data.inactivity_threshold_owner,
+        neighborhood_color_config_data.inactivity_threshold_arbitrary,
+        neighborhood_color_config_data.arbitrary_changer_fee,
+    ))
+}
+
+// verify space metadata of space (x, y) and that owner holds its token
+pub fn assert_space_owner(
+    base: &AccountInfo,
+    space_metadata: &AccountInfo,
+    space_ata: &AccountInfo,
+    owner: &AccountInfo,
+    space_x: i64,
+    space_y: i64,
+) -> ProgramResult {
+    let space_metadata_data: SpaceMetadata =
+        try_from_slice_unchecked(&space_metadata.data.borrow())?;
+    let key = create_space_metadata_address(
+        &Pubkey::from_str(SPACE_PID).unwrap(),
+        base.key,
+        space_x,
+        space_y,
+        space_metadata_data.bump,
+    )?;
+    assert_keys_equal(key, *space_metadata.key)?;
+
+    // check ATAs
+    assert_is_ata(space_ata, owner.key, &space_metadata_data.mint)?;
+
+    // verify token is owned
+    let space_ata_data = spl_token::state::Account::unpack_from_slice(&space_ata.data.borrow())?;
+    if space_ata_data.amount != 1 {
+        msg!("Error: token account does not own token");
+        return Err(CustomError::MissingTokenOwner.into());
     }
+
+    Ok(())
+}
+
+// offset of space (x, y) in its neighborhood's frame colors
+pub fn get_color_index(space_x: i64, space_y: i64) -> usize {
+    let n = NEIGHBORHOOD_SIZE as i64;
+    let x_mod = (space_x % n + n) % n;
+    let y_mod = (space_y % n + n) % n;
+    (3 * n * x_mod + 3 * y_mod) as usize
 }
 
-pub fn get_neighborhood_xy(x: i64, y: i64) -> (i64, i64){
-    return (floor_divide(x, NEIGHBORHOOD_SIZE), floor_divide(y, NEIGHBORHOOD_SIZE));
-}
\ No newline at end of file
+// offset of space (x, y) in its neighborhood's time cluster timestamps
+pub fn get_time_cluster_index(space_x: i64, space_y: i64) -> usize {
+    let n = NEIGHBORHOOD_SIZE as i64;
+    let x_mod = (space_x % n + n) % n;
+    let y_mod = (space_y % n + n) % n;
+    (8 * n * x_mod + 8 * y_mod) as usize
+}
+
+// inactivity threshold stored at idx in a time cluster
+pub fn get_time_thresh(time_cluster_data: &[u8], idx: usize) -> Result<u64, ProgramError> {
+    time_cluster_data
+        .get(idx..idx + 8)
+        .and_then(|bytes| bytes.try_into().ok())
+        .map(u64::from_le_bytes)
+        .ok_or_else(|| {
+            msg!("Error: time cluster account is too small");
+            ProgramError::InvalidAccountData
+        })
+}
+
+// store the inactivity threshold at idx in a time cluster
+pub fn set_time_thresh(time_cluster_data: &mut [u8], idx: usize, time_thresh: u64) -> ProgramResult {
+    time_cluster_data
+        .get_mut(idx..idx + 8)
+        .ok_or_else(|| {
+            msg!("Error: time cluster account is too small");
+            ProgramError::InvalidAccountData
+        })?
+        .copy_from_slice(&time_thresh.to_le_bytes());
+    Ok(())
+}
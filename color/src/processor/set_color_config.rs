@@ -0,0 +1,110 @@
+use borsh::BorshSerialize;
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    borsh::try_from_slice_unchecked,
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_instruction, system_program,
+    sysvar::rent::Rent,
+};
+use std::str::FromStr;
+
+use crate::{
+    error::CustomError,
+    instruction::SetColorConfigArgs,
+    pda::{create_neighborhood_metadata_address, find_color_config_address},
+    state::{
+        SPACE_PID,
+        NEIGHBORHOOD_COLOR_CONFIG_SEED,
+        NEIGHBORHOOD_COLOR_CONFIG_RESERVE,
+        NeighborhoodColorConfig,
+        NeighborhoodMetadata,
+    },
+    validation_utils::assert_keys_equal,
+};
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: &SetColorConfigArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let base = next_account_info(account_info_iter)?;
+    let neighborhood_metadata = next_account_info(account_info_iter)?;
+    let neighborhood_color_config = next_account_info(account_info_iter)?;
+    let creator = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    // check signers
+    if !creator.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // check programs
+    assert_keys_equal(system_program::id(), *system_program.key)?;
+
+    // deserialize and verify neighborhood metadata
+    let neighborhood_metadata_data: NeighborhoodMetadata =
+        try_from_slice_unchecked(&neighborhood_metadata.data.borrow())?;
+    let key = create_neighborhood_metadata_address(
+        &Pubkey::from_str(SPACE_PID).unwrap(),
+        base.key,
+        args.neighborhood_x,
+        args.neighborhood_y,
+        neighborhood_metadata_data.bump,
+    )?;
+    assert_keys_equal(key, *neighborhood_metadata.key)?;
+
+    // only the neighborhood creator can configure its colors
+    if neighborhood_metadata_data.creator != *creator.key {
+        msg!("Error: signer is not the neighborhood creator");
+        return Err(CustomError::WrongAuthority.into());
+    }
+
+    // check PDA of neighborhood color config and create it if necessary
+    let (key, neighborhood_color_config_bump) =
+        find_color_config_address(program_id, base.key, args.neighborhood_x, args.neighborhood_y);
+    assert_keys_equal(key, *neighborhood_color_config.key)?;
+    if neighborhood_color_config.data_len() == 0 {
+        let seeds_neighborhood_color_config = &[
+            &base.key.to_bytes(),
+            NEIGHBORHOOD_COLOR_CONFIG_SEED,
+            &args.neighborhood_x.to_le_bytes(),
+            &args.neighborhood_y.to_le_bytes(),
+            &[neighborhood_color_config_bump],
+        ];
+        let required_lamports = Rent::default()
+            .minimum_balance(NEIGHBORHOOD_COLOR_CONFIG_RESERVE)
+            .max(1)
+            .saturating_sub(neighborhood_color_config.lamports());
+        invoke_signed(
+            &system_instruction::create_account(
+                creator.key,
+                neighborhood_color_config.key,
+                required_lamports,
+                NEIGHBORHOOD_COLOR_CONFIG_RESERVE as u64,
+                program_id,
+            ),
+            &[
+                creator.clone(),
+                neighborhood_color_config.clone(),
+                system_program.clone(),
+            ],
+            &[seeds_neighborhood_color_config],
+        )?;
+    }
+
+    // write parameters
+    let neighborhood_color_config_data = NeighborhoodColorConfig {
+        bump: neighborhood_color_config_bump,
+        inactivity_threshold_owner: args.inactivity_threshold_owner,
+        inactivity_threshold_arbitrary: args.inactivity_threshold_arbitrary,
+        arbitrary_changer_fee: args.arbitrary_changer_fee,
+    };
+    neighborhood_color_config_data.serialize(&mut *neighborhood_color_config.data.borrow_mut())?;
+
+    Ok(())
+}
@@ -0,0 +1,414 @@
+// run against the BPF build with `cargo test-bpf`
+#![cfg(feature = "test-bpf")]
+
+mod utils;
+
+use extend_color::{
+    instruction,
+    state::{ARBITRARY_CHANGER_FEE, NEIGHBORHOOD_SIZE},
+};
+use extend_common::utils::get_neighborhood_xy;
+use solana_program::pubkey::Pubkey;
+use solana_program_test::ProgramTestContext;
+use solana_sdk::signature::{Keypair, Signer};
+use utils::*;
+
+// space at (space_x, space_y) held by owner, with a single frame created through InitFrame
+struct FrameFixture {
+    context: ProgramTestContext,
+    base: Pubkey,
+    owner: Keypair,
+    space_mint: Pubkey,
+    frame: Pubkey,
+    time_cluster: Pubkey,
+}
+
+async fn setup_frame(space_x: i64, space_y: i64) -> FrameFixture {
+    let mut program_test = program_test();
+    let base = Pubkey::new_unique();
+    let owner = add_wallet(&mut program_test);
+    let (neighborhood_x, neighborhood_y) = get_neighborhood_xy(space_x, space_y);
+    add_neighborhood(&mut program_test, &base, neighborhood_x, neighborhood_y);
+    let space_mint = add_space(&mut program_test, &base, &owner.pubkey(), space_x, space_y);
+    let frame = add_frame_account(&mut program_test);
+    let time_cluster = add_time_cluster_account(&mut program_test);
+    let mut context = program_test.start_with_context().await;
+
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::init_frame(
+            &program_id(),
+            &base,
+            &frame,
+            &time_cluster,
+            &context.payer.pubkey(),
+            neighborhood_x,
+            neighborhood_y,
+            0,
+        )],
+        &[&context.payer],
+    ).await.unwrap();
+
+    FrameFixture {
+        context,
+        base,
+        owner,
+        space_mint,
+        frame,
+        time_cluster,
+    }
+}
+
+#[tokio::test]
+async fn test_change_color_writes_pixel() {
+    let (space_x, space_y) = (10, 20);
+    let FrameFixture { mut context, base, owner, space_mint, frame, time_cluster } = setup_frame(space_x, space_y).await;
+
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::change_color(
+            &program_id(),
+            &base,
+            &frame,
+            &time_cluster,
+            &space_mint,
+            &owner.pubkey(),
+            &owner.pubkey(),
+            space_x,
+            space_y,
+            0,
+            1,
+            2,
+            3,
+        )],
+        &[&owner],
+    ).await.unwrap();
+
+    let frame_data = get_account(&mut context.banks_client, &frame).await.data;
+    let idx = 3 * (NEIGHBORHOOD_SIZE * 10 + 20);
+    assert_eq!(frame_data[idx..idx + 3], [1, 2, 3]);
+    assert_eq!(frame_color(&frame_data, space_x, space_y), [1, 2, 3]);
+    assert_eq!(frame_data.iter().filter(|&&val| val != 0).count(), 3 + 1);
+}
+
+#[tokio::test]
+async fn test_change_color_brief_writes_pixel() {
+    let (space_x, space_y) = (199, 0);
+    let FrameFixture { mut context, base, owner, space_mint, frame, time_cluster } = setup_frame(space_x, space_y).await;
+
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::change_color_brief(
+            &program_id(),
+            &base,
+            &frame,
+            &time_cluster,
+            &space_mint,
+            &owner.pubkey(),
+            &owner.pubkey(),
+            space_x as i16,
+            space_y as i16,
+            0,
+            255,
+            128,
+            7,
+        )],
+        &[&owner],
+    ).await.unwrap();
+
+    let frame_data = get_account(&mut context.banks_client, &frame).await.data;
+    let idx = 3 * (NEIGHBORHOOD_SIZE * 199);
+    assert_eq!(frame_data[idx..idx + 3], [255, 128, 7]);
+}
+
+#[tokio::test]
+async fn test_change_color_negative_coordinates() {
+    // neighborhood (-1, -2), position (199, 199) within it
+    let (space_x, space_y) = (-1, -201);
+    assert_eq!(get_neighborhood_xy(space_x, space_y), (-1, -2));
+    let FrameFixture { mut context, base, owner, space_mint, frame, time_cluster } = setup_frame(space_x, space_y).await;
+
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::change_color_brief(
+            &program_id(),
+            &base,
+            &frame,
+            &time_cluster,
+            &space_mint,
+            &owner.pubkey(),
+            &owner.pubkey(),
+            space_x as i16,
+            space_y as i16,
+            0,
+            9,
+            8,
+            7,
+        )],
+        &[&owner],
+    ).await.unwrap();
+
+    let frame_data = get_account(&mut context.banks_client, &frame).await.data;
+    let idx = 3 * (NEIGHBORHOOD_SIZE * 199 + 199);
+    assert_eq!(frame_data[idx..idx + 3], [9, 8, 7]);
+}
+
+#[tokio::test]
+async fn test_change_color_rejects_other_frame() {
+    let (space_x, space_y) = (10, 20);
+    let FrameFixture { mut context, base, owner, space_mint, time_cluster, .. } = setup_frame(space_x, space_y).await;
+
+    let result = process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::change_color(
+            &program_id(),
+            &base,
+            &Pubkey::new_unique(),
+            &time_cluster,
+            &space_mint,
+            &owner.pubkey(),
+            &owner.pubkey(),
+            space_x,
+            space_y,
+            0,
+            1,
+            2,
+            3,
+        )],
+        &[&owner],
+    ).await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_change_color_non_owner_locked() {
+    let (space_x, space_y) = (10, 20);
+    let FrameFixture { mut context, base, owner, space_mint, frame, time_cluster } = setup_frame(space_x, space_y).await;
+
+    let result = process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::change_color(
+            &program_id(),
+            &base,
+            &frame,
+            &time_cluster,
+            &space_mint,
+            &owner.pubkey(),
+            &context.payer.pubkey(),
+            space_x,
+            space_y,
+            0,
+            1,
+            2,
+            3,
+        )],
+        &[&context.payer],
+    ).await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_change_color_non_owner_pays_fee_after_inactivity() {
+    let (space_x, space_y) = (-5, 7);
+    let (neighborhood_x, neighborhood_y) = get_neighborhood_xy(space_x, space_y);
+    let mut program_test = program_test();
+    let owner = add_wallet(&mut program_test);
+    let changer = add_wallet(&mut program_test);
+    let creator = add_wallet(&mut program_test);
+    let treasury = add_wallet(&mut program_test);
+    let base = add_base(&mut program_test, &treasury.pubkey(), 5000);
+    add_neighborhood_with_creator(&mut program_test, &base, &creator.pubkey(), neighborhood_x, neighborhood_y);
+    let space_mint = add_space(&mut program_test, &base, &owner.pubkey(), space_x, space_y);
+    let (frame, time_cluster) =
+        add_initialized_frame(&mut program_test, &base, neighborhood_x, neighborhood_y, &[(space_x, space_y)]);
+    let mut context = program_test.start_with_context().await;
+    let banks_client = &mut context.banks_client;
+
+    let time_cluster_data = get_account(banks_client, &time_cluster).await.data;
+    assert_eq!(time_cluster_threshold(&time_cluster_data, space_x, space_y), 0);
+    assert_eq!(time_cluster_threshold(&time_cluster_data, space_x + 1, space_y), u64::MAX);
+
+    let owner_lamports = banks_client.get_balance(owner.pubkey()).await.unwrap();
+    process_instructions(
+        banks_client,
+        context.last_blockhash,
+        &[instruction::change_color_paying_fee(
+            &program_id(),
+            &base,
+            &frame,
+            &time_cluster,
+            &space_mint,
+            &owner.pubkey(),
+            &changer.pubkey(),
+            &creator.pubkey(),
+            &treasury.pubkey(),
+            space_x,
+            space_y,
+            0,
+            4,
+            5,
+            6,
+        )],
+        &[&changer],
+    ).await.unwrap();
+
+    // marketplace fee on the changer fee is split evenly between neighborhood creator and treasury
+    let marketplace_fee = ARBITRARY_CHANGER_FEE - owner_share(ARBITRARY_CHANGER_FEE);
+    assert_eq!(
+        banks_client.get_balance(owner.pubkey()).await.unwrap(),
+        owner_lamports + owner_share(ARBITRARY_CHANGER_FEE),
+    );
+    assert_eq!(banks_client.get_balance(creator.pubkey()).await.unwrap(), WALLET_LAMPORTS + marketplace_fee / 2);
+    assert_eq!(banks_client.get_balance(treasury.pubkey()).await.unwrap(), WALLET_LAMPORTS + marketplace_fee / 2);
+    let frame_data = get_account(banks_client, &frame).await.data;
+    assert_eq!(frame_color(&frame_data, space_x, space_y), [4, 5, 6]);
+}
+
+#[tokio::test]
+async fn test_change_color_owner_ignores_lock() {
+    let (space_x, space_y) = (-5, 7);
+    let (neighborhood_x, neighborhood_y) = get_neighborhood_xy(space_x, space_y);
+    let mut program_test = program_test();
+    let base = Pubkey::new_unique();
+    let owner = add_wallet(&mut program_test);
+    add_neighborhood(&mut program_test, &base, neighborhood_x, neighborhood_y);
+    let space_mint = add_space(&mut program_test, &base, &owner.pubkey(), space_x, space_y);
+    let (frame, time_cluster) = add_initialized_frame(&mut program_test, &base, neighborhood_x, neighborhood_y, &[]);
+    let mut context = program_test.start_with_context().await;
+
+    let owner_lamports = context.banks_client.get_balance(owner.pubkey()).await.unwrap();
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::change_color(
+            &program_id(),
+            &base,
+            &frame,
+            &time_cluster,
+            &space_mint,
+            &owner.pubkey(),
+            &owner.pubkey(),
+            space_x,
+            space_y,
+            0,
+            4,
+            5,
+            6,
+        )],
+        &[&owner],
+    ).await.unwrap();
+
+    // owner only pays the transaction fee
+    assert!(context.banks_client.get_balance(owner.pubkey()).await.unwrap() < owner_lamports);
+    let frame_data = get_account(&mut context.banks_client, &frame).await.data;
+    assert_eq!(frame_color(&frame_data, space_x, space_y), [4, 5, 6]);
+}
+
+// space at (space_x, space_y) held by owner and locked, with its rent account recording a rental to lessee until rent_end
+async fn setup_rented_frame(space_x: i64, space_y: i64, rent_end: u64) -> (FrameFixture, Keypair) {
+    let (neighborhood_x, neighborhood_y) = get_neighborhood_xy(space_x, space_y);
+    let mut program_test = program_test();
+    let base = Pubkey::new_unique();
+    let owner = add_wallet(&mut program_test);
+    let lessee = add_wallet(&mut program_test);
+    add_neighborhood(&mut program_test, &base, neighborhood_x, neighborhood_y);
+    let space_mint = add_space(&mut program_test, &base, &owner.pubkey(), space_x, space_y);
+    add_rent_account(&mut program_test, &base, &space_mint, &lessee.pubkey(), space_x, space_y, rent_end);
+    let (frame, time_cluster) = add_initialized_frame(&mut program_test, &base, neighborhood_x, neighborhood_y, &[]);
+    let context = program_test.start_with_context().await;
+    (FrameFixture { context, base, owner, space_mint, frame, time_cluster }, lessee)
+}
+
+#[tokio::test]
+async fn test_change_color_lessee_during_rental() {
+    let (space_x, space_y) = (10, 20);
+    let (FrameFixture { mut context, base, owner, space_mint, frame, time_cluster }, lessee) =
+        setup_rented_frame(space_x, space_y, u64::MAX).await;
+
+    // the lessee paints the locked space without paying the changer fee
+    let owner_lamports = context.banks_client.get_balance(owner.pubkey()).await.unwrap();
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::change_color_as_lessee(
+            &program_id(),
+            &base,
+            &frame,
+            &time_cluster,
+            &space_mint,
+            &owner.pubkey(),
+            &lessee.pubkey(),
+            space_x,
+            space_y,
+            0,
+            1,
+            2,
+            3,
+        )],
+        &[&lessee],
+    ).await.unwrap();
+
+    let banks_client = &mut context.banks_client;
+    assert_eq!(banks_client.get_balance(owner.pubkey()).await.unwrap(), owner_lamports);
+    let frame_data = get_account(banks_client, &frame).await.data;
+    assert_eq!(frame_color(&frame_data, space_x, space_y), [1, 2, 3]);
+
+    // anyone else passing the rent account is still locked out
+    let changer = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+    let result = process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::change_color_as_lessee(
+            &program_id(),
+            &base,
+            &frame,
+            &time_cluster,
+            &space_mint,
+            &owner.pubkey(),
+            &changer.pubkey(),
+            space_x,
+            space_y,
+            0,
+            4,
+            5,
+            6,
+        )],
+        &[&changer],
+    ).await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_change_color_lessee_after_rental_ends() {
+    let (space_x, space_y) = (10, 20);
+    let (FrameFixture { mut context, base, owner, space_mint, frame, time_cluster }, lessee) =
+        setup_rented_frame(space_x, space_y, 1).await;
+
+    let result = process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::change_color_as_lessee(
+            &program_id(),
+            &base,
+            &frame,
+            &time_cluster,
+            &space_mint,
+            &owner.pubkey(),
+            &lessee.pubkey(),
+            space_x,
+            space_y,
+            0,
+            1,
+            2,
+            3,
+        )],
+        &[&lessee],
+    ).await;
+    assert!(result.is_err());
+}
@@ -0,0 +1,192 @@
+// run against the BPF build with `cargo test-bpf`
+#![cfg(feature = "test-bpf")]
+
+mod utils;
+
+use extend_color::instruction::{self, SpaceColor};
+use extend_common::utils::get_neighborhood_xy;
+use solana_program::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+use utils::*;
+
+const NEIGHBORHOOD_X: i64 = -1;
+const NEIGHBORHOOD_Y: i64 = 0;
+const SPACES: [(i64, i64); 3] = [(-200, 0), (-1, 199), (-100, 50)];
+
+fn colors(spaces: &[(i64, i64)]) -> Vec<SpaceColor> {
+    spaces.iter().enumerate().map(|(i, &(space_x, space_y))| SpaceColor {
+        space_x,
+        space_y,
+        r: i as u8 + 1,
+        g: i as u8 + 10,
+        b: i as u8 + 100,
+    }).collect()
+}
+
+#[tokio::test]
+async fn test_change_color_batch_writes_pixels() {
+    let mut program_test = program_test();
+    let base = Pubkey::new_unique();
+    let owner = add_wallet(&mut program_test);
+    let space_mints: Vec<Pubkey> = SPACES.iter()
+        .map(|&(space_x, space_y)| add_space(&mut program_test, &base, &owner.pubkey(), space_x, space_y))
+        .collect();
+    let (frame, time_cluster) = add_initialized_frame(&mut program_test, &base, NEIGHBORHOOD_X, NEIGHBORHOOD_Y, &[]);
+    let mut context = program_test.start_with_context().await;
+
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::change_color_batch(
+            &program_id(),
+            &base,
+            &frame,
+            &time_cluster,
+            &owner.pubkey(),
+            NEIGHBORHOOD_X,
+            NEIGHBORHOOD_Y,
+            0,
+            &space_mints,
+            colors(&SPACES),
+        )],
+        &[&owner],
+    ).await.unwrap();
+
+    let frame_data = get_account(&mut context.banks_client, &frame).await.data;
+    for color in colors(&SPACES) {
+        assert_eq!(frame_color(&frame_data, color.space_x, color.space_y), [color.r, color.g, color.b]);
+    }
+    assert_eq!(frame_color(&frame_data, -2, 0), [0, 0, 0]);
+}
+
+#[tokio::test]
+async fn test_change_color_batch_locks_spaces_against_changers() {
+    let (space_x, space_y) = SPACES[0];
+    let (neighborhood_x, neighborhood_y) = get_neighborhood_xy(space_x, space_y);
+    let mut program_test = program_test();
+    let owner = add_wallet(&mut program_test);
+    let changer = add_wallet(&mut program_test);
+    let creator = add_wallet(&mut program_test);
+    let treasury = add_wallet(&mut program_test);
+    let base = add_base(&mut program_test, &treasury.pubkey(), 5000);
+    add_neighborhood_with_creator(&mut program_test, &base, &creator.pubkey(), neighborhood_x, neighborhood_y);
+    let space_mint = add_space(&mut program_test, &base, &owner.pubkey(), space_x, space_y);
+    let (frame, time_cluster) =
+        add_initialized_frame(&mut program_test, &base, neighborhood_x, neighborhood_y, &[(space_x, space_y)]);
+    let mut context = program_test.start_with_context().await;
+
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::change_color_batch(
+            &program_id(),
+            &base,
+            &frame,
+            &time_cluster,
+            &owner.pubkey(),
+            NEIGHBORHOOD_X,
+            NEIGHBORHOOD_Y,
+            0,
+            &[space_mint],
+            colors(&SPACES[..1]),
+        )],
+        &[&owner],
+    ).await.unwrap();
+
+    // the space was open to changers before the batch, the owner's paint locks it again
+    let result = process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::change_color_paying_fee(
+            &program_id(),
+            &base,
+            &frame,
+            &time_cluster,
+            &space_mint,
+            &owner.pubkey(),
+            &changer.pubkey(),
+            &creator.pubkey(),
+            &treasury.pubkey(),
+            space_x,
+            space_y,
+            0,
+            4,
+            5,
+            6,
+        )],
+        &[&changer],
+    ).await;
+    assert!(result.is_err());
+
+    let frame_data = get_account(&mut context.banks_client, &frame).await.data;
+    assert_eq!(frame_color(&frame_data, space_x, space_y), [1, 10, 100]);
+}
+
+#[tokio::test]
+async fn test_change_color_batch_rejects_unowned_space() {
+    let mut program_test = program_test();
+    let base = Pubkey::new_unique();
+    let owner = add_wallet(&mut program_test);
+    let other = add_wallet(&mut program_test);
+    let space_mints = vec![
+        add_space(&mut program_test, &base, &owner.pubkey(), SPACES[0].0, SPACES[0].1),
+        add_space(&mut program_test, &base, &other.pubkey(), SPACES[1].0, SPACES[1].1),
+    ];
+    let (frame, time_cluster) = add_initialized_frame(&mut program_test, &base, NEIGHBORHOOD_X, NEIGHBORHOOD_Y, &[]);
+    let mut context = program_test.start_with_context().await;
+
+    let result = process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::change_color_batch(
+            &program_id(),
+            &base,
+            &frame,
+            &time_cluster,
+            &owner.pubkey(),
+            NEIGHBORHOOD_X,
+            NEIGHBORHOOD_Y,
+            0,
+            &space_mints,
+            colors(&SPACES[..2]),
+        )],
+        &[&owner],
+    ).await;
+    assert!(result.is_err());
+
+    // nothing is written when any space fails
+    let frame_data = get_account(&mut context.banks_client, &frame).await.data;
+    assert_eq!(frame_color(&frame_data, SPACES[0].0, SPACES[0].1), [0, 0, 0]);
+}
+
+#[tokio::test]
+async fn test_change_color_batch_rejects_space_outside_neighborhood() {
+    let mut program_test = program_test();
+    let base = Pubkey::new_unique();
+    let owner = add_wallet(&mut program_test);
+    let spaces = [SPACES[0], (0, 0)];
+    let space_mints: Vec<Pubkey> = spaces.iter()
+        .map(|&(space_x, space_y)| add_space(&mut program_test, &base, &owner.pubkey(), space_x, space_y))
+        .collect();
+    let (frame, time_cluster) = add_initialized_frame(&mut program_test, &base, NEIGHBORHOOD_X, NEIGHBORHOOD_Y, &[]);
+    let mut context = program_test.start_with_context().await;
+
+    let result = process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::change_color_batch(
+            &program_id(),
+            &base,
+            &frame,
+            &time_cluster,
+            &owner.pubkey(),
+            NEIGHBORHOOD_X,
+            NEIGHBORHOOD_Y,
+            0,
+            &space_mints,
+            colors(&spaces),
+        )],
+        &[&owner],
+    ).await;
+    assert!(result.is_err());
+}
@@ -0,0 +1,258 @@
+// run against the BPF build with `cargo test-bpf`
+#![cfg(feature = "test-bpf")]
+
+mod utils;
+
+use borsh::BorshSerialize;
+use extend_color::{
+    instruction::{self, encode_run_length},
+    state::INACTIVITY_THRESHOLD_OWNER,
+};
+use solana_program::pubkey::Pubkey;
+use solana_program_test::ProgramTest;
+use solana_sdk::signature::{Keypair, Signer};
+use utils::*;
+
+const SPACE_X: i64 = 5;
+const SPACE_Y: i64 = 10;
+const WIDTH: u16 = 2;
+const HEIGHT: u16 = 3;
+
+fn region() -> Vec<(i64, i64)> {
+    let mut spaces = Vec::new();
+    for x in SPACE_X..SPACE_X + WIDTH as i64 {
+        for y in SPACE_Y..SPACE_Y + HEIGHT as i64 {
+            spaces.push((x, y));
+        }
+    }
+    spaces
+}
+
+fn add_region(program_test: &mut ProgramTest, base: &Pubkey, owner: &Keypair, spaces: &[(i64, i64)]) -> Vec<Pubkey> {
+    spaces.iter()
+        .map(|&(space_x, space_y)| add_space(program_test, base, &owner.pubkey(), space_x, space_y))
+        .collect()
+}
+
+#[test]
+fn test_encode_run_length() {
+    let colors = [[1, 1, 1], [1, 1, 1], [2, 3, 4], [1, 1, 1]];
+    assert_eq!(encode_run_length(&colors), vec![2, 1, 1, 1, 1, 2, 3, 4, 1, 1, 1, 1]);
+
+    let colors = vec![[7, 7, 7]; 300];
+    assert_eq!(encode_run_length(&colors), vec![255, 7, 7, 7, 45, 7, 7, 7]);
+}
+
+#[tokio::test]
+async fn test_change_color_region_writes_rectangle() {
+    let mut program_test = program_test();
+    let base = Pubkey::new_unique();
+    let owner = add_wallet(&mut program_test);
+    let space_mints = add_region(&mut program_test, &base, &owner, &region());
+    let (frame, time_cluster) = add_initialized_frame(&mut program_test, &base, 0, 0, &[]);
+    let mut context = program_test.start_with_context().await;
+
+    let colors = [[1, 2, 3], [1, 2, 3], [1, 2, 3], [1, 2, 3], [9, 9, 9], [1, 2, 3]];
+    let before = get_unix_timestamp(&mut context.banks_client).await;
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::change_color_region(
+            &program_id(),
+            &base,
+            &frame,
+            &time_cluster,
+            &owner.pubkey(),
+            SPACE_X,
+            SPACE_Y,
+            WIDTH,
+            HEIGHT,
+            0,
+            &space_mints,
+            &colors,
+        )],
+        &[&owner],
+    ).await.unwrap();
+
+    let frame_data = get_account(&mut context.banks_client, &frame).await.data;
+    for (&(space_x, space_y), color) in region().iter().zip(colors.iter()) {
+        assert_eq!(frame_color(&frame_data, space_x, space_y), *color);
+    }
+
+    // every painted space is locked for the owner's inactivity period
+    let after = get_unix_timestamp(&mut context.banks_client).await;
+    let time_cluster_data = get_account(&mut context.banks_client, &time_cluster).await.data;
+    for &(space_x, space_y) in region().iter() {
+        let threshold = time_cluster_threshold(&time_cluster_data, space_x, space_y);
+        assert!(threshold >= before + INACTIVITY_THRESHOLD_OWNER as u64);
+        assert!(threshold <= after + INACTIVITY_THRESHOLD_OWNER as u64);
+    }
+    assert_eq!(frame_color(&frame_data, SPACE_X, SPACE_Y + HEIGHT as i64), [0, 0, 0]);
+    assert_eq!(frame_color(&frame_data, SPACE_X + WIDTH as i64, SPACE_Y), [0, 0, 0]);
+}
+
+#[tokio::test]
+async fn test_change_color_region_rejects_unowned_space() {
+    let mut program_test = program_test();
+    let base = Pubkey::new_unique();
+    let owner = add_wallet(&mut program_test);
+    let other = add_wallet(&mut program_test);
+    let spaces = region();
+    let mut space_mints = add_region(&mut program_test, &base, &owner, &spaces[..5]);
+    space_mints.extend(add_region(&mut program_test, &base, &other, &spaces[5..]));
+    let (frame, time_cluster) = add_initialized_frame(&mut program_test, &base, 0, 0, &[]);
+    let mut context = program_test.start_with_context().await;
+
+    let result = process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::change_color_region(
+            &program_id(),
+            &base,
+            &frame,
+            &time_cluster,
+            &owner.pubkey(),
+            SPACE_X,
+            SPACE_Y,
+            WIDTH,
+            HEIGHT,
+            0,
+            &space_mints,
+            &[[1, 2, 3]; 6],
+        )],
+        &[&owner],
+    ).await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_change_color_region_rejects_payload_size_mismatch() {
+    let mut program_test = program_test();
+    let base = Pubkey::new_unique();
+    let owner = add_wallet(&mut program_test);
+    let space_mints = add_region(&mut program_test, &base, &owner, &region());
+    let (frame, time_cluster) = add_initialized_frame(&mut program_test, &base, 0, 0, &[]);
+    let mut context = program_test.start_with_context().await;
+
+    let result = process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::change_color_region(
+            &program_id(),
+            &base,
+            &frame,
+            &time_cluster,
+            &owner.pubkey(),
+            SPACE_X,
+            SPACE_Y,
+            WIDTH,
+            HEIGHT,
+            0,
+            &space_mints,
+            &[[1, 2, 3]; 5],
+        )],
+        &[&owner],
+    ).await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_change_color_region_rejects_neighborhood_boundary() {
+    let mut program_test = program_test();
+    let base = Pubkey::new_unique();
+    let owner = add_wallet(&mut program_test);
+    let spaces = [(199, 0), (200, 0)];
+    let space_mints = add_region(&mut program_test, &base, &owner, &spaces);
+    let (frame, time_cluster) = add_initialized_frame(&mut program_test, &base, 0, 0, &[]);
+    let mut context = program_test.start_with_context().await;
+
+    let result = process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::change_color_region(
+            &program_id(),
+            &base,
+            &frame,
+            &time_cluster,
+            &owner.pubkey(),
+            199,
+            0,
+            2,
+            1,
+            0,
+            &space_mints,
+            &[[1, 2, 3]; 2],
+        )],
+        &[&owner],
+    ).await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_change_color_region_rejects_account_count_mismatch() {
+    let mut program_test = program_test();
+    let base = Pubkey::new_unique();
+    let owner = add_wallet(&mut program_test);
+    let space_mints = add_region(&mut program_test, &base, &owner, &region());
+    let (frame, time_cluster) = add_initialized_frame(&mut program_test, &base, 0, 0, &[]);
+    let mut context = program_test.start_with_context().await;
+
+    // the last space of the region is left out of the accounts
+    let result = process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::change_color_region(
+            &program_id(),
+            &base,
+            &frame,
+            &time_cluster,
+            &owner.pubkey(),
+            SPACE_X,
+            SPACE_Y,
+            WIDTH,
+            HEIGHT,
+            0,
+            &space_mints[..space_mints.len() - 1],
+            &[[1, 2, 3]; (WIDTH * HEIGHT) as usize],
+        )],
+        &[&owner],
+    ).await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_change_color_region_rejects_region_past_the_edge() {
+    let mut program_test = program_test();
+    let base = Pubkey::new_unique();
+    let owner = add_wallet(&mut program_test);
+    let (frame, time_cluster) = add_initialized_frame(&mut program_test, &base, 0, 0, &[]);
+    let mut context = program_test.start_with_context().await;
+
+    // the builder can not describe a region overflowing i64, so the coordinates are swapped into its data
+    let mut ix = instruction::change_color_region(
+        &program_id(),
+        &base,
+        &frame,
+        &time_cluster,
+        &owner.pubkey(),
+        SPACE_X,
+        SPACE_Y,
+        2,
+        1,
+        0,
+        &[],
+        &[[1, 2, 3]; 2],
+    );
+    let args = instruction::ChangeColorRegionArgs {
+        space_x: i64::MAX,
+        space_y: SPACE_Y,
+        width: 2,
+        height: 1,
+        frame: 0,
+        payload: encode_run_length(&[[1, 2, 3]; 2]),
+    };
+    ix.data.truncate(1);
+    ix.data.extend(args.try_to_vec().unwrap());
+    let result = process_instructions(&mut context.banks_client, context.last_blockhash, &[ix], &[&owner]).await;
+    assert!(result.is_err());
+}
@@ -0,0 +1,260 @@
+// run against the BPF build with `cargo test-bpf`
+#![cfg(feature = "test-bpf")]
+
+mod utils;
+
+use borsh::BorshDeserialize;
+use extend_color::{
+    instruction,
+    pda::find_color_config_address,
+    state::{INACTIVITY_THRESHOLD_OWNER, NeighborhoodColorConfig},
+};
+use extend_common::utils::get_neighborhood_xy;
+use solana_program::pubkey::Pubkey;
+use solana_program_test::BanksClient;
+use solana_sdk::{hash::Hash, signature::{Keypair, Signer}};
+use utils::*;
+
+const SPACE_X: i64 = 17;
+const SPACE_Y: i64 = -250;
+
+#[allow(clippy::too_many_arguments)]
+// fee recipients are the neighborhood creator and treasury, only passed when fee_payer is not the owner
+async fn change_color(
+    banks_client: &mut BanksClient,
+    recent_blockhash: Hash,
+    base: &Pubkey,
+    frame: &Pubkey,
+    time_cluster: &Pubkey,
+    space_mint: &Pubkey,
+    owner: &Pubkey,
+    fee_payer: &Keypair,
+    fee_recipients: Option<(&Pubkey, &Pubkey)>,
+    r: u8,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let instruction = match fee_recipients {
+        Some((neighborhood_creator, treasury)) => instruction::change_color_paying_fee(
+            &program_id(),
+            base,
+            frame,
+            time_cluster,
+            space_mint,
+            owner,
+            &fee_payer.pubkey(),
+            neighborhood_creator,
+            treasury,
+            SPACE_X,
+            SPACE_Y,
+            0,
+            r,
+            0,
+            0,
+        ),
+        None => instruction::change_color(
+            &program_id(),
+            base,
+            frame,
+            time_cluster,
+            space_mint,
+            owner,
+            &fee_payer.pubkey(),
+            SPACE_X,
+            SPACE_Y,
+            0,
+            r,
+            0,
+            0,
+        ),
+    };
+    process_instructions(banks_client, recent_blockhash, &[instruction], &[fee_payer]).await
+}
+
+#[tokio::test]
+async fn test_set_color_config_creates_and_updates() {
+    let (neighborhood_x, neighborhood_y) = get_neighborhood_xy(SPACE_X, SPACE_Y);
+    let mut program_test = program_test();
+    let base = Pubkey::new_unique();
+    let creator = add_wallet(&mut program_test);
+    add_neighborhood_with_creator(&mut program_test, &base, &creator.pubkey(), neighborhood_x, neighborhood_y);
+    let mut context = program_test.start_with_context().await;
+    let (config, bump) = find_color_config_address(&program_id(), &base, neighborhood_x, neighborhood_y);
+
+    for (owner_threshold, arbitrary_threshold, fee) in [(100, 5, 0), (200, 10, 5000)] {
+        process_instructions(
+            &mut context.banks_client,
+            context.last_blockhash,
+            &[instruction::set_color_config(
+                &program_id(),
+                &base,
+                &creator.pubkey(),
+                neighborhood_x,
+                neighborhood_y,
+                owner_threshold,
+                arbitrary_threshold,
+                fee,
+            )],
+            &[&creator],
+        ).await.unwrap();
+
+        let data = get_account(&mut context.banks_client, &config).await.data;
+        let config_data = NeighborhoodColorConfig::deserialize(&mut &data[..]).unwrap();
+        assert_eq!(config_data.bump, bump);
+        assert_eq!(config_data.inactivity_threshold_owner, owner_threshold);
+        assert_eq!(config_data.inactivity_threshold_arbitrary, arbitrary_threshold);
+        assert_eq!(config_data.arbitrary_changer_fee, fee);
+    }
+}
+
+#[tokio::test]
+async fn test_set_color_config_requires_creator() {
+    let (neighborhood_x, neighborhood_y) = get_neighborhood_xy(SPACE_X, SPACE_Y);
+    let mut program_test = program_test();
+    let base = Pubkey::new_unique();
+    let other = add_wallet(&mut program_test);
+    add_neighborhood(&mut program_test, &base, neighborhood_x, neighborhood_y);
+    let mut context = program_test.start_with_context().await;
+
+    let result = process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::set_color_config(
+            &program_id(),
+            &base,
+            &other.pubkey(),
+            neighborhood_x,
+            neighborhood_y,
+            0,
+            0,
+            0,
+        )],
+        &[&other],
+    ).await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_change_color_owner_locks_space_with_default_threshold() {
+    let (neighborhood_x, neighborhood_y) = get_neighborhood_xy(SPACE_X, SPACE_Y);
+    let mut program_test = program_test();
+    let base = Pubkey::new_unique();
+    let owner = add_wallet(&mut program_test);
+    add_neighborhood(&mut program_test, &base, neighborhood_x, neighborhood_y);
+    let space_mint = add_space(&mut program_test, &base, &owner.pubkey(), SPACE_X, SPACE_Y);
+    let (frame, time_cluster) =
+        add_initialized_frame(&mut program_test, &base, neighborhood_x, neighborhood_y, &[(SPACE_X, SPACE_Y)]);
+    let mut context = program_test.start_with_context().await;
+
+    let before = get_unix_timestamp(&mut context.banks_client).await;
+    change_color(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &base,
+        &frame,
+        &time_cluster,
+        &space_mint,
+        &owner.pubkey(),
+        &owner,
+        None,
+        1,
+    ).await.unwrap();
+    let after = get_unix_timestamp(&mut context.banks_client).await;
+
+    let time_cluster_data = get_account(&mut context.banks_client, &time_cluster).await.data;
+    let threshold = time_cluster_threshold(&time_cluster_data, SPACE_X, SPACE_Y);
+    assert!(threshold >= before + INACTIVITY_THRESHOLD_OWNER as u64);
+    assert!(threshold <= after + INACTIVITY_THRESHOLD_OWNER as u64);
+}
+
+#[tokio::test]
+async fn test_change_color_non_owner_uses_config() {
+    let (neighborhood_x, neighborhood_y) = get_neighborhood_xy(SPACE_X, SPACE_Y);
+    let mut program_test = program_test();
+    let owner = add_wallet(&mut program_test);
+    let changer = add_wallet(&mut program_test);
+    let creator = add_wallet(&mut program_test);
+    let treasury = add_wallet(&mut program_test);
+    let base = add_base(&mut program_test, &treasury.pubkey(), 0);
+    add_neighborhood_with_creator(&mut program_test, &base, &creator.pubkey(), neighborhood_x, neighborhood_y);
+    add_color_config(&mut program_test, &base, neighborhood_x, neighborhood_y, 1_000_000, 1000, 5000);
+    let space_mint = add_space(&mut program_test, &base, &owner.pubkey(), SPACE_X, SPACE_Y);
+    let (frame, time_cluster) =
+        add_initialized_frame(&mut program_test, &base, neighborhood_x, neighborhood_y, &[(SPACE_X, SPACE_Y)]);
+    let mut context = program_test.start_with_context().await;
+
+    let owner_lamports = context.banks_client.get_balance(owner.pubkey()).await.unwrap();
+    let before = get_unix_timestamp(&mut context.banks_client).await;
+    change_color(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &base,
+        &frame,
+        &time_cluster,
+        &space_mint,
+        &owner.pubkey(),
+        &changer,
+        Some((&creator.pubkey(), &treasury.pubkey())),
+        1,
+    ).await.unwrap();
+    let after = get_unix_timestamp(&mut context.banks_client).await;
+
+    // fee comes from the config rather than ARBITRARY_CHANGER_FEE
+    assert_eq!(context.banks_client.get_balance(owner.pubkey()).await.unwrap(), owner_lamports + owner_share(5000));
+    assert_eq!(
+        context.banks_client.get_balance(creator.pubkey()).await.unwrap(),
+        WALLET_LAMPORTS + 5000 - owner_share(5000),
+    );
+    assert_eq!(context.banks_client.get_balance(treasury.pubkey()).await.unwrap(), WALLET_LAMPORTS);
+    let time_cluster_data = get_account(&mut context.banks_client, &time_cluster).await.data;
+    let threshold = time_cluster_threshold(&time_cluster_data, SPACE_X, SPACE_Y);
+    assert!(threshold >= before + 1000);
+    assert!(threshold <= after + 1000);
+
+    // space stays locked for other changers until the threshold passes
+    let result = change_color(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &base,
+        &frame,
+        &time_cluster,
+        &space_mint,
+        &owner.pubkey(),
+        &changer,
+        Some((&creator.pubkey(), &treasury.pubkey())),
+        2,
+    ).await;
+    assert!(result.is_err());
+    let frame_data = get_account(&mut context.banks_client, &frame).await.data;
+    assert_eq!(frame_color(&frame_data, SPACE_X, SPACE_Y), [1, 0, 0]);
+}
+
+#[tokio::test]
+async fn test_change_color_rejects_other_time_cluster() {
+    let (neighborhood_x, neighborhood_y) = get_neighborhood_xy(SPACE_X, SPACE_Y);
+    let mut program_test = program_test();
+    let owner = add_wallet(&mut program_test);
+    let changer = add_wallet(&mut program_test);
+    let creator = add_wallet(&mut program_test);
+    let treasury = add_wallet(&mut program_test);
+    let base = add_base(&mut program_test, &treasury.pubkey(), 0);
+    add_neighborhood_with_creator(&mut program_test, &base, &creator.pubkey(), neighborhood_x, neighborhood_y);
+    let space_mint = add_space(&mut program_test, &base, &owner.pubkey(), SPACE_X, SPACE_Y);
+    let (frame, _) = add_initialized_frame(&mut program_test, &base, neighborhood_x, neighborhood_y, &[]);
+
+    // a fresh time cluster would read every space as unlocked
+    let other_time_cluster = add_time_cluster_account(&mut program_test);
+    let mut context = program_test.start_with_context().await;
+
+    let result = change_color(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &base,
+        &frame,
+        &other_time_cluster,
+        &space_mint,
+        &owner.pubkey(),
+        &changer,
+        Some((&creator.pubkey(), &treasury.pubkey())),
+        1,
+    ).await;
+    assert!(result.is_err());
+}
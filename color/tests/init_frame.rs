@@ -0,0 +1,163 @@
+// run against the BPF build with `cargo test-bpf`
+#![cfg(feature = "test-bpf")]
+
+mod utils;
+
+use std::mem::size_of;
+use extend_color::{
+    instruction,
+    pda::{find_frame_base_address, find_frame_pointer_address},
+    state::{MAX_FRAMES, NEIGHBORHOOD_SIZE, NeighborhoodFrameBase, NeighborhoodFramePointer},
+};
+use solana_program::{borsh::try_from_slice_unchecked, pubkey::Pubkey};
+use solana_sdk::signature::Signer;
+use utils::*;
+
+const NEIGHBORHOOD_X: i64 = 1;
+const NEIGHBORHOOD_Y: i64 = -2;
+
+#[tokio::test]
+async fn test_init_frame_up_to_max_frames() {
+    let mut program_test = program_test();
+    let base = Pubkey::new_unique();
+    add_neighborhood(&mut program_test, &base, NEIGHBORHOOD_X, NEIGHBORHOOD_Y);
+    let time_cluster = add_time_cluster_account(&mut program_test);
+    let frames: Vec<Pubkey> = (0..=MAX_FRAMES).map(|_| add_frame_account(&mut program_test)).collect();
+    let mut context = program_test.start_with_context().await;
+
+    for (i, frame) in frames[..MAX_FRAMES as usize].iter().enumerate() {
+        process_instructions(
+            &mut context.banks_client,
+            context.last_blockhash,
+            &[instruction::init_frame(
+                &program_id(),
+                &base,
+                frame,
+                &time_cluster,
+                &context.payer.pubkey(),
+                NEIGHBORHOOD_X,
+                NEIGHBORHOOD_Y,
+                i as u64,
+            )],
+            &[&context.payer],
+        ).await.unwrap();
+    }
+
+    let banks_client = &mut context.banks_client;
+    let (frame_base, _) = find_frame_base_address(&program_id(), &base, NEIGHBORHOOD_X, NEIGHBORHOOD_Y);
+    let frame_base_data: NeighborhoodFrameBase =
+        try_from_slice_unchecked(&get_account(banks_client, &frame_base).await.data).unwrap();
+    assert_eq!(frame_base_data.length, MAX_FRAMES);
+    assert_eq!(frame_base_data.time_cluster_account, time_cluster);
+
+    for (i, frame) in frames[..MAX_FRAMES as usize].iter().enumerate() {
+        let (frame_pointer, _) =
+            find_frame_pointer_address(&program_id(), &base, NEIGHBORHOOD_X, NEIGHBORHOOD_Y, i as u64);
+        let frame_pointer_data: NeighborhoodFramePointer =
+            try_from_slice_unchecked(&get_account(banks_client, &frame_pointer).await.data).unwrap();
+        assert_eq!(frame_pointer_data.framekey, *frame);
+
+        let frame_data = get_account(banks_client, frame).await.data;
+        let start_x = 3 * NEIGHBORHOOD_SIZE * NEIGHBORHOOD_SIZE;
+        assert!(frame_data[..start_x].iter().all(|&val| val == 0));
+        assert_eq!(frame_data[start_x..start_x + 8], NEIGHBORHOOD_X.to_le_bytes());
+        assert_eq!(frame_data[start_x + 8..start_x + 16], NEIGHBORHOOD_Y.to_le_bytes());
+        assert_eq!(frame_data[start_x + 16], 1);
+    }
+
+    // every space starts locked for non-owners
+    let time_cluster_data = get_account(banks_client, &time_cluster).await.data;
+    let start_x = size_of::<u64>() * NEIGHBORHOOD_SIZE * NEIGHBORHOOD_SIZE;
+    assert!(time_cluster_data[..start_x].iter().all(|&val| val == 255));
+    assert_eq!(time_cluster_data[start_x..start_x + 8], NEIGHBORHOOD_X.to_le_bytes());
+    assert_eq!(time_cluster_data[start_x + 8..start_x + 16], NEIGHBORHOOD_Y.to_le_bytes());
+    assert_eq!(time_cluster_data[start_x + 16], 1);
+
+    let result = process_instructions(
+        banks_client,
+        context.last_blockhash,
+        &[instruction::init_frame(
+            &program_id(),
+            &base,
+            &frames[MAX_FRAMES as usize],
+            &time_cluster,
+            &context.payer.pubkey(),
+            NEIGHBORHOOD_X,
+            NEIGHBORHOOD_Y,
+            MAX_FRAMES,
+        )],
+        &[&context.payer],
+    ).await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_init_frame_rejects_other_time_cluster() {
+    let mut program_test = program_test();
+    let base = Pubkey::new_unique();
+    add_neighborhood(&mut program_test, &base, NEIGHBORHOOD_X, NEIGHBORHOOD_Y);
+    let time_cluster = add_time_cluster_account(&mut program_test);
+    let other_time_cluster = add_time_cluster_account(&mut program_test);
+    let frames = [add_frame_account(&mut program_test), add_frame_account(&mut program_test)];
+    let mut context = program_test.start_with_context().await;
+
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::init_frame(
+            &program_id(),
+            &base,
+            &frames[0],
+            &time_cluster,
+            &context.payer.pubkey(),
+            NEIGHBORHOOD_X,
+            NEIGHBORHOOD_Y,
+            0,
+        )],
+        &[&context.payer],
+    ).await.unwrap();
+
+    let result = process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::init_frame(
+            &program_id(),
+            &base,
+            &frames[1],
+            &other_time_cluster,
+            &context.payer.pubkey(),
+            NEIGHBORHOOD_X,
+            NEIGHBORHOOD_Y,
+            1,
+        )],
+        &[&context.payer],
+    ).await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_init_frame_requires_neighborhood() {
+    let mut program_test = program_test();
+    let base = Pubkey::new_unique();
+    add_neighborhood(&mut program_test, &base, NEIGHBORHOOD_X, NEIGHBORHOOD_Y);
+    let time_cluster = add_time_cluster_account(&mut program_test);
+    let frame = add_frame_account(&mut program_test);
+    let mut context = program_test.start_with_context().await;
+
+    let result = process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::init_frame(
+            &program_id(),
+            &base,
+            &frame,
+            &time_cluster,
+            &context.payer.pubkey(),
+            NEIGHBORHOOD_X + 1,
+            NEIGHBORHOOD_Y,
+            0,
+        )],
+        &[&context.payer],
+    ).await;
+    assert!(result.is_err());
+}
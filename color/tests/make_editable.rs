@@ -0,0 +1,193 @@
+// run against the BPF build with `cargo test-bpf`
+#![cfg(feature = "test-bpf")]
+
+mod utils;
+
+use extend_color::{instruction, state::ARBITRARY_CHANGER_FEE};
+use solana_program::pubkey::Pubkey;
+use solana_program_test::ProgramTestContext;
+use solana_sdk::signature::{Keypair, Signer};
+use utils::*;
+
+const SPACE_X: i64 = 42;
+const SPACE_Y: i64 = -3;
+
+struct EditableFixture {
+    context: ProgramTestContext,
+    base: Pubkey,
+    owner: Keypair,
+    changer: Keypair,
+    creator: Keypair,
+    treasury: Keypair,
+    space_mint: Pubkey,
+    frame: Pubkey,
+    time_cluster: Pubkey,
+}
+
+async fn setup_editable() -> EditableFixture {
+    let mut program_test = program_test();
+    let owner = add_wallet(&mut program_test);
+    let changer = add_wallet(&mut program_test);
+    let creator = add_wallet(&mut program_test);
+    let treasury = add_wallet(&mut program_test);
+    let base = add_base(&mut program_test, &treasury.pubkey(), 0);
+    add_neighborhood_with_creator(&mut program_test, &base, &creator.pubkey(), 0, -1);
+    let space_mint = add_space(&mut program_test, &base, &owner.pubkey(), SPACE_X, SPACE_Y);
+    let (frame, time_cluster) = add_initialized_frame(&mut program_test, &base, 0, -1, &[]);
+    let context = program_test.start_with_context().await;
+    EditableFixture {
+        context,
+        base,
+        owner,
+        changer,
+        creator,
+        treasury,
+        space_mint,
+        frame,
+        time_cluster,
+    }
+}
+
+async fn make_editable(fixture: &mut EditableFixture, editable: bool) -> Result<(), Box<dyn std::error::Error>> {
+    process_instructions(
+        &mut fixture.context.banks_client,
+        fixture.context.last_blockhash,
+        &[instruction::make_editable(
+            &program_id(),
+            &fixture.base,
+            &fixture.time_cluster,
+            &fixture.space_mint,
+            &fixture.owner.pubkey(),
+            SPACE_X,
+            SPACE_Y,
+            editable,
+        )],
+        &[&fixture.owner],
+    ).await
+}
+
+async fn change_color_as_changer(fixture: &mut EditableFixture, r: u8) -> Result<(), Box<dyn std::error::Error>> {
+    process_instructions(
+        &mut fixture.context.banks_client,
+        fixture.context.last_blockhash,
+        &[instruction::change_color_paying_fee(
+            &program_id(),
+            &fixture.base,
+            &fixture.frame,
+            &fixture.time_cluster,
+            &fixture.space_mint,
+            &fixture.owner.pubkey(),
+            &fixture.changer.pubkey(),
+            &fixture.creator.pubkey(),
+            &fixture.treasury.pubkey(),
+            SPACE_X,
+            SPACE_Y,
+            0,
+            r,
+            0,
+            0,
+        )],
+        &[&fixture.changer],
+    ).await
+}
+
+async fn make_editable_brief(fixture: &mut EditableFixture, editable: bool) -> Result<(), Box<dyn std::error::Error>> {
+    process_instructions(
+        &mut fixture.context.banks_client,
+        fixture.context.last_blockhash,
+        &[instruction::make_editable_brief(
+            &program_id(),
+            &fixture.base,
+            &fixture.time_cluster,
+            &fixture.space_mint,
+            &fixture.owner.pubkey(),
+            SPACE_X as i16,
+            SPACE_Y as i16,
+            editable,
+        )],
+        &[&fixture.owner],
+    ).await
+}
+
+#[tokio::test]
+async fn test_make_editable_opens_and_closes_space() {
+    let mut fixture = setup_editable().await;
+    assert!(change_color_as_changer(&mut fixture, 1).await.is_err());
+
+    make_editable(&mut fixture, true).await.unwrap();
+    let time_cluster_data = get_account(&mut fixture.context.banks_client, &fixture.time_cluster).await.data;
+    assert!(time_cluster_threshold(&time_cluster_data, SPACE_X, SPACE_Y) < u64::MAX);
+    assert_eq!(time_cluster_threshold(&time_cluster_data, SPACE_X, SPACE_Y + 1), u64::MAX);
+
+    let owner_lamports = fixture.context.banks_client.get_balance(fixture.owner.pubkey()).await.unwrap();
+    change_color_as_changer(&mut fixture, 2).await.unwrap();
+    assert_eq!(
+        fixture.context.banks_client.get_balance(fixture.owner.pubkey()).await.unwrap(),
+        owner_lamports + owner_share(ARBITRARY_CHANGER_FEE),
+    );
+    let frame_data = get_account(&mut fixture.context.banks_client, &fixture.frame).await.data;
+    assert_eq!(frame_color(&frame_data, SPACE_X, SPACE_Y), [2, 0, 0]);
+
+    make_editable(&mut fixture, false).await.unwrap();
+    let time_cluster_data = get_account(&mut fixture.context.banks_client, &fixture.time_cluster).await.data;
+    assert_eq!(time_cluster_threshold(&time_cluster_data, SPACE_X, SPACE_Y), u64::MAX);
+    assert!(change_color_as_changer(&mut fixture, 3).await.is_err());
+}
+
+#[tokio::test]
+async fn test_make_editable_rejects_no_change() {
+    let mut fixture = setup_editable().await;
+    assert!(make_editable(&mut fixture, false).await.is_err());
+    make_editable(&mut fixture, true).await.unwrap();
+    assert!(make_editable_brief(&mut fixture, true).await.is_err());
+}
+
+#[tokio::test]
+async fn test_make_editable_requires_owner() {
+    let mut fixture = setup_editable().await;
+    let result = process_instructions(
+        &mut fixture.context.banks_client,
+        fixture.context.last_blockhash,
+        &[instruction::make_editable(
+            &program_id(),
+            &fixture.base,
+            &fixture.time_cluster,
+            &fixture.space_mint,
+            &fixture.changer.pubkey(),
+            SPACE_X,
+            SPACE_Y,
+            true,
+        )],
+        &[&fixture.changer],
+    ).await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_make_editable_rejects_other_time_cluster() {
+    let mut program_test = program_test();
+    let base = Pubkey::new_unique();
+    let owner = add_wallet(&mut program_test);
+    add_neighborhood(&mut program_test, &base, 0, -1);
+    let space_mint = add_space(&mut program_test, &base, &owner.pubkey(), SPACE_X, SPACE_Y);
+    add_initialized_frame(&mut program_test, &base, 0, -1, &[]);
+    let other_time_cluster = add_time_cluster_account(&mut program_test);
+    let mut context = program_test.start_with_context().await;
+
+    let result = process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::make_editable(
+            &program_id(),
+            &base,
+            &other_time_cluster,
+            &space_mint,
+            &owner.pubkey(),
+            SPACE_X,
+            SPACE_Y,
+            true,
+        )],
+        &[&owner],
+    ).await;
+    assert!(result.is_err());
+}
@@ -0,0 +1,341 @@
+#![allow(dead_code)]
+
+use std::{convert::TryInto, mem::size_of, str::FromStr};
+use borsh::BorshSerialize;
+use extend_color::{
+    pda::{
+        find_color_config_address,
+        find_frame_base_address,
+        find_frame_pointer_address,
+        find_neighborhood_metadata_address,
+        find_rent_account_address,
+        find_space_metadata_address,
+    },
+    processor::Processor,
+    state::{
+        BPS_DENOMINATOR,
+        MARKETPLACE_FEE_BPS,
+        MAX_MARKETPLACE_FEE_BPS,
+        NEIGHBORHOOD_SIZE,
+        NEIGHBORHOOD_COLOR_CONFIG_RESERVE,
+        NEIGHBORHOOD_FRAME_BASE_RESERVE,
+        NEIGHBORHOOD_FRAME_POINTER_RESERVE,
+        NEIGHBORHOOD_METADATA_RESERVE,
+        RENT_ACCOUNT_RESERVE,
+        RENT_PID,
+        SPACE_METADATA_RESERVE,
+        SPACE_PID,
+        TIME_CLUSTER_RESERVE,
+        Base,
+        Frame,
+        NeighborhoodColorConfig,
+        NeighborhoodFrameBase,
+        NeighborhoodFramePointer,
+        NeighborhoodMetadata,
+        RentAccount,
+        SpaceMetadata,
+    },
+};
+use solana_program::{
+    clock::Clock,
+    program_option::COption,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_program,
+};
+use solana_program_test::{processor, BanksClient, ProgramTest};
+use solana_sdk::{
+    account::Account,
+    hash::Hash,
+    instruction::Instruction,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+use spl_associated_token_account::get_associated_token_address;
+
+pub const COLOR_PID: &str = "XCLReS3yMKtcHWJxW8HX8yr6YmY8rwTaS5NUmVk21mM";
+pub const WALLET_LAMPORTS: u64 = 10_000_000_000;
+
+pub fn program_id() -> Pubkey {
+    Pubkey::from_str(COLOR_PID).unwrap()
+}
+
+pub fn space_program_id() -> Pubkey {
+    Pubkey::from_str(SPACE_PID).unwrap()
+}
+
+pub fn program_test() -> ProgramTest {
+    ProgramTest::new("extend_color", program_id(), processor!(Processor::process))
+}
+
+pub fn add_wallet(program_test: &mut ProgramTest) -> Keypair {
+    let wallet = Keypair::new();
+    program_test.add_account(
+        wallet.pubkey(),
+        Account {
+            lamports: WALLET_LAMPORTS,
+            data: vec![],
+            owner: system_program::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+    wallet
+}
+
+fn add_data_account(program_test: &mut ProgramTest, address: Pubkey, owner: Pubkey, data: Vec<u8>) {
+    program_test.add_account(
+        address,
+        Account {
+            lamports: Rent::default().minimum_balance(data.len()),
+            data,
+            owner,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+}
+
+// base as written by the space program's InitBase and UpdateTreasury
+pub fn add_base(program_test: &mut ProgramTest, treasury: &Pubkey, treasury_fee_share_bps: u16) -> Pubkey {
+    let address = Pubkey::new_unique();
+    let data = Base {
+        neighborhood_count: 0,
+        authority: Pubkey::new_unique(),
+        authority_privileges: false,
+        max_marketplace_fee_bps: MAX_MARKETPLACE_FEE_BPS,
+        treasury: *treasury,
+        treasury_fee_share_bps,
+        max_marketplace_fee_bps_set: true,
+    }.try_to_vec().unwrap();
+    add_data_account(program_test, address, space_program_id(), data);
+    address
+}
+
+// what the owner keeps of a changer fee after the default marketplace fee
+pub fn owner_share(fee: u64) -> u64 {
+    fee - fee * MARKETPLACE_FEE_BPS as u64 / BPS_DENOMINATOR
+}
+
+// neighborhood metadata as written by the space program's InitNeighborhoodMetadata
+pub fn add_neighborhood(program_test: &mut ProgramTest, base: &Pubkey, neighborhood_x: i64, neighborhood_y: i64) {
+    add_neighborhood_with_creator(program_test, base, &Pubkey::new_unique(), neighborhood_x, neighborhood_y);
+}
+
+pub fn add_neighborhood_with_creator(
+    program_test: &mut ProgramTest,
+    base: &Pubkey,
+    creator: &Pubkey,
+    neighborhood_x: i64,
+    neighborhood_y: i64,
+) {
+    let (address, bump) = find_neighborhood_metadata_address(&space_program_id(), base, neighborhood_x, neighborhood_y);
+    let mut data = NeighborhoodMetadata {
+        bump,
+        creator: *creator,
+        candymachine_config: Pubkey::new_unique(),
+        candymachine_account: Pubkey::new_unique(),
+        neighborhood_name: [0; 64],
+        marketplace_fee_bps: MARKETPLACE_FEE_BPS,
+        marketplace_fee_bps_set: true,
+    }.try_to_vec().unwrap();
+    data.resize(NEIGHBORHOOD_METADATA_RESERVE, 0);
+    add_data_account(program_test, address, space_program_id(), data);
+}
+
+// space metadata as written by the space program's InitSpaceMetadata, with the space token held by owner
+pub fn add_space(program_test: &mut ProgramTest, base: &Pubkey, owner: &Pubkey, space_x: i64, space_y: i64) -> Pubkey {
+    let mint = Pubkey::new_unique();
+
+    let mut data = vec![0; spl_token::state::Mint::LEN];
+    spl_token::state::Mint {
+        mint_authority: COption::None,
+        supply: 1,
+        decimals: 0,
+        is_initialized: true,
+        freeze_authority: COption::None,
+    }.pack_into_slice(&mut data);
+    add_data_account(program_test, mint, spl_token::id(), data);
+
+    let mut data = vec![0; spl_token::state::Account::LEN];
+    spl_token::state::Account {
+        mint,
+        owner: *owner,
+        amount: 1,
+        delegate: COption::None,
+        state: spl_token::state::AccountState::Initialized,
+        is_native: COption::None,
+        delegated_amount: 0,
+        close_authority: COption::None,
+    }.pack_into_slice(&mut data);
+    add_data_account(program_test, get_associated_token_address(owner, &mint), spl_token::id(), data);
+
+    let (address, bump) = find_space_metadata_address(&space_program_id(), base, space_x, space_y);
+    let mut data = SpaceMetadata {
+        bump,
+        mint,
+        price: 0,
+        space_x,
+        space_y,
+        escrow_seller: Pubkey::default(),
+        payment_mint: Pubkey::default(),
+        listing_terms: false,
+    }.try_to_vec().unwrap();
+    data.resize(SPACE_METADATA_RESERVE, 0);
+    add_data_account(program_test, address, space_program_id(), data);
+
+    mint
+}
+
+// rent account as written by the rent program's AcceptRent, the space rented to lessee until rent_end
+pub fn add_rent_account(
+    program_test: &mut ProgramTest,
+    base: &Pubkey,
+    space_mint: &Pubkey,
+    lessee: &Pubkey,
+    space_x: i64,
+    space_y: i64,
+    rent_end: u64,
+) {
+    let rent_program_id = Pubkey::from_str(RENT_PID).unwrap();
+    let (address, bump) = find_rent_account_address(&rent_program_id, base, space_x, space_y);
+    let mut data = RentAccount {
+        bump,
+        price: 1,
+        min_duration: 0,
+        max_duration: u64::MAX,
+        max_timestamp: u64::MAX,
+        lister: Pubkey::new_unique(),
+        rent_end,
+        lessee: *lessee,
+        mint: *space_mint,
+        first_refusal_period: 0,
+    }.try_to_vec().unwrap();
+    data.resize(RENT_ACCOUNT_RESERVE, 0);
+    add_data_account(program_test, address, rent_program_id, data);
+}
+
+// uninitialized frame account, as allocated by the client before InitFrame
+pub fn add_frame_account(program_test: &mut ProgramTest) -> Pubkey {
+    let frame = Pubkey::new_unique();
+    add_data_account(program_test, frame, program_id(), vec![0; Frame::LEN]);
+    frame
+}
+
+// uninitialized time cluster account, as allocated by the client before the first InitFrame
+pub fn add_time_cluster_account(program_test: &mut ProgramTest) -> Pubkey {
+    let time_cluster = Pubkey::new_unique();
+    add_data_account(program_test, time_cluster, program_id(), vec![0; TIME_CLUSTER_RESERVE]);
+    time_cluster
+}
+
+// neighborhood with a single initialized frame, where every space is locked except the
+// (space_x, space_y) pairs in unlocked, whose inactivity threshold has already passed
+pub fn add_initialized_frame(
+    program_test: &mut ProgramTest,
+    base: &Pubkey,
+    neighborhood_x: i64,
+    neighborhood_y: i64,
+    unlocked: &[(i64, i64)],
+) -> (Pubkey, Pubkey) {
+    let frame = Pubkey::new_unique();
+    let time_cluster = Pubkey::new_unique();
+
+    let (address, bump) = find_frame_base_address(&program_id(), base, neighborhood_x, neighborhood_y);
+    let mut data = NeighborhoodFrameBase {
+        bump,
+        length: 1,
+        time_cluster_account: time_cluster,
+    }.try_to_vec().unwrap();
+    data.resize(NEIGHBORHOOD_FRAME_BASE_RESERVE, 0);
+    add_data_account(program_test, address, program_id(), data);
+
+    let (address, bump) = find_frame_pointer_address(&program_id(), base, neighborhood_x, neighborhood_y, 0);
+    let mut data = NeighborhoodFramePointer {
+        bump,
+        framekey: frame,
+    }.try_to_vec().unwrap();
+    data.resize(NEIGHBORHOOD_FRAME_POINTER_RESERVE, 0);
+    add_data_account(program_test, address, program_id(), data);
+
+    let mut data = vec![0; Frame::LEN];
+    let start_x = 3 * NEIGHBORHOOD_SIZE * NEIGHBORHOOD_SIZE;
+    data[start_x..start_x + 8].copy_from_slice(&neighborhood_x.to_le_bytes());
+    data[start_x + 8..start_x + 16].copy_from_slice(&neighborhood_y.to_le_bytes());
+    data[start_x + 16] = 1;
+    add_data_account(program_test, frame, program_id(), data);
+
+    let mut data = vec![255; TIME_CLUSTER_RESERVE];
+    let start_x = size_of::<u64>() * NEIGHBORHOOD_SIZE * NEIGHBORHOOD_SIZE;
+    data[start_x..start_x + 8].copy_from_slice(&neighborhood_x.to_le_bytes());
+    data[start_x + 8..start_x + 16].copy_from_slice(&neighborhood_y.to_le_bytes());
+    data[start_x + 16] = 1;
+    for &(space_x, space_y) in unlocked {
+        let idx = 8 * space_index(space_x, space_y);
+        data[idx..idx + 8].copy_from_slice(&0u64.to_le_bytes());
+    }
+    add_data_account(program_test, time_cluster, program_id(), data);
+
+    (frame, time_cluster)
+}
+
+// color config as written by SetColorConfig
+#[allow(clippy::too_many_arguments)]
+pub fn add_color_config(
+    program_test: &mut ProgramTest,
+    base: &Pubkey,
+    neighborhood_x: i64,
+    neighborhood_y: i64,
+    inactivity_threshold_owner: u64,
+    inactivity_threshold_arbitrary: u64,
+    arbitrary_changer_fee: u64,
+) {
+    let (address, bump) = find_color_config_address(&program_id(), base, neighborhood_x, neighborhood_y);
+    let mut data = NeighborhoodColorConfig {
+        bump,
+        inactivity_threshold_owner,
+        inactivity_threshold_arbitrary,
+        arbitrary_changer_fee,
+    }.try_to_vec().unwrap();
+    data.resize(NEIGHBORHOOD_COLOR_CONFIG_RESERVE, 0);
+    add_data_account(program_test, address, program_id(), data);
+}
+
+// position of a space within its neighborhood's frame and time cluster
+pub fn space_index(space_x: i64, space_y: i64) -> usize {
+    let n = NEIGHBORHOOD_SIZE as i64;
+    let x_mod = (space_x % n + n) % n;
+    let y_mod = (space_y % n + n) % n;
+    (n * x_mod + y_mod) as usize
+}
+
+pub fn frame_color(frame_data: &[u8], space_x: i64, space_y: i64) -> [u8; 3] {
+    let idx = 3 * space_index(space_x, space_y);
+    frame_data[idx..idx + 3].try_into().unwrap()
+}
+
+pub fn time_cluster_threshold(time_cluster_data: &[u8], space_x: i64, space_y: i64) -> u64 {
+    let idx = 8 * space_index(space_x, space_y);
+    u64::from_le_bytes(time_cluster_data[idx..idx + 8].try_into().unwrap())
+}
+
+pub async fn process_instructions(
+    banks_client: &mut BanksClient,
+    recent_blockhash: Hash,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut transaction = Transaction::new_with_payer(instructions, Some(&signers[0].pubkey()));
+    transaction.sign(signers, recent_blockhash);
+    banks_client.process_transaction(transaction).await?;
+    Ok(())
+}
+
+pub async fn get_unix_timestamp(banks_client: &mut BanksClient) -> u64 {
+    banks_client.get_sysvar::<Clock>().await.unwrap().unix_timestamp as u64
+}
+
+pub async fn get_account(banks_client: &mut BanksClient, address: &Pubkey) -> Account {
+    banks_client.get_account(*address).await.unwrap().unwrap()
+}
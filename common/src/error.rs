@@ -35,6 +35,10 @@ pub enum CustomError {
     InvalidListing,
     #[error("ColorClusterAlreadyInitialized")]
     ColorClusterAlreadyInitialized,
+    #[error("FeeAboveCap")]
+    FeeAboveCap,
+    #[error("ListingExpired")]
+    ListingExpired,
 }
 
 impl From<CustomError> for ProgramError {
@@ -0,0 +1,6 @@
+pub mod error;
+pub mod pda;
+pub mod state;
+pub mod utils;
+#[cfg(feature = "validation")]
+pub mod validation_utils;
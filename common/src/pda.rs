@@ -0,0 +1,316 @@
+use solana_program::pubkey::{Pubkey, PubkeyError};
+
+use crate::state::{
+    AUCTION_SEED,
+    BID_SEED,
+    LISTING_SEED,
+    METAPLEX_METADATA_SEED,
+    NEIGHBORHOOD_LIST_SEED,
+    NEIGHBORHOOD_METADATA_SEED,
+    RENT_ACCOUNT_SEED,
+    SELL_DELEGATE_SEED,
+    SPACE_ESCROW_SEED,
+    SPACE_METADATA_SEED,
+    VOUCHER_MINT_SEED,
+    VOUCHER_SINK_SEED,
+};
+
+pub fn find_neighborhood_list_address(program_id: &Pubkey, base: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[&base.to_bytes(), NEIGHBORHOOD_LIST_SEED], program_id)
+}
+
+pub fn create_neighborhood_list_address(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    bump: u8,
+) -> Result<Pubkey, PubkeyError> {
+    Pubkey::create_program_address(&[&base.to_bytes(), NEIGHBORHOOD_LIST_SEED, &[bump]], program_id)
+}
+
+pub fn find_neighborhood_metadata_address(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    neighborhood_x: i64,
+    neighborhood_y: i64,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            &base.to_bytes(),
+            NEIGHBORHOOD_METADATA_SEED,
+            &neighborhood_x.to_le_bytes(),
+            &neighborhood_y.to_le_bytes(),
+        ],
+        program_id,
+    )
+}
+
+pub fn create_neighborhood_metadata_address(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    neighborhood_x: i64,
+    neighborhood_y: i64,
+    bump: u8,
+) -> Result<Pubkey, PubkeyError> {
+    Pubkey::create_program_address(
+        &[
+            &base.to_bytes(),
+            NEIGHBORHOOD_METADATA_SEED,
+            &neighborhood_x.to_le_bytes(),
+            &neighborhood_y.to_le_bytes(),
+            &[bump],
+        ],
+        program_id,
+    )
+}
+
+pub fn find_space_metadata_address(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    space_x: i64,
+    space_y: i64,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            &base.to_bytes(),
+            SPACE_METADATA_SEED,
+            &space_x.to_le_bytes(),
+            &space_y.to_le_bytes(),
+        ],
+        program_id,
+    )
+}
+
+pub fn create_space_metadata_address(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    space_x: i64,
+    space_y: i64,
+    bump: u8,
+) -> Result<Pubkey, PubkeyError> {
+    Pubkey::create_program_address(
+        &[
+            &base.to_bytes(),
+            SPACE_METADATA_SEED,
+            &space_x.to_le_bytes(),
+            &space_y.to_le_bytes(),
+            &[bump],
+        ],
+        program_id,
+    )
+}
+
+pub fn find_sell_delegate_address(program_id: &Pubkey, base: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[&base.to_bytes(), SELL_DELEGATE_SEED], program_id)
+}
+
+// token account holding an escrowed space, owned by the sell delegate
+pub fn find_space_escrow_address(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    space_x: i64,
+    space_y: i64,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            &base.to_bytes(),
+            SPACE_ESCROW_SEED,
+            &space_x.to_le_bytes(),
+            &space_y.to_le_bytes(),
+        ],
+        program_id,
+    )
+}
+
+pub fn find_bid_address(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    space_x: i64,
+    space_y: i64,
+    bidder: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            &base.to_bytes(),
+            BID_SEED,
+            &space_x.to_le_bytes(),
+            &space_y.to_le_bytes(),
+            &bidder.to_bytes(),
+        ],
+        program_id,
+    )
+}
+
+pub fn create_bid_address(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    space_x: i64,
+    space_y: i64,
+    bidder: &Pubkey,
+    bump: u8,
+) -> Result<Pubkey, PubkeyError> {
+    Pubkey::create_program_address(
+        &[
+            &base.to_bytes(),
+            BID_SEED,
+            &space_x.to_le_bytes(),
+            &space_y.to_le_bytes(),
+            &bidder.to_bytes(),
+            &[bump],
+        ],
+        program_id,
+    )
+}
+
+pub fn find_auction_address(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    space_x: i64,
+    space_y: i64,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            &base.to_bytes(),
+            AUCTION_SEED,
+            &space_x.to_le_bytes(),
+            &space_y.to_le_bytes(),
+        ],
+        program_id,
+    )
+}
+
+pub fn create_auction_address(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    space_x: i64,
+    space_y: i64,
+    bump: u8,
+) -> Result<Pubkey, PubkeyError> {
+    Pubkey::create_program_address(
+        &[
+            &base.to_bytes(),
+            AUCTION_SEED,
+            &space_x.to_le_bytes(),
+            &space_y.to_le_bytes(),
+            &[bump],
+        ],
+        program_id,
+    )
+}
+
+pub fn find_listing_address(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    space_x: i64,
+    space_y: i64,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            &base.to_bytes(),
+            LISTING_SEED,
+            &space_x.to_le_bytes(),
+            &space_y.to_le_bytes(),
+        ],
+        program_id,
+    )
+}
+
+pub fn create_listing_address(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    space_x: i64,
+    space_y: i64,
+    bump: u8,
+) -> Result<Pubkey, PubkeyError> {
+    Pubkey::create_program_address(
+        &[
+            &base.to_bytes(),
+            LISTING_SEED,
+            &space_x.to_le_bytes(),
+            &space_y.to_le_bytes(),
+            &[bump],
+        ],
+        program_id,
+    )
+}
+
+pub fn find_rent_account_address(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    space_x: i64,
+    space_y: i64,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            &base.to_bytes(),
+            RENT_ACCOUNT_SEED,
+            &space_x.to_le_bytes(),
+            &space_y.to_le_bytes(),
+        ],
+        program_id,
+    )
+}
+
+pub fn create_rent_account_address(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    space_x: i64,
+    space_y: i64,
+    bump: u8,
+) -> Result<Pubkey, PubkeyError> {
+    Pubkey::create_program_address(
+        &[
+            &base.to_bytes(),
+            RENT_ACCOUNT_SEED,
+            &space_x.to_le_bytes(),
+            &space_y.to_le_bytes(),
+            &[bump],
+        ],
+        program_id,
+    )
+}
+
+pub fn find_voucher_mint_address(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    neighborhood_x: i64,
+    neighborhood_y: i64,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            &base.to_bytes(),
+            VOUCHER_MINT_SEED,
+            &neighborhood_x.to_le_bytes(),
+            &neighborhood_y.to_le_bytes(),
+        ],
+        program_id,
+    )
+}
+
+pub fn find_voucher_sink_address(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    neighborhood_x: i64,
+    neighborhood_y: i64,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            &base.to_bytes(),
+            VOUCHER_SINK_SEED,
+            &neighborhood_x.to_le_bytes(),
+            &neighborhood_y.to_le_bytes(),
+        ],
+        program_id,
+    )
+}
+
+// metaplex metadata of a mint, program_id is the metaplex token metadata program
+pub fn find_metaplex_metadata_address(program_id: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            METAPLEX_METADATA_SEED,
+            &program_id.to_bytes(),
+            &mint.to_bytes(),
+        ],
+        program_id,
+    )
+}
@@ -0,0 +1,216 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::pubkey::Pubkey;
+use std::mem::size_of;
+
+pub const NEIGHBORHOOD_SIZE: usize = 200;
+// marketplace fees are in basis points of the sale price
+pub const BPS_DENOMINATOR: u64 = 10_000;
+pub const MARKETPLACE_FEE_BPS: u16 = 100;
+pub const MAX_MARKETPLACE_FEE_BPS: u16 = 1_000;
+pub const EXTEND_TOKEN_MINT: &str = "PLACEHOLDER";
+pub const NEIGHBORHOOD_METADATA_SEED: &[u8] = b"neighborhood_metadata";
+pub const NEIGHBORHOOD_LIST_SEED: &[u8] = b"neighborhood_list";
+pub const VOUCHER_MINT_SEED: &[u8] = b"voucher_mint";
+pub const VOUCHER_SINK_SEED: &[u8] = b"voucher_sink";
+pub const SPACE_METADATA_SEED: &[u8] = b"space_metadata";
+pub const SELL_DELEGATE_SEED: &[u8] = b"sell_delegate";
+pub const SPACE_ESCROW_SEED: &[u8] = b"space_escrow";
+pub const BID_SEED: &[u8] = b"bid";
+pub const AUCTION_SEED: &[u8] = b"auction";
+pub const LISTING_SEED: &[u8] = b"listing";
+pub const RENT_ACCOUNT_SEED: &[u8] = b"rent_account";
+// seed of metaplex token metadata accounts, derived by the metaplex token metadata program
+pub const METAPLEX_METADATA_SEED: &[u8] = b"metadata";
+
+pub const SPACE_PID: &str = "XSPCZghPXkWTWpvrfQ34Szpx3rwmUjsxebRFf5ckbMD";
+pub const RENT_PID: &str = "XRNTtrxNf3Y2pAyi2bKkngYpuRxRouTkTQ1bNro3KGx";
+
+pub const BASE_RESERVE: usize = 2048;
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct Base {
+    pub neighborhood_count: u64,
+    pub authority: Pubkey,
+    pub authority_privileges: bool,
+    // cap on every neighborhood's marketplace fee
+    pub max_marketplace_fee_bps: u16,
+    // protocol treasury and its share of every marketplace fee, the rest goes to the neighborhood creator
+    pub treasury: Pubkey,
+    pub treasury_fee_share_bps: u16,
+    // whether max_marketplace_fee_bps was written, bases initialized before the fee cap was stored read false
+    pub max_marketplace_fee_bps_set: bool,
+}
+impl Base {
+    pub const LEN: usize = size_of::<u64>() + size_of::<Pubkey>() + size_of::<bool>() + size_of::<u16>()
+        + size_of::<Pubkey>() + size_of::<u16>() + size_of::<bool>();
+
+    // bases initialized before the fee cap was stored are capped at MAX_MARKETPLACE_FEE_BPS
+    pub fn get_max_marketplace_fee_bps(&self) -> u16 {
+        if self.max_marketplace_fee_bps_set {
+            self.max_marketplace_fee_bps
+        }
+        else {
+            MAX_MARKETPLACE_FEE_BPS
+        }
+    }
+
+    // bases initialized before the treasury was stored read it as the default key, their treasury is the authority
+    // until UpdateTreasury sets one
+    pub fn get_treasury(&self) -> Pubkey {
+        if self.treasury == Pubkey::default() {
+            self.authority
+        }
+        else {
+            self.treasury
+        }
+    }
+}
+
+pub const MAX_NEIGHBORHOODS: usize = 8;
+pub const NEIGHBORHOOD_LIST_RESERVE: usize = 10240;
+// pub const MAX_NEIGHBORHOODS: usize = 0;
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct NeighborhoodList {
+    pub bump: u8,
+    pub neighborhoods_x: Vec<i64>,
+    pub neighborhoods_y: Vec<i64>,
+}
+
+pub const NEIGHBORHOOD_METADATA_RESERVE: usize = 512;
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct NeighborhoodMetadata {
+    pub bump: u8,
+    pub creator: Pubkey,
+    pub candymachine_config: Pubkey,
+    pub candymachine_account: Pubkey,
+    pub neighborhood_name: [u8; 64],
+    // fee paid to the creator on sales in the neighborhood
+    pub marketplace_fee_bps: u16,
+    // whether marketplace_fee_bps was written, neighborhoods initialized before the fee was stored read false
+    pub marketplace_fee_bps_set: bool,
+}
+
+impl NeighborhoodMetadata {
+    pub const LEN: usize = size_of::<u8>() + size_of::<Pubkey>() + size_of::<Pubkey>() + size_of::<Pubkey>() + 64*size_of::<u8>()
+        + size_of::<u16>() + size_of::<bool>();
+
+    // neighborhoods initialized before the fee was stored charge MARKETPLACE_FEE_BPS
+    pub fn get_marketplace_fee_bps(&self) -> u16 {
+        if self.marketplace_fee_bps_set {
+            self.marketplace_fee_bps
+        }
+        else {
+            MARKETPLACE_FEE_BPS
+        }
+    }
+}
+
+pub const SPACE_METADATA_RESERVE: usize = 128;
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct SpaceMetadata {
+    pub bump: u8,
+    pub mint: Pubkey,
+    pub price: u64,
+    pub space_x: i64,
+    pub space_y: i64,
+    // seller of an escrowed listing, default if the space is not in escrow
+    pub escrow_seller: Pubkey,
+    // mint the listing is priced in, default if the price is in lamports
+    pub payment_mint: Pubkey,
+    // listing is subject to the terms in the space's listing account, a dutch schedule or an expiry
+    pub listing_terms: bool,
+}
+
+impl SpaceMetadata {
+    pub const LEN: usize =
+        size_of::<u8>() + size_of::<Pubkey>() + size_of::<u64>() + size_of::<i64>() + size_of::<i64>()
+        + size_of::<Pubkey>() + size_of::<Pubkey>() + size_of::<bool>();
+}
+
+// SOL bid on a space, the bid account holds the price on top of its rent
+pub const BID_RESERVE: usize = 128;
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct Bid {
+    pub bump: u8,
+    pub bidder: Pubkey,
+    pub space_x: i64,
+    pub space_y: i64,
+    pub price: u64,
+}
+
+impl Bid {
+    pub const LEN: usize =
+        size_of::<u8>() + size_of::<Pubkey>() + size_of::<i64>() + size_of::<i64>() + size_of::<u64>();
+}
+
+// English auction of a space, the auction account holds the highest bid on top of its rent
+pub const AUCTION_RESERVE: usize = 128;
+// a bid landing this many seconds or less before the end pushes the end back to this many seconds out
+pub const AUCTION_EXTENSION_WINDOW: u64 = 300;
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct Auction {
+    pub bump: u8,
+    pub seller: Pubkey,
+    pub space_x: i64,
+    pub space_y: i64,
+    pub reserve_price: u64,
+    pub min_increment: u64,
+    pub end_time: u64,
+    // the seller with a zero bid until the first bid lands
+    pub highest_bidder: Pubkey,
+    pub highest_bid: u64,
+}
+
+impl Auction {
+    pub const LEN: usize = size_of::<u8>() + size_of::<Pubkey>() + size_of::<i64>() + size_of::<i64>()
+        + size_of::<u64>() + size_of::<u64>() + size_of::<u64>() + size_of::<Pubkey>() + size_of::<u64>();
+}
+
+// listing terms that do not fit in the space metadata, only read while it is flagged listing_terms
+pub const LISTING_RESERVE: usize = 128;
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct Listing {
+    pub bump: u8,
+    pub seller: Pubkey,
+    pub start_price: u64,
+    pub end_price: u64,
+    // price declines from start_price to end_price between the times, 0 end_time for a fixed price
+    pub start_time: u64,
+    pub end_time: u64,
+    // listing can not be accepted from this time on, 0 if it does not expire
+    pub expires_at: u64,
+}
+
+impl Listing {
+    pub const LEN: usize = size_of::<u8>() + size_of::<Pubkey>() + size_of::<u64>() + size_of::<u64>()
+        + size_of::<u64>() + size_of::<u64>() + size_of::<u64>();
+}
+
+// rent listing of a space and its current rental, written by the rent program
+pub const RENT_ACCOUNT_RESERVE: usize = 200;
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct RentAccount {
+    pub bump: u8,
+    pub price: u64,
+    pub min_duration: u64,
+    pub max_duration: u64,
+    pub max_timestamp: u64,
+    pub lister: Pubkey,
+    pub rent_end: u64,
+    pub lessee: Pubkey,
+    pub mint: Pubkey,
+    // how long after a rental ends only its lessee can rent the space again, through ExtendRent
+    pub first_refusal_period: u64,
+}
+
+impl RentAccount {
+    pub const LEN: usize = size_of::<u8>() + size_of::<u64>() + size_of::<u64>() + size_of::<u64>() + size_of::<u64>()
+        + size_of::<Pubkey>() + size_of::<u64>() + size_of::<Pubkey>() + size_of::<Pubkey>() + size_of::<u64>();
+}
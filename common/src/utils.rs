@@ -0,0 +1,111 @@
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_instruction,
+    sysvar::rent::Rent,
+};
+use std::{cmp, convert::TryFrom};
+
+use crate::state::{BPS_DENOMINATOR, NEIGHBORHOOD_SIZE};
+
+pub fn floor_divide(x: i64, y: usize) -> i64 {
+    if x >= 0{
+        x / y as i64
+    }
+    else {
+        let mut ans = x / y as i64;
+        if x % y as i64 != 0{
+            ans -= 1;
+        }
+        ans
+    }
+}
+
+pub fn get_neighborhood_xy(x: i64, y: i64) -> (i64, i64){
+    (floor_divide(x, NEIGHBORHOOD_SIZE), floor_divide(y, NEIGHBORHOOD_SIZE))
+}
+
+// marketplace fee on a sale at price, the neighborhood fee limited by the cap in base
+pub fn get_marketplace_fee(price: u64, fee_bps: u16, max_fee_bps: u16) -> Result<u64, ProgramError> {
+    let fee = (price as u128)
+        .checked_mul(cmp::min(fee_bps, max_fee_bps) as u128)
+        .and_then(|fee| fee.checked_div(BPS_DENOMINATOR as u128))
+        .ok_or(ProgramError::InvalidArgument)?;
+    u64::try_from(fee).map_err(|_| ProgramError::InvalidArgument)
+}
+
+// split a fee into (neighborhood creator share, treasury share)
+pub fn split_fee(fee: u64, treasury_fee_share_bps: u16) -> Result<(u64, u64), ProgramError> {
+    let treasury_fee = get_marketplace_fee(fee, treasury_fee_share_bps, BPS_DENOMINATOR as u16)?;
+    Ok((fee - treasury_fee, treasury_fee))
+}
+
+// price of a dutch listing at now, declining linearly from start_price to end_price over the schedule
+pub fn get_dutch_price(start_price: u64, end_price: u64, start_time: u64, end_time: u64, now: u64) -> u64 {
+    if now <= start_time || end_time <= start_time {
+        return start_price;
+    }
+    if now >= end_time {
+        return end_price;
+    }
+    let decline = (start_price.saturating_sub(end_price) as u128) * ((now - start_time) as u128)
+        / ((end_time - start_time) as u128);
+    start_price - decline as u64
+}
+
+// move lamports out of an account owned by the calling program
+pub fn transfer_lamports(from: &AccountInfo, to: &AccountInfo, amount: u64) -> ProgramResult {
+    **from.try_borrow_mut_lamports()? = from
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(ProgramError::InsufficientFunds)?;
+    **to.try_borrow_mut_lamports()? = to
+        .lamports()
+        .checked_add(amount)
+        .ok_or(ProgramError::InvalidArgument)?;
+    Ok(())
+}
+
+// drain an account owned by the calling program into destination and wipe its data
+pub fn close_program_account(account: &AccountInfo, destination: &AccountInfo) -> ProgramResult {
+    transfer_lamports(account, destination, account.lamports())?;
+    for val in account.data.borrow_mut().iter_mut() {
+        *val = 0;
+    }
+    Ok(())
+}
+
+// create the PDA account signed for by seeds with space bytes owned by owner, payer topping up its balance to
+// rent exemption, as create_account would fail on lamports anyone can send to the address beforehand
+pub fn create_pda_account<'a>(
+    payer: &AccountInfo<'a>,
+    account: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    space: usize,
+    owner: &Pubkey,
+    seeds: &[&[u8]],
+) -> ProgramResult {
+    let required_lamports = Rent::default()
+        .minimum_balance(space)
+        .max(1)
+        .saturating_sub(account.lamports());
+    if required_lamports > 0 {
+        invoke(
+            &system_instruction::transfer(payer.key, account.key, required_lamports),
+            &[payer.clone(), account.clone(), system_program.clone()],
+        )?;
+    }
+    invoke_signed(
+        &system_instruction::allocate(account.key, space as u64),
+        &[account.clone(), system_program.clone()],
+        &[seeds],
+    )?;
+    invoke_signed(
+        &system_instruction::assign(account.key, owner),
+        &[account.clone(), system_program.clone()],
+        &[seeds],
+    )
+}
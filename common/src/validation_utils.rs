@@ -1,5 +1,6 @@
 use solana_program::{
     account_info::AccountInfo,
+    borsh::try_from_slice_unchecked,
     entrypoint::ProgramResult,
     msg,
     program_error::ProgramError,
@@ -9,8 +10,13 @@ use solana_program::{
 use spl_associated_token_account::get_associated_token_address;
 use spl_token;
 use spl_token::state::Account;
+use std::str::FromStr;
 
-use super::error::CustomError;
+use super::{
+    error::CustomError,
+    pda::create_rent_account_address,
+    state::{RENT_PID, RentAccount},
+};
 
 pub fn assert_is_ata(ata: &AccountInfo, wallet: &Pubkey, mint: &Pubkey) -> ProgramResult {
     assert_owned_by(ata, &spl_token::id())?;
@@ -49,3 +55,18 @@ pub fn assert_owned_by(account: &AccountInfo, owner: &Pubkey) -> ProgramResult {
         Ok(())
     }
 }
+
+// deserialize and verify the rent program's rent account of a space
+pub fn assert_rent_account(
+    rent_account: &AccountInfo,
+    base: &Pubkey,
+    space_x: i64,
+    space_y: i64,
+) -> Result<RentAccount, ProgramError> {
+    let rent_program_id = Pubkey::from_str(RENT_PID).unwrap();
+    assert_owned_by(rent_account, &rent_program_id)?;
+    let rent_account_data: RentAccount = try_from_slice_unchecked(&rent_account.data.borrow())?;
+    let key = create_rent_account_address(&rent_program_id, base, space_x, space_y, rent_account_data.bump)?;
+    assert_keys_equal(key, *rent_account.key)?;
+    Ok(rent_account_data)
+}
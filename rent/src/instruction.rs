@@ -1,5 +1,19 @@
 use borsh::{BorshDeserialize, BorshSerialize};
-use solana_program::program_error::ProgramError;
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_program,
+};
+use spl_associated_token_account::get_associated_token_address;
+use std::str::FromStr;
+
+use extend_common::utils::get_neighborhood_xy;
+
+use crate::{
+    pda::{find_neighborhood_metadata_address, find_rent_account_address, find_rent_escrow_address, find_space_metadata_address},
+    state::SPACE_PID,
+};
 
 #[repr(C)]
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
@@ -11,6 +25,7 @@ pub struct SetRentArgs{
     pub max_duration: u64,
     pub max_timestamp: u64,
     pub create: bool,
+    pub first_refusal_period: u64,
 }
 
 #[repr(C)]
@@ -22,6 +37,36 @@ pub struct AcceptRentArgs {
     pub rent_time: u64,
 }
 
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct ExtendRentArgs {
+    pub space_x: i64,
+    pub space_y: i64,
+    pub price: u64,
+    pub rent_time: u64,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct ClaimRentArgs {
+    pub space_x: i64,
+    pub space_y: i64,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct EndRentEarlyArgs {
+    pub space_x: i64,
+    pub space_y: i64,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct ReclaimExpiredRentArgs {
+    pub space_x: i64,
+    pub space_y: i64,
+}
+
 pub enum RentInstruction {
 
     /*
@@ -29,7 +74,7 @@ pub enum RentInstruction {
     0. base
     1. space metadata
     2. [Writable] rent account
-    3. [Signer] lessor wallet
+    3. [Signer, Writable] lessor wallet
     4. ATA of lessor holding space
     5. system program
     */
@@ -44,8 +89,61 @@ pub enum RentInstruction {
     4. [Writable] lessor wallet
     5. ATA of lessor holding space
     6. system program
+    7. neighborhood metadata
+    8. [Writable] neighborhood creator
+    9. [Writable] treasury
+    10. [Writable] rent escrow, taken over from the previous rental if it expired without being settled
+    11. [Writable] lessor wallet of the previous rental, paid the rest of its rent when its escrow is taken over
+    12. [Writable] lessee wallet of the previous rental, refunded the escrow account rent when it is taken over
     */
     AcceptRent,
+
+    /*
+    Accounts expected:
+    0. base
+    1. [Writable] rent escrow
+    2. [Signer, Writable] lessor wallet
+    3. [Writable] lessee wallet
+    */
+    ClaimRent,
+
+    /*
+    Accounts expected:
+    0. base
+    1. [Writable] rent account
+    2. [Writable] rent escrow
+    3. [Signer, Writable] lessee wallet
+    4. [Writable] lessor wallet
+    */
+    EndRentEarly,
+
+    /*
+    Accounts expected:
+    0. base
+    1. [Writable] rent account
+    2. [Writable] rent escrow, settled if the lessor has not claimed all of the rent yet
+    3. [Writable] lessor wallet
+    4. [Writable] lessee wallet
+    */
+    ReclaimExpiredRent,
+
+    /*
+    Extend the current rental by rent_time, or renew it within the first refusal period after it ends
+
+    Accounts expected:
+    0. base
+    1. space metadata
+    2. [Writable] rent account
+    3. [Signer, Writable] lessee wallet
+    4. [Writable] lessor wallet
+    5. ATA of lessor holding space
+    6. system program
+    7. neighborhood metadata
+    8. [Writable] neighborhood creator
+    9. [Writable] treasury
+    10. [Writable] rent escrow
+    */
+    ExtendRent,
 }
 
 impl RentInstruction {
@@ -53,7 +151,330 @@ impl RentInstruction {
         Ok(match tag {
             0 => Self::SetRent,
             1 => Self::AcceptRent,
+            2 => Self::ClaimRent,
+            3 => Self::EndRentEarly,
+            4 => Self::ReclaimExpiredRent,
+            5 => Self::ExtendRent,
             _ => return Err(ProgramError::InvalidInstructionData),
         })
     }
+
+    pub fn pack(&self) -> u8 {
+        match self {
+            Self::SetRent => 0,
+            Self::AcceptRent => 1,
+            Self::ClaimRent => 2,
+            Self::EndRentEarly => 3,
+            Self::ReclaimExpiredRent => 4,
+            Self::ExtendRent => 5,
+        }
+    }
+}
+
+fn build_instruction<T: BorshSerialize>(
+    program_id: &Pubkey,
+    instruction: RentInstruction,
+    args: &T,
+    accounts: Vec<AccountMeta>,
+) -> Instruction {
+    let mut data = vec![instruction.pack()];
+    data.extend(args.try_to_vec().unwrap());
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    }
+}
+
+fn find_space_and_rent_accounts(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    space_x: i64,
+    space_y: i64,
+) -> (Pubkey, Pubkey) {
+    let (space_metadata, _) =
+        find_space_metadata_address(&Pubkey::from_str(SPACE_PID).unwrap(), base, space_x, space_y);
+    let (rent_account, _) = find_rent_account_address(program_id, base, space_x, space_y);
+    (space_metadata, rent_account)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn set_rent(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    space_mint: &Pubkey,
+    lessor: &Pubkey,
+    space_x: i64,
+    space_y: i64,
+    price: u64,
+    min_duration: u64,
+    max_duration: u64,
+    max_timestamp: u64,
+    create: bool,
+) -> Instruction {
+    set_rent_with_first_refusal(
+        program_id,
+        base,
+        space_mint,
+        lessor,
+        space_x,
+        space_y,
+        price,
+        min_duration,
+        max_duration,
+        max_timestamp,
+        create,
+        0,
+    )
+}
+
+// list for rent, leaving each lessee first_refusal_period after their rental ends to extend it
+#[allow(clippy::too_many_arguments)]
+pub fn set_rent_with_first_refusal(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    space_mint: &Pubkey,
+    lessor: &Pubkey,
+    space_x: i64,
+    space_y: i64,
+    price: u64,
+    min_duration: u64,
+    max_duration: u64,
+    max_timestamp: u64,
+    create: bool,
+    first_refusal_period: u64,
+) -> Instruction {
+    let (space_metadata, rent_account) =
+        find_space_and_rent_accounts(program_id, base, space_x, space_y);
+    build_instruction(
+        program_id,
+        RentInstruction::SetRent,
+        &SetRentArgs {
+            space_x,
+            space_y,
+            price,
+            min_duration,
+            max_duration,
+            max_timestamp,
+            create,
+            first_refusal_period,
+        },
+        vec![
+            AccountMeta::new_readonly(*base, false),
+            AccountMeta::new_readonly(space_metadata, false),
+            AccountMeta::new(rent_account, false),
+            AccountMeta::new(*lessor, true),
+            AccountMeta::new_readonly(get_associated_token_address(lessor, space_mint), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn accept_rent(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    space_mint: &Pubkey,
+    lessee: &Pubkey,
+    lessor: &Pubkey,
+    space_x: i64,
+    space_y: i64,
+    price: u64,
+    rent_time: u64,
+    neighborhood_creator: &Pubkey,
+    treasury: &Pubkey,
+) -> Instruction {
+    accept_rent_after(
+        program_id,
+        base,
+        space_mint,
+        lessee,
+        lessor,
+        space_x,
+        space_y,
+        price,
+        rent_time,
+        neighborhood_creator,
+        treasury,
+        lessor,
+        lessee,
+    )
+}
+
+// rent out the space after a rental between previous_lessor and previous_lessee, whose escrow is settled
+// here if it expired without being settled
+#[allow(clippy::too_many_arguments)]
+pub fn accept_rent_after(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    space_mint: &Pubkey,
+    lessee: &Pubkey,
+    lessor: &Pubkey,
+    space_x: i64,
+    space_y: i64,
+    price: u64,
+    rent_time: u64,
+    neighborhood_creator: &Pubkey,
+    treasury: &Pubkey,
+    previous_lessor: &Pubkey,
+    previous_lessee: &Pubkey,
+) -> Instruction {
+    let mut accounts =
+        rental_accounts(program_id, base, space_mint, lessee, lessor, space_x, space_y, neighborhood_creator, treasury);
+    accounts.push(AccountMeta::new(*previous_lessor, false));
+    accounts.push(AccountMeta::new(*previous_lessee, false));
+    build_instruction(
+        program_id,
+        RentInstruction::AcceptRent,
+        &AcceptRentArgs {
+            space_x,
+            space_y,
+            price,
+            rent_time,
+        },
+        accounts,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn extend_rent(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    space_mint: &Pubkey,
+    lessee: &Pubkey,
+    lessor: &Pubkey,
+    space_x: i64,
+    space_y: i64,
+    price: u64,
+    rent_time: u64,
+    neighborhood_creator: &Pubkey,
+    treasury: &Pubkey,
+) -> Instruction {
+    build_instruction(
+        program_id,
+        RentInstruction::ExtendRent,
+        &ExtendRentArgs {
+            space_x,
+            space_y,
+            price,
+            rent_time,
+        },
+        rental_accounts(program_id, base, space_mint, lessee, lessor, space_x, space_y, neighborhood_creator, treasury),
+    )
+}
+
+// accounts of AcceptRent and ExtendRent, which both pay rent into the escrow
+#[allow(clippy::too_many_arguments)]
+fn rental_accounts(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    space_mint: &Pubkey,
+    lessee: &Pubkey,
+    lessor: &Pubkey,
+    space_x: i64,
+    space_y: i64,
+    neighborhood_creator: &Pubkey,
+    treasury: &Pubkey,
+) -> Vec<AccountMeta> {
+    let (space_metadata, rent_account) =
+        find_space_and_rent_accounts(program_id, base, space_x, space_y);
+    let (neighborhood_x, neighborhood_y) = get_neighborhood_xy(space_x, space_y);
+    let (neighborhood_metadata, _) = find_neighborhood_metadata_address(
+        &Pubkey::from_str(SPACE_PID).unwrap(),
+        base,
+        neighborhood_x,
+        neighborhood_y,
+    );
+    vec![
+        AccountMeta::new_readonly(*base, false),
+        AccountMeta::new_readonly(space_metadata, false),
+        AccountMeta::new(rent_account, false),
+        AccountMeta::new(*lessee, true),
+        AccountMeta::new(*lessor, false),
+        AccountMeta::new_readonly(get_associated_token_address(lessor, space_mint), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(neighborhood_metadata, false),
+        AccountMeta::new(*neighborhood_creator, false),
+        AccountMeta::new(*treasury, false),
+        AccountMeta::new(find_rent_escrow_address(program_id, base, space_x, space_y).0, false),
+    ]
+}
+
+pub fn claim_rent(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    lessor: &Pubkey,
+    lessee: &Pubkey,
+    space_x: i64,
+    space_y: i64,
+) -> Instruction {
+    let (rent_escrow, _) = find_rent_escrow_address(program_id, base, space_x, space_y);
+    build_instruction(
+        program_id,
+        RentInstruction::ClaimRent,
+        &ClaimRentArgs {
+            space_x,
+            space_y,
+        },
+        vec![
+            AccountMeta::new_readonly(*base, false),
+            AccountMeta::new(rent_escrow, false),
+            AccountMeta::new(*lessor, true),
+            AccountMeta::new(*lessee, false),
+        ],
+    )
+}
+
+pub fn end_rent_early(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    lessee: &Pubkey,
+    lessor: &Pubkey,
+    space_x: i64,
+    space_y: i64,
+) -> Instruction {
+    let (rent_account, _) = find_rent_account_address(program_id, base, space_x, space_y);
+    let (rent_escrow, _) = find_rent_escrow_address(program_id, base, space_x, space_y);
+    build_instruction(
+        program_id,
+        RentInstruction::EndRentEarly,
+        &EndRentEarlyArgs {
+            space_x,
+            space_y,
+        },
+        vec![
+            AccountMeta::new_readonly(*base, false),
+            AccountMeta::new(rent_account, false),
+            AccountMeta::new(rent_escrow, false),
+            AccountMeta::new(*lessee, true),
+            AccountMeta::new(*lessor, false),
+        ],
+    )
+}
+
+pub fn reclaim_expired_rent(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    lessor: &Pubkey,
+    lessee: &Pubkey,
+    space_x: i64,
+    space_y: i64,
+) -> Instruction {
+    let (rent_account, _) = find_rent_account_address(program_id, base, space_x, space_y);
+    let (rent_escrow, _) = find_rent_escrow_address(program_id, base, space_x, space_y);
+    build_instruction(
+        program_id,
+        RentInstruction::ReclaimExpiredRent,
+        &ReclaimExpiredRentArgs {
+            space_x,
+            space_y,
+        },
+        vec![
+            AccountMeta::new_readonly(*base, false),
+            AccountMeta::new(rent_account, false),
+            AccountMeta::new(rent_escrow, false),
+            AccountMeta::new(*lessor, false),
+            AccountMeta::new(*lessee, false),
+        ],
+    )
 }
@@ -0,0 +1,41 @@
+use solana_program::pubkey::{Pubkey, PubkeyError};
+
+use crate::state::RENT_ESCROW_SEED;
+
+pub use extend_common::pda::*;
+
+pub fn find_rent_escrow_address(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    space_x: i64,
+    space_y: i64,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            &base.to_bytes(),
+            RENT_ESCROW_SEED,
+            &space_x.to_le_bytes(),
+            &space_y.to_le_bytes(),
+        ],
+        program_id,
+    )
+}
+
+pub fn create_rent_escrow_address(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    space_x: i64,
+    space_y: i64,
+    bump: u8,
+) -> Result<Pubkey, PubkeyError> {
+    Pubkey::create_program_address(
+        &[
+            &base.to_bytes(),
+            RENT_ESCROW_SEED,
+            &space_x.to_le_bytes(),
+            &space_y.to_le_bytes(),
+            &[bump],
+        ],
+        program_id,
+    )
+}
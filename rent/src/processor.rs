@@ -6,12 +6,17 @@ use solana_program::{
 
 use crate::{
     instruction::{
-        RentInstruction, SetRentArgs, AcceptRentArgs,
+        RentInstruction, SetRentArgs, AcceptRentArgs, ClaimRentArgs, EndRentEarlyArgs, ReclaimExpiredRentArgs, ExtendRentArgs,
     },
 };
 
 pub mod set_rent;
 pub mod accept_rent;
+pub mod claim_rent;
+pub mod end_rent_early;
+pub mod reclaim_expired_rent;
+pub mod extend_rent;
+pub mod processor_utils;
 
 pub struct Processor;
 impl Processor {
@@ -35,6 +40,26 @@ impl Processor {
                 msg!("Instruction: accepting rent offer");
                 accept_rent::process(program_id, accounts, &args)
             }
+            RentInstruction::ClaimRent => {
+                let args = ClaimRentArgs::try_from_slice(rest)?;
+                msg!("Instruction: claiming rent");
+                claim_rent::process(program_id, accounts, &args)
+            }
+            RentInstruction::EndRentEarly => {
+                let args = EndRentEarlyArgs::try_from_slice(rest)?;
+                msg!("Instruction: ending rent early");
+                end_rent_early::process(program_id, accounts, &args)
+            }
+            RentInstruction::ReclaimExpiredRent => {
+                let args = ReclaimExpiredRentArgs::try_from_slice(rest)?;
+                msg!("Instruction: reclaiming expired rent");
+                reclaim_expired_rent::process(program_id, accounts, &args)
+            }
+            RentInstruction::ExtendRent => {
+                let args = ExtendRentArgs::try_from_slice(rest)?;
+                msg!("Instruction: extending rent");
+                extend_rent::process(program_id, accounts, &args)
+            }
         }
     }
 }
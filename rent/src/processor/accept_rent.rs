@@ -9,25 +9,36 @@ use solana_program::{
     program_pack::Pack,
     pubkey::Pubkey,
     clock::Clock,
-    sysvar::{Sysvar},
+    sysvar::Sysvar,
     system_instruction,
     system_program,
 };
 use std::{str::FromStr, cmp::min};
 
 use spl_token;
+use extend_common::utils::get_neighborhood_xy;
 
 use crate::{
     error::CustomError,
     instruction::AcceptRentArgs,
+    pda::{create_neighborhood_metadata_address, create_rent_account_address, create_space_metadata_address},
+    processor::processor_utils::{
+        create_rent_escrow,
+        get_rent_escrow_data,
+        in_first_refusal_period,
+        log_rent_event,
+        pay_rent,
+        release_accrued_rent,
+    },
     state::{
-        SPACE_METADATA_SEED,
-        RENT_ACCOUNT_SEED,
+        Base,
+        NeighborhoodMetadata,
         RentAccount,
+        RentEscrow,
         SpaceMetadata,
         SPACE_PID,
     },
-    validation_utils::{assert_is_ata, assert_keys_equal},
+    validation_utils::{assert_is_ata, assert_keys_equal, assert_owned_by},
 };
 
 pub fn process(
@@ -43,6 +54,12 @@ pub fn process(
     let lessor = next_account_info(account_info_iter)?;
     let ata_space = next_account_info(account_info_iter)?;
     let system_program = next_account_info(account_info_iter)?;
+    let neighborhood_metadata = next_account_info(account_info_iter)?;
+    let neighborhood_creator = next_account_info(account_info_iter)?;
+    let treasury = next_account_info(account_info_iter)?;
+    let rent_escrow = next_account_info(account_info_iter)?;
+    let previous_lessor = next_account_info(account_info_iter)?;
+    let previous_lessee = next_account_info(account_info_iter)?;
 
     if !lessee.is_signer {
         return Err(ProgramError::MissingRequiredSignature);
@@ -59,19 +76,39 @@ pub fn process(
     assert_keys_equal(system_program::id(), *system_program.key)?;
 
 
+    //deserialize and verify base
+    let space_program_id = Pubkey::from_str(SPACE_PID).unwrap();
+    assert_owned_by(base, &space_program_id)?;
+    let base_data: Base = try_from_slice_unchecked(&base.data.borrow())?;
+
     //deserialize and verify space metadata
     let space_metadata_data: SpaceMetadata = try_from_slice_unchecked(&space_metadata.data.borrow_mut())?;
 
-    let seeds_space_metadata = &[
-        &base.key.to_bytes(),
-        SPACE_METADATA_SEED,
-        &args.space_x.to_le_bytes(),
-        &args.space_y.to_le_bytes(),
-        &[space_metadata_data.bump],
-    ];
-    let key = Pubkey::create_program_address(seeds_space_metadata, &Pubkey::from_str(SPACE_PID).unwrap())?;
+    let key = create_space_metadata_address(
+        &space_program_id,
+        base.key,
+        args.space_x,
+        args.space_y,
+        space_metadata_data.bump,
+    )?;
     assert_keys_equal(key, *space_metadata.key)?;
 
+    //deserialize and verify neighborhood metadata
+    let neighborhood_metadata_data: NeighborhoodMetadata = try_from_slice_unchecked(&neighborhood_metadata.data.borrow())?;
+    let (neighborhood_x, neighborhood_y) = get_neighborhood_xy(args.space_x, args.space_y);
+    let key = create_neighborhood_metadata_address(
+        &space_program_id,
+        base.key,
+        neighborhood_x,
+        neighborhood_y,
+        neighborhood_metadata_data.bump,
+    )?;
+    assert_keys_equal(key, *neighborhood_metadata.key)?;
+
+    // check neighborhood creator and treasury are passed in correctly
+    assert_keys_equal(neighborhood_metadata_data.creator, *neighborhood_creator.key)?;
+    assert_keys_equal(base_data.get_treasury(), *treasury.key)?;
+
 
     //check ATAs
     assert_is_ata(ata_space, lessor.key, &space_metadata_data.mint)?;
@@ -86,14 +123,13 @@ pub fn process(
 
     // deserialize and verify rent account
     let mut rent_account_data: RentAccount = try_from_slice_unchecked(&rent_account.data.borrow_mut())?;
-    let seeds_rent_account = &[
-        &base.key.to_bytes(),
-        RENT_ACCOUNT_SEED,
-        &args.space_x.to_le_bytes(),
-        &args.space_y.to_le_bytes(),
-        &[rent_account_data.bump],
-    ];
-    let key = Pubkey::create_program_address(seeds_rent_account, program_id)?;
+    let key = create_rent_account_address(
+        program_id,
+        base.key,
+        args.space_x,
+        args.space_y,
+        rent_account_data.bump,
+    )?;
     assert_keys_equal(key, *rent_account.key)?;
     
     // ensure not already rented
@@ -102,6 +138,12 @@ pub fn process(
         msg!("Space currently rented out");
         return Err(ProgramError::InvalidInstructionData);
     }
+
+    // ensure the previous lessee has passed up extending the rental
+    if in_first_refusal_period(&rent_account_data, now_ts) && rent_account_data.lessee != *lessee.key {
+        msg!("Space held for its previous lessee to extend the rental");
+        return Err(ProgramError::InvalidInstructionData);
+    }
     
     // ensure rentable
     // ensure rent lister matches lessor
@@ -109,7 +151,10 @@ pub fn process(
 
     // ensure rent period valid
     let now_ts = Clock::get().unwrap().unix_timestamp as u64;
-    let putative_rent_end = now_ts + args.rent_time;
+    let putative_rent_end = now_ts.checked_add(args.rent_time).ok_or_else(|| {
+        msg!("Error: rent end overflows");
+        ProgramError::InvalidArgument
+    })?;
     
     let exceeds_max_timestamp = rent_account_data.max_timestamp < putative_rent_end;
     let below_min_duration = rent_account_data.min_duration > args.rent_time;
@@ -134,25 +179,82 @@ pub fn process(
 
     // main code
     let actual_rent_time = min(args.rent_time, rent_account_data.max_timestamp-now_ts);
-    // invoke SOL transfer
-    invoke(
-        &system_instruction::transfer(
-            lessee.key,
-            lessor.key,
-            args.price * (actual_rent_time),
-        ),
-        &[
-            lessee.clone(),
-            lessor.clone(),
-            system_program.clone(),
-        ],
+    let rent_total = args.price.checked_mul(actual_rent_time).ok_or_else(|| {
+        msg!("Error: rent total overflows");
+        ProgramError::InvalidArgument
+    })?;
+
+    // escrow the rent, paid out to the lessor as the rental runs, in the escrow of the previous rental if it
+    // expired without being settled, as closing it earlier in the same transaction would not free it up
+    let mut previous_rent_escrow_data = None;
+    let rent_escrow_bump = if rent_escrow.data_len() != 0 && rent_escrow.lamports() != 0 {
+        let rent_escrow_data =
+            get_rent_escrow_data(program_id, rent_escrow, base.key, args.space_x, args.space_y)?;
+        if rent_escrow_data.rent_end > now_ts {
+            msg!("Error: escrow of the previous rental has not been settled");
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+        assert_keys_equal(rent_escrow_data.lessor, *previous_lessor.key)?;
+        assert_keys_equal(rent_escrow_data.lessee, *previous_lessee.key)?;
+
+        // the previous lessee paid for the escrow account, the new lessee buys them out of it
+        let unclaimed = rent_escrow_data.amount - rent_escrow_data.claimed;
+        if previous_lessee.key != lessee.key {
+            invoke(
+                &system_instruction::transfer(lessee.key, previous_lessee.key, rent_escrow.lamports() - unclaimed),
+                &[lessee.clone(), previous_lessee.clone(), system_program.clone()],
+            )?;
+        }
+        let rent_escrow_bump = rent_escrow_data.bump;
+        previous_rent_escrow_data = Some(rent_escrow_data);
+        rent_escrow_bump
+    }
+    else {
+        create_rent_escrow(
+            program_id,
+            base.key,
+            rent_escrow,
+            lessee,
+            system_program,
+            args.space_x,
+            args.space_y,
+        )?
+    };
+    let amount = pay_rent(
+        rent_total,
+        &base_data,
+        &neighborhood_metadata_data,
+        lessee,
+        rent_escrow,
+        neighborhood_creator,
+        treasury,
+        system_program,
     )?;
 
+    // release the rest of the previous rental to its lessor, after the transfers as a CPI fails once lamports
+    // were moved out of the escrow directly
+    if let Some(mut rent_escrow_data) = previous_rent_escrow_data {
+        let rent_end = rent_escrow_data.rent_end;
+        release_accrued_rent(rent_escrow, &mut rent_escrow_data, previous_lessor, rent_end)?;
+    }
+
+    let rent_escrow_data = RentEscrow {
+        bump: rent_escrow_bump,
+        lessor: *lessor.key,
+        lessee: *lessee.key,
+        rent_start: now_ts,
+        rent_end: now_ts + actual_rent_time,
+        amount,
+        claimed: 0,
+    };
+    rent_escrow_data.serialize(&mut *rent_escrow.data.borrow_mut())?;
+
     // update rent account data
     rent_account_data.rent_end = now_ts + actual_rent_time;
     rent_account_data.lessee = *lessee.key;
 
     rent_account_data.serialize(&mut *rent_account.data.borrow_mut())?;
+    log_rent_event("started", args.space_x, args.space_y, &rent_account_data);
     
     msg!("done");
     Ok(())
@@ -0,0 +1,51 @@
+use borsh::{BorshSerialize};
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    entrypoint::ProgramResult,
+    clock::Clock,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+use crate::{
+    instruction::ClaimRentArgs,
+    processor::processor_utils::{get_rent_escrow_data, release_accrued_rent, settle_rent_escrow},
+    validation_utils::assert_keys_equal,
+};
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: &ClaimRentArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let base = next_account_info(account_info_iter)?;
+    let rent_escrow = next_account_info(account_info_iter)?;
+    let lessor = next_account_info(account_info_iter)?;
+    let lessee = next_account_info(account_info_iter)?;
+
+    if !lessor.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // deserialize and verify rent escrow
+    let mut rent_escrow_data =
+        get_rent_escrow_data(program_id, rent_escrow, base.key, args.space_x, args.space_y)?;
+    assert_keys_equal(rent_escrow_data.lessor, *lessor.key)?;
+    assert_keys_equal(rent_escrow_data.lessee, *lessee.key)?;
+
+    // main code
+    // once the rental is over the escrow is settled and closed, until then the lessor takes what has accrued
+    let now_ts = Clock::get().unwrap().unix_timestamp as u64;
+    if now_ts >= rent_escrow_data.rent_end {
+        let rent_end = rent_escrow_data.rent_end;
+        settle_rent_escrow(rent_escrow, &mut rent_escrow_data, lessor, lessee, rent_end)?;
+    }
+    else {
+        release_accrued_rent(rent_escrow, &mut rent_escrow_data, lessor, now_ts)?;
+        rent_escrow_data.serialize(&mut *rent_escrow.data.borrow_mut())?;
+    }
+
+    Ok(())
+}
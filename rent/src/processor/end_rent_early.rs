@@ -0,0 +1,63 @@
+use borsh::{BorshSerialize};
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    entrypoint::ProgramResult,
+    clock::Clock,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+use crate::{
+    instruction::EndRentEarlyArgs,
+    processor::processor_utils::{get_rent_escrow_data, log_rent_event, settle_rent_escrow},
+    validation_utils::{assert_keys_equal, assert_rent_account},
+};
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: &EndRentEarlyArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let base = next_account_info(account_info_iter)?;
+    let rent_account = next_account_info(account_info_iter)?;
+    let rent_escrow = next_account_info(account_info_iter)?;
+    let lessee = next_account_info(account_info_iter)?;
+    let lessor = next_account_info(account_info_iter)?;
+
+    if !lessee.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // deserialize and verify rent account
+    let mut rent_account_data = assert_rent_account(rent_account, base.key, args.space_x, args.space_y)?;
+    assert_keys_equal(rent_account_data.lessee, *lessee.key)?;
+
+    // ensure still rented
+    let now_ts = Clock::get().unwrap().unix_timestamp as u64;
+    if rent_account_data.rent_end <= now_ts {
+        msg!("Error: rental of space has already ended");
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    // deserialize and verify rent escrow
+    let mut rent_escrow_data =
+        get_rent_escrow_data(program_id, rent_escrow, base.key, args.space_x, args.space_y)?;
+    assert_keys_equal(rent_escrow_data.lessor, *lessor.key)?;
+    assert_keys_equal(rent_escrow_data.lessee, *lessee.key)?;
+
+    // main code
+    // the lessor keeps the rent accrued so far, the rest is refunded, marketplace fees are not
+    settle_rent_escrow(rent_escrow, &mut rent_escrow_data, lessor, lessee, now_ts)?;
+
+    rent_account_data.rent_end = now_ts;
+    log_rent_event("ended_early", args.space_x, args.space_y, &rent_account_data);
+
+    rent_account_data.rent_end = 0;
+    rent_account_data.lessee = Pubkey::default();
+    rent_account_data.serialize(&mut *rent_account.data.borrow_mut())?;
+
+    Ok(())
+}
@@ -0,0 +1,240 @@
+use borsh::{BorshSerialize};
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    entrypoint::ProgramResult,
+    borsh::try_from_slice_unchecked,
+    msg,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    clock::Clock,
+    sysvar::Sysvar,
+    system_program,
+};
+use std::{str::FromStr, cmp::{max, min}};
+
+use spl_token;
+use extend_common::utils::get_neighborhood_xy;
+
+use crate::{
+    error::CustomError,
+    instruction::ExtendRentArgs,
+    pda::{create_neighborhood_metadata_address, create_rent_account_address, create_space_metadata_address},
+    processor::processor_utils::{
+        create_rent_escrow,
+        get_rent_escrow_data,
+        in_first_refusal_period,
+        log_rent_event,
+        pay_rent,
+        release_accrued_rent,
+    },
+    state::{
+        Base,
+        NeighborhoodMetadata,
+        RentAccount,
+        RentEscrow,
+        SpaceMetadata,
+        SPACE_PID,
+    },
+    validation_utils::{assert_is_ata, assert_keys_equal, assert_owned_by},
+};
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: &ExtendRentArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let base = next_account_info(account_info_iter)?;
+    let space_metadata = next_account_info(account_info_iter)?;
+    let rent_account = next_account_info(account_info_iter)?;
+    let lessee = next_account_info(account_info_iter)?;
+    let lessor = next_account_info(account_info_iter)?;
+    let ata_space = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let neighborhood_metadata = next_account_info(account_info_iter)?;
+    let neighborhood_creator = next_account_info(account_info_iter)?;
+    let treasury = next_account_info(account_info_iter)?;
+    let rent_escrow = next_account_info(account_info_iter)?;
+
+    if !lessee.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+
+    // check programs
+    assert_keys_equal(system_program::id(), *system_program.key)?;
+
+
+    //deserialize and verify base
+    let space_program_id = Pubkey::from_str(SPACE_PID).unwrap();
+    assert_owned_by(base, &space_program_id)?;
+    let base_data: Base = try_from_slice_unchecked(&base.data.borrow())?;
+
+    //deserialize and verify space metadata
+    let space_metadata_data: SpaceMetadata = try_from_slice_unchecked(&space_metadata.data.borrow())?;
+    let key = create_space_metadata_address(
+        &space_program_id,
+        base.key,
+        args.space_x,
+        args.space_y,
+        space_metadata_data.bump,
+    )?;
+    assert_keys_equal(key, *space_metadata.key)?;
+
+    //deserialize and verify neighborhood metadata
+    let neighborhood_metadata_data: NeighborhoodMetadata = try_from_slice_unchecked(&neighborhood_metadata.data.borrow())?;
+    let (neighborhood_x, neighborhood_y) = get_neighborhood_xy(args.space_x, args.space_y);
+    let key = create_neighborhood_metadata_address(
+        &space_program_id,
+        base.key,
+        neighborhood_x,
+        neighborhood_y,
+        neighborhood_metadata_data.bump,
+    )?;
+    assert_keys_equal(key, *neighborhood_metadata.key)?;
+
+    // check neighborhood creator and treasury are passed in correctly
+    assert_keys_equal(neighborhood_metadata_data.creator, *neighborhood_creator.key)?;
+    assert_keys_equal(base_data.get_treasury(), *treasury.key)?;
+
+
+    //check ATAs
+    assert_is_ata(ata_space, lessor.key, &space_metadata_data.mint)?;
+
+    // check NFT owned
+    let ata_data = spl_token::state::Account::unpack_from_slice(&ata_space.data.borrow())?;
+    if ata_data.amount != 1 {
+        msg!("Error: token account does not own token");
+        return Err(CustomError::MissingTokenOwner.into());
+    }
+
+
+    // deserialize and verify rent account
+    let mut rent_account_data: RentAccount = try_from_slice_unchecked(&rent_account.data.borrow())?;
+    let key = create_rent_account_address(
+        program_id,
+        base.key,
+        args.space_x,
+        args.space_y,
+        rent_account_data.bump,
+    )?;
+    assert_keys_equal(key, *rent_account.key)?;
+
+    // ensure the lessee extends their own rental, while it runs or in the first refusal period after it
+    assert_keys_equal(rent_account_data.lessee, *lessee.key)?;
+    let now_ts = Clock::get().unwrap().unix_timestamp as u64;
+    if rent_account_data.rent_end <= now_ts && !in_first_refusal_period(&rent_account_data, now_ts) {
+        msg!("Rental of space has ended");
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    // ensure rent lister matches lessor
+    assert_keys_equal(rent_account_data.lister, *lessor.key)?;
+
+    // ensure extension valid, the rental runs on from its end or starts over once it has ended
+    let extension_start = max(rent_account_data.rent_end, now_ts);
+    if rent_account_data.max_timestamp <= extension_start {
+        msg!("Listing ends before the rental would be extended");
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    let putative_rent_end = extension_start.checked_add(args.rent_time).ok_or_else(|| {
+        msg!("Error: rent end overflows");
+        ProgramError::InvalidArgument
+    })?;
+
+    let exceeds_max_timestamp = rent_account_data.max_timestamp < putative_rent_end;
+    let below_min_duration = rent_account_data.min_duration > args.rent_time;
+    let above_max_duration = rent_account_data.max_duration < putative_rent_end - now_ts;
+
+    if !exceeds_max_timestamp && below_min_duration {
+        msg!("Specified rent period less than listing minimum rent duration");
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    if above_max_duration {
+        msg!("Extended rental would run longer than listing maximum rent duration");
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    // ensure price valid
+    if rent_account_data.price != args.price {
+        msg!("Specified price does not match listing price");
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+
+    // main code
+    let rent_end = min(putative_rent_end, rent_account_data.max_timestamp);
+    let rent_total = args.price.checked_mul(rent_end - extension_start).ok_or_else(|| {
+        msg!("Error: rent total overflows");
+        ProgramError::InvalidArgument
+    })?;
+
+    // escrow the rent, in a new escrow if the lessor already claimed the whole rental
+    let rent_escrow_data = if rent_escrow.data_len() == 0 {
+        let rent_escrow_bump = create_rent_escrow(
+            program_id,
+            base.key,
+            rent_escrow,
+            lessee,
+            system_program,
+            args.space_x,
+            args.space_y,
+        )?;
+        let amount = pay_rent(
+            rent_total,
+            &base_data,
+            &neighborhood_metadata_data,
+            lessee,
+            rent_escrow,
+            neighborhood_creator,
+            treasury,
+            system_program,
+        )?;
+        RentEscrow {
+            bump: rent_escrow_bump,
+            lessor: *lessor.key,
+            lessee: *lessee.key,
+            rent_start: now_ts,
+            rent_end,
+            amount,
+            claimed: 0,
+        }
+    }
+    else {
+        let mut rent_escrow_data =
+            get_rent_escrow_data(program_id, rent_escrow, base.key, args.space_x, args.space_y)?;
+        assert_keys_equal(rent_escrow_data.lessor, *lessor.key)?;
+        assert_keys_equal(rent_escrow_data.lessee, *lessee.key)?;
+
+        // pay out what has accrued so far, the rest streams on together with the extension from now,
+        // taking the extension in first as a CPI fails once lamports were moved out of the escrow directly
+        let amount = pay_rent(
+            rent_total,
+            &base_data,
+            &neighborhood_metadata_data,
+            lessee,
+            rent_escrow,
+            neighborhood_creator,
+            treasury,
+            system_program,
+        )?;
+        release_accrued_rent(rent_escrow, &mut rent_escrow_data, lessor, now_ts)?;
+        rent_escrow_data.amount = rent_escrow_data.amount - rent_escrow_data.claimed + amount;
+        rent_escrow_data.claimed = 0;
+        rent_escrow_data.rent_start = now_ts;
+        rent_escrow_data.rent_end = rent_end;
+        rent_escrow_data
+    };
+    rent_escrow_data.serialize(&mut *rent_escrow.data.borrow_mut())?;
+
+    // update rent account data
+    rent_account_data.rent_end = rent_end;
+
+    rent_account_data.serialize(&mut *rent_account.data.borrow_mut())?;
+    log_rent_event("extended", args.space_x, args.space_y, &rent_account_data);
+
+    msg!("done");
+    Ok(())
+}
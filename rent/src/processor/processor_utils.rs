@@ -0,0 +1,177 @@
+use solana_program::{
+    account_info::AccountInfo,
+    borsh::try_from_slice_unchecked,
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_instruction,
+};
+
+use extend_common::utils::{close_program_account, create_pda_account, get_marketplace_fee, split_fee, transfer_lamports};
+
+use crate::{
+    pda::{create_rent_escrow_address, find_rent_escrow_address},
+    state::{RENT_ESCROW_RESERVE, RENT_ESCROW_SEED, Base, NeighborhoodMetadata, RentAccount, RentEscrow},
+    validation_utils::{assert_keys_equal, assert_owned_by},
+};
+
+// whether the rental has ended but its lessee may still renew it through ExtendRent, before anyone else can rent
+pub fn in_first_refusal_period(rent_account_data: &RentAccount, now: u64) -> bool {
+    rent_account_data.lessee != Pubkey::default()
+        && rent_account_data.rent_end <= now
+        && now < rent_account_data.rent_end.saturating_add(rent_account_data.first_refusal_period)
+}
+
+// create the rent escrow of the space at (space_x, space_y) paid by lessee, returning its bump
+#[allow(clippy::too_many_arguments)]
+pub fn create_rent_escrow<'a>(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    rent_escrow: &AccountInfo<'a>,
+    lessee: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    space_x: i64,
+    space_y: i64,
+) -> Result<u8, ProgramError> {
+    let (key, rent_escrow_bump) = find_rent_escrow_address(program_id, base, space_x, space_y);
+    assert_keys_equal(key, *rent_escrow.key)?;
+    if rent_escrow.data_len() != 0 {
+        msg!("Error: escrow of the previous rental has not been settled");
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+    let seeds_rent_escrow = &[
+        &base.to_bytes(),
+        RENT_ESCROW_SEED,
+        &space_x.to_le_bytes(),
+        &space_y.to_le_bytes(),
+        &[rent_escrow_bump],
+    ];
+    create_pda_account(
+        lessee,
+        rent_escrow,
+        system_program,
+        RENT_ESCROW_RESERVE,
+        program_id,
+        seeds_rent_escrow,
+    )?;
+    Ok(rent_escrow_bump)
+}
+
+// pay rent_total from the lessee, the marketplace fee going to the neighborhood creator and treasury and the
+// rest into the rent escrow, returning the amount escrowed
+#[allow(clippy::too_many_arguments)]
+pub fn pay_rent<'a>(
+    rent_total: u64,
+    base_data: &Base,
+    neighborhood_metadata_data: &NeighborhoodMetadata,
+    lessee: &AccountInfo<'a>,
+    rent_escrow: &AccountInfo<'a>,
+    neighborhood_creator: &AccountInfo<'a>,
+    treasury: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+) -> Result<u64, ProgramError> {
+    let marketplace_fee = get_marketplace_fee(
+        rent_total,
+        neighborhood_metadata_data.get_marketplace_fee_bps(),
+        base_data.get_max_marketplace_fee_bps(),
+    )?;
+    let (creator_fee, treasury_fee) = split_fee(marketplace_fee, base_data.treasury_fee_share_bps)?;
+
+    // invoke SOL transfers to escrow, neighborhood creator and treasury
+    for (wallet, amount) in [
+        (rent_escrow, rent_total - marketplace_fee),
+        (neighborhood_creator, creator_fee),
+        (treasury, treasury_fee),
+    ] {
+        if amount == 0 {
+            continue;
+        }
+        invoke(
+            &system_instruction::transfer(
+                lessee.key,
+                wallet.key,
+                amount,
+            ),
+            &[
+                lessee.clone(),
+                wallet.clone(),
+                system_program.clone(),
+            ],
+        )?;
+    }
+    Ok(rent_total - marketplace_fee)
+}
+
+// deserialize and verify the rent escrow of the space at (space_x, space_y)
+pub fn get_rent_escrow_data(
+    program_id: &Pubkey,
+    rent_escrow: &AccountInfo,
+    base: &Pubkey,
+    space_x: i64,
+    space_y: i64,
+) -> Result<RentEscrow, ProgramError> {
+    assert_owned_by(rent_escrow, program_id)?;
+    let rent_escrow_data: RentEscrow = try_from_slice_unchecked(&rent_escrow.data.borrow())?;
+    let key = create_rent_escrow_address(program_id, base, space_x, space_y, rent_escrow_data.bump)?;
+    assert_keys_equal(key, *rent_escrow.key)?;
+    Ok(rent_escrow_data)
+}
+
+// one line per change to a rental, in the form
+// `rent <event>: space=(x,y) lister=<pubkey> lessee=<pubkey> rent_end=<unix timestamp>` for indexers to parse,
+// logging the rent account as changed, or the rental that ended when one is ended
+pub fn log_rent_event(event: &str, space_x: i64, space_y: i64, rent_account_data: &RentAccount) {
+    msg!(
+        "rent {}: space=({},{}) lister={} lessee={} rent_end={}",
+        event,
+        space_x,
+        space_y,
+        rent_account_data.lister,
+        rent_account_data.lessee,
+        rent_account_data.rent_end,
+    );
+}
+
+// rent of an escrowed rental accrued to the lessor by now, streaming linearly over the rental
+pub fn get_accrued_rent(rent_escrow_data: &RentEscrow, now: u64) -> u64 {
+    let RentEscrow { rent_start, rent_end, amount, .. } = *rent_escrow_data;
+    if now >= rent_end || rent_end <= rent_start {
+        return amount;
+    }
+    if now <= rent_start {
+        return 0;
+    }
+    ((amount as u128) * ((now - rent_start) as u128) / ((rent_end - rent_start) as u128)) as u64
+}
+
+// release what has accrued by now and is not yet claimed to the lessor
+pub fn release_accrued_rent<'a>(
+    rent_escrow: &AccountInfo<'a>,
+    rent_escrow_data: &mut RentEscrow,
+    lessor: &AccountInfo<'a>,
+    now: u64,
+) -> ProgramResult {
+    let owed = get_accrued_rent(rent_escrow_data, now) - rent_escrow_data.claimed;
+    if owed > 0 {
+        transfer_lamports(rent_escrow, lessor, owed)?;
+        rent_escrow_data.claimed += owed;
+    }
+    msg!("rent released to lessor: {}", owed);
+    Ok(())
+}
+
+// end the rental at end_ts, paying the lessor up to then and refunding the unused rent together with the
+// escrow account's rent to the lessee, who paid for it
+pub fn settle_rent_escrow<'a>(
+    rent_escrow: &AccountInfo<'a>,
+    rent_escrow_data: &mut RentEscrow,
+    lessor: &AccountInfo<'a>,
+    lessee: &AccountInfo<'a>,
+    end_ts: u64,
+) -> ProgramResult {
+    release_accrued_rent(rent_escrow, rent_escrow_data, lessor, end_ts)?;
+    msg!("rent refunded to lessee: {}", rent_escrow_data.amount - rent_escrow_data.claimed);
+    close_program_account(rent_escrow, lessee)
+}
@@ -0,0 +1,70 @@
+use borsh::{BorshSerialize};
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    entrypoint::ProgramResult,
+    clock::Clock,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+use crate::{
+    instruction::ReclaimExpiredRentArgs,
+    pda::find_rent_escrow_address,
+    processor::processor_utils::{get_rent_escrow_data, in_first_refusal_period, log_rent_event, settle_rent_escrow},
+    validation_utils::{assert_keys_equal, assert_rent_account},
+};
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: &ReclaimExpiredRentArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let base = next_account_info(account_info_iter)?;
+    let rent_account = next_account_info(account_info_iter)?;
+    let rent_escrow = next_account_info(account_info_iter)?;
+    let lessor = next_account_info(account_info_iter)?;
+    let lessee = next_account_info(account_info_iter)?;
+
+    // deserialize and verify rent account
+    let mut rent_account_data = assert_rent_account(rent_account, base.key, args.space_x, args.space_y)?;
+
+    // ensure there is an expired rental to reclaim
+    let now_ts = Clock::get().unwrap().unix_timestamp as u64;
+    if rent_account_data.lessee == Pubkey::default() {
+        msg!("Error: space is not rented out");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if rent_account_data.rent_end > now_ts {
+        msg!("Error: rental of space has not ended yet");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if in_first_refusal_period(&rent_account_data, now_ts) {
+        msg!("Error: lessee may still extend the rental");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    assert_keys_equal(rent_account_data.lessee, *lessee.key)?;
+
+    // main code
+    // settle the escrow unless the lessor already claimed all of the rent
+    let (key, _) = find_rent_escrow_address(program_id, base.key, args.space_x, args.space_y);
+    assert_keys_equal(key, *rent_escrow.key)?;
+    if rent_escrow.data_len() != 0 {
+        let mut rent_escrow_data =
+            get_rent_escrow_data(program_id, rent_escrow, base.key, args.space_x, args.space_y)?;
+        assert_keys_equal(rent_escrow_data.lessor, *lessor.key)?;
+        assert_keys_equal(rent_escrow_data.lessee, *lessee.key)?;
+        let rent_end = rent_escrow_data.rent_end;
+        settle_rent_escrow(rent_escrow, &mut rent_escrow_data, lessor, lessee, rent_end)?;
+    }
+
+    log_rent_event("reclaimed", args.space_x, args.space_y, &rent_account_data);
+
+    rent_account_data.rent_end = 0;
+    rent_account_data.lessee = Pubkey::default();
+    rent_account_data.serialize(&mut *rent_account.data.borrow_mut())?;
+
+    Ok(())
+}
@@ -20,8 +20,9 @@ use spl_token;
 use crate::{
     error::CustomError,
     instruction::SetRentArgs,
+    pda::{create_space_metadata_address, find_rent_account_address},
+    processor::processor_utils::log_rent_event,
     state::{
-        SPACE_METADATA_SEED,
         RENT_ACCOUNT_SEED,
         RENT_ACCOUNT_RESERVE,
         RentAccount,
@@ -55,14 +56,13 @@ pub fn process(
     //deserialize and verify space metadata
     let space_metadata_data: SpaceMetadata = try_from_slice_unchecked(&space_metadata.data.borrow_mut())?;
 
-    let seeds_space_metadata = &[
-        &base.key.to_bytes(),
-        SPACE_METADATA_SEED,
-        &args.space_x.to_le_bytes(),
-        &args.space_y.to_le_bytes(),
-        &[space_metadata_data.bump],
-    ];
-    let key = Pubkey::create_program_address(seeds_space_metadata, &Pubkey::from_str(SPACE_PID).unwrap())?;
+    let key = create_space_metadata_address(
+        &Pubkey::from_str(SPACE_PID).unwrap(),
+        base.key,
+        args.space_x,
+        args.space_y,
+        space_metadata_data.bump,
+    )?;
     assert_keys_equal(key, *space_metadata.key)?;
 
 
@@ -79,13 +79,8 @@ pub fn process(
 
     // deserialize and verify rent account
     // create rent account if not already existing
-    let seeds_rent_account = &[
-        &base.key.to_bytes(),
-        RENT_ACCOUNT_SEED,
-        &args.space_x.to_le_bytes(),
-        &args.space_y.to_le_bytes(),
-    ];
-    let (key, rent_account_bump) = Pubkey::find_program_address(seeds_rent_account, program_id);
+    let (key, rent_account_bump) =
+        find_rent_account_address(program_id, base.key, args.space_x, args.space_y);
     assert_keys_equal(key, *rent_account.key)?;
     let seeds_rent_account = &[
         &base.key.to_bytes(),
@@ -140,6 +135,7 @@ pub fn process(
         rent_account_data.max_duration = args.max_duration;
         rent_account_data.max_timestamp = args.max_timestamp;
         rent_account_data.lister = *lessor.key;
+        rent_account_data.first_refusal_period = args.first_refusal_period;
     }
     // delist
     else 
@@ -148,9 +144,11 @@ pub fn process(
         rent_account_data.min_duration = 0;
         rent_account_data.max_duration = 0;
         rent_account_data.max_timestamp = 0;
+        rent_account_data.first_refusal_period = 0;
     }
 
     rent_account_data.serialize(&mut *rent_account.data.borrow_mut())?;
+    log_rent_event(if args.create { "listed" } else { "delisted" }, args.space_x, args.space_y, &rent_account_data);
     
     msg!("done");
     Ok(())
@@ -0,0 +1,179 @@
+// run against the BPF build with `cargo test-bpf`
+#![cfg(feature = "test-bpf")]
+
+mod utils;
+
+use extend_rent::{
+    instruction,
+    pda::find_rent_escrow_address,
+};
+use solana_program::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use utils::*;
+
+const PRICE: u64 = 1_000;
+const RENT_TIME: u64 = 3600;
+
+async fn set_rent(fixture: &mut RentFixture) {
+    let now = get_unix_timestamp(&mut fixture.context.banks_client).await;
+    process_instructions(
+        &mut fixture.context.banks_client,
+        fixture.context.last_blockhash,
+        &[instruction::set_rent(
+            &program_id(),
+            &fixture.base,
+            &fixture.space_mint,
+            &fixture.lessor.pubkey(),
+            SPACE_X,
+            SPACE_Y,
+            PRICE,
+            0,
+            10 * RENT_TIME,
+            now + 100 * RENT_TIME,
+            true,
+        )],
+        &[&fixture.payer, &fixture.lessor],
+    ).await.unwrap();
+}
+
+async fn accept_rent(fixture: &mut RentFixture, rent_time: u64) -> Result<(), Box<dyn std::error::Error>> {
+    process_instructions(
+        &mut fixture.context.banks_client,
+        fixture.context.last_blockhash,
+        &[instruction::accept_rent(
+            &program_id(),
+            &fixture.base,
+            &fixture.space_mint,
+            &fixture.lessee.pubkey(),
+            &fixture.lessor.pubkey(),
+            SPACE_X,
+            SPACE_Y,
+            PRICE,
+            rent_time,
+            &fixture.neighborhood_creator,
+            &fixture.treasury,
+        )],
+        &[&fixture.payer, &fixture.lessee],
+    ).await
+}
+
+async fn end_rent_early(fixture: &mut RentFixture, lessee: &Keypair) -> Result<(), Box<dyn std::error::Error>> {
+    process_instructions(
+        &mut fixture.context.banks_client,
+        fixture.context.last_blockhash,
+        &[instruction::end_rent_early(
+            &program_id(),
+            &fixture.base,
+            &lessee.pubkey(),
+            &fixture.lessor.pubkey(),
+            SPACE_X,
+            SPACE_Y,
+        )],
+        &[&fixture.payer, lessee],
+    ).await
+}
+
+// signed by the context payer only, as anyone can reclaim an expired rental
+async fn reclaim_expired_rent(fixture: &mut RentFixture) -> Result<(), Box<dyn std::error::Error>> {
+    process_instructions(
+        &mut fixture.context.banks_client,
+        fixture.context.last_blockhash,
+        &[instruction::reclaim_expired_rent(
+            &program_id(),
+            &fixture.base,
+            &fixture.lessor.pubkey(),
+            &fixture.lessee.pubkey(),
+            SPACE_X,
+            SPACE_Y,
+        )],
+        &[&fixture.payer],
+    ).await
+}
+
+#[tokio::test]
+async fn test_end_rent_early_refunds_unused_rent() {
+    let mut fixture = setup_rent().await;
+    let lessee = Keypair::from_bytes(&fixture.lessee.to_bytes()).unwrap();
+    let lessor = Keypair::from_bytes(&fixture.lessor.to_bytes()).unwrap();
+    let (rent_escrow, _) = find_rent_escrow_address(&program_id(), &fixture.base, SPACE_X, SPACE_Y);
+    set_rent(&mut fixture).await;
+    accept_rent(&mut fixture, RENT_TIME).await.unwrap();
+    let escrow = get_rent_escrow(&mut fixture.context.banks_client, &fixture.base).await.unwrap();
+    let escrow_lamports = get_balance(&mut fixture.context.banks_client, &rent_escrow).await;
+
+    // only the lessee can give the space back
+    assert!(end_rent_early(&mut fixture, &lessor).await.is_err());
+
+    // a quarter into the rental the lessor keeps what has accrued and the lessee gets the rest back
+    warp_past(&mut fixture.context, escrow.rent_start + RENT_TIME / 4).await;
+    let now = get_unix_timestamp(&mut fixture.context.banks_client).await;
+    let accrued = escrow.amount * (now - escrow.rent_start) / RENT_TIME;
+    let lessor_lamports = get_balance(&mut fixture.context.banks_client, &lessor.pubkey()).await;
+    let lessee_lamports = get_balance(&mut fixture.context.banks_client, &lessee.pubkey()).await;
+    end_rent_early(&mut fixture, &lessee).await.unwrap();
+    let banks_client = &mut fixture.context.banks_client;
+    assert_eq!(get_balance(banks_client, &lessor.pubkey()).await, lessor_lamports + accrued);
+    assert_eq!(get_balance(banks_client, &lessee.pubkey()).await, lessee_lamports + escrow_lamports - accrued);
+    assert!(get_rent_escrow(banks_client, &fixture.base).await.is_none());
+    let rent_account = get_rent_account(banks_client, &fixture.base).await;
+    assert_eq!(rent_account.lessee, Pubkey::default());
+    assert_eq!(rent_account.rent_end, 0);
+
+    // and the space can be rented again straight away
+    accept_rent(&mut fixture, 2 * RENT_TIME).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_reclaim_expired_rent_settles_escrow() {
+    let mut fixture = setup_rent().await;
+    let lessor = fixture.lessor.pubkey();
+    let lessee = fixture.lessee.pubkey();
+    let (rent_escrow, _) = find_rent_escrow_address(&program_id(), &fixture.base, SPACE_X, SPACE_Y);
+    set_rent(&mut fixture).await;
+    accept_rent(&mut fixture, RENT_TIME).await.unwrap();
+    let escrow = get_rent_escrow(&mut fixture.context.banks_client, &fixture.base).await.unwrap();
+    let escrow_lamports = get_balance(&mut fixture.context.banks_client, &rent_escrow).await;
+
+    assert!(reclaim_expired_rent(&mut fixture).await.is_err());
+
+    warp_past(&mut fixture.context, escrow.rent_end).await;
+    let lessor_lamports = get_balance(&mut fixture.context.banks_client, &lessor).await;
+    let lessee_lamports = get_balance(&mut fixture.context.banks_client, &lessee).await;
+    reclaim_expired_rent(&mut fixture).await.unwrap();
+    let banks_client = &mut fixture.context.banks_client;
+    assert_eq!(get_balance(banks_client, &lessor).await, lessor_lamports + escrow.amount);
+    assert_eq!(get_balance(banks_client, &lessee).await, lessee_lamports + escrow_lamports - escrow.amount);
+    assert!(get_rent_escrow(banks_client, &fixture.base).await.is_none());
+    let rent_account = get_rent_account(banks_client, &fixture.base).await;
+    assert_eq!(rent_account.lessee, Pubkey::default());
+    assert_eq!(rent_account.rent_end, 0);
+}
+
+#[tokio::test]
+async fn test_reclaim_expired_rent_after_rent_is_claimed() {
+    let mut fixture = setup_rent().await;
+    let lessor = Keypair::from_bytes(&fixture.lessor.to_bytes()).unwrap();
+    set_rent(&mut fixture).await;
+    accept_rent(&mut fixture, RENT_TIME).await.unwrap();
+    let rent_end = get_rent_account(&mut fixture.context.banks_client, &fixture.base).await.rent_end;
+
+    // the lessor claiming the whole rent closes the escrow, leaving only the rental to reset
+    warp_past(&mut fixture.context, rent_end).await;
+    process_instructions(
+        &mut fixture.context.banks_client,
+        fixture.context.last_blockhash,
+        &[instruction::claim_rent(
+            &program_id(),
+            &fixture.base,
+            &lessor.pubkey(),
+            &fixture.lessee.pubkey(),
+            SPACE_X,
+            SPACE_Y,
+        )],
+        &[&fixture.payer, &lessor],
+    ).await.unwrap();
+    assert!(get_rent_escrow(&mut fixture.context.banks_client, &fixture.base).await.is_none());
+
+    reclaim_expired_rent(&mut fixture).await.unwrap();
+    assert_eq!(get_rent_account(&mut fixture.context.banks_client, &fixture.base).await.lessee, Pubkey::default());
+}
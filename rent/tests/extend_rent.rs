@@ -0,0 +1,168 @@
+// run against the BPF build with `cargo test-bpf`
+#![cfg(feature = "test-bpf")]
+
+mod utils;
+
+use extend_rent::{
+    instruction,
+    state::{BPS_DENOMINATOR, MARKETPLACE_FEE_BPS},
+};
+use solana_program::system_instruction;
+use solana_sdk::signature::{Keypair, Signer};
+use utils::*;
+
+const PRICE: u64 = 1_000;
+const RENT_TIME: u64 = 3600;
+
+async fn set_rent(fixture: &mut RentFixture, first_refusal_period: u64) {
+    let now = get_unix_timestamp(&mut fixture.context.banks_client).await;
+    process_instructions(
+        &mut fixture.context.banks_client,
+        fixture.context.last_blockhash,
+        &[instruction::set_rent_with_first_refusal(
+            &program_id(),
+            &fixture.base,
+            &fixture.space_mint,
+            &fixture.lessor.pubkey(),
+            SPACE_X,
+            SPACE_Y,
+            PRICE,
+            0,
+            10 * RENT_TIME,
+            now + 100 * RENT_TIME,
+            true,
+            first_refusal_period,
+        )],
+        &[&fixture.payer, &fixture.lessor],
+    ).await.unwrap();
+}
+
+async fn accept_rent(fixture: &mut RentFixture, lessee: &Keypair) -> Result<(), Box<dyn std::error::Error>> {
+    process_instructions(
+        &mut fixture.context.banks_client,
+        fixture.context.last_blockhash,
+        &[instruction::accept_rent(
+            &program_id(),
+            &fixture.base,
+            &fixture.space_mint,
+            &lessee.pubkey(),
+            &fixture.lessor.pubkey(),
+            SPACE_X,
+            SPACE_Y,
+            PRICE,
+            RENT_TIME,
+            &fixture.neighborhood_creator,
+            &fixture.treasury,
+        )],
+        &[&fixture.payer, lessee],
+    ).await
+}
+
+async fn extend_rent(fixture: &mut RentFixture, lessee: &Keypair, rent_time: u64) -> Result<(), Box<dyn std::error::Error>> {
+    process_instructions(
+        &mut fixture.context.banks_client,
+        fixture.context.last_blockhash,
+        &[instruction::extend_rent(
+            &program_id(),
+            &fixture.base,
+            &fixture.space_mint,
+            &lessee.pubkey(),
+            &fixture.lessor.pubkey(),
+            SPACE_X,
+            SPACE_Y,
+            PRICE,
+            rent_time,
+            &fixture.neighborhood_creator,
+            &fixture.treasury,
+        )],
+        &[&fixture.payer, lessee],
+    ).await
+}
+
+fn escrowed(rent_time: u64) -> u64 {
+    let rent_total = PRICE * rent_time;
+    rent_total - rent_total * MARKETPLACE_FEE_BPS as u64 / BPS_DENOMINATOR
+}
+
+#[tokio::test]
+async fn test_lessee_extends_rental() {
+    let mut fixture = setup_rent().await;
+    let lessee = Keypair::from_bytes(&fixture.lessee.to_bytes()).unwrap();
+    let lessor = Keypair::from_bytes(&fixture.lessor.to_bytes()).unwrap();
+    set_rent(&mut fixture, 0).await;
+    accept_rent(&mut fixture, &lessee).await.unwrap();
+    let escrow = get_rent_escrow(&mut fixture.context.banks_client, &fixture.base).await.unwrap();
+
+    // only the lessee can extend, and not past the maximum duration from now
+    assert!(extend_rent(&mut fixture, &lessor, RENT_TIME).await.is_err());
+    assert!(extend_rent(&mut fixture, &lessee, 10 * RENT_TIME).await.is_err());
+
+    // halfway through, the lessor is paid what has accrued and the rest streams on with the extension
+    warp_past(&mut fixture.context, escrow.rent_start + RENT_TIME / 2).await;
+    let now = get_unix_timestamp(&mut fixture.context.banks_client).await;
+    let accrued = escrow.amount * (now - escrow.rent_start) / RENT_TIME;
+    let lessor_lamports = get_balance(&mut fixture.context.banks_client, &lessor.pubkey()).await;
+    extend_rent(&mut fixture, &lessee, RENT_TIME).await.unwrap();
+    let banks_client = &mut fixture.context.banks_client;
+    assert_eq!(get_balance(banks_client, &lessor.pubkey()).await, lessor_lamports + accrued);
+    let rent_account = get_rent_account(banks_client, &fixture.base).await;
+    assert_eq!(rent_account.lessee, lessee.pubkey());
+    assert_eq!(rent_account.rent_end, escrow.rent_end + RENT_TIME);
+    let extended_escrow = get_rent_escrow(banks_client, &fixture.base).await.unwrap();
+    assert_eq!(extended_escrow.amount, escrow.amount - accrued + escrowed(RENT_TIME));
+    assert_eq!(extended_escrow.claimed, 0);
+    assert_eq!(extended_escrow.rent_start, now);
+    assert_eq!(extended_escrow.rent_end, rent_account.rent_end);
+}
+
+#[tokio::test]
+async fn test_first_refusal_after_rental_ends() {
+    let mut fixture = setup_rent().await;
+    let lessee = Keypair::from_bytes(&fixture.lessee.to_bytes()).unwrap();
+    let other_lessee = Keypair::new();
+    process_instructions(
+        &mut fixture.context.banks_client,
+        fixture.context.last_blockhash,
+        &[system_instruction::transfer(&fixture.payer.pubkey(), &other_lessee.pubkey(), WALLET_LAMPORTS)],
+        &[&fixture.payer],
+    ).await.unwrap();
+    set_rent(&mut fixture, RENT_TIME).await;
+    accept_rent(&mut fixture, &lessee).await.unwrap();
+    let rent_end = get_rent_account(&mut fixture.context.banks_client, &fixture.base).await.rent_end;
+
+    // once the rental ends the space is held for its lessee, who renews it from now on
+    warp_past(&mut fixture.context, rent_end).await;
+    assert!(accept_rent(&mut fixture, &other_lessee).await.is_err());
+    extend_rent(&mut fixture, &lessee, RENT_TIME).await.unwrap();
+    let now = get_unix_timestamp(&mut fixture.context.banks_client).await;
+    let rent_account = get_rent_account(&mut fixture.context.banks_client, &fixture.base).await;
+    assert_eq!(rent_account.lessee, lessee.pubkey());
+    assert_eq!(rent_account.rent_end, now + RENT_TIME);
+    let escrow = get_rent_escrow(&mut fixture.context.banks_client, &fixture.base).await.unwrap();
+    assert_eq!(escrow.amount, escrowed(RENT_TIME));
+}
+
+#[tokio::test]
+async fn test_extend_rent_without_first_refusal_after_rental_ends() {
+    let mut fixture = setup_rent().await;
+    let lessee = Keypair::from_bytes(&fixture.lessee.to_bytes()).unwrap();
+    set_rent(&mut fixture, 0).await;
+    accept_rent(&mut fixture, &lessee).await.unwrap();
+    let rent_end = get_rent_account(&mut fixture.context.banks_client, &fixture.base).await.rent_end;
+
+    warp_past(&mut fixture.context, rent_end).await;
+    assert!(extend_rent(&mut fixture, &lessee, RENT_TIME).await.is_err());
+}
+
+#[tokio::test]
+async fn test_extend_rent_rejects_overflowing_rent_time() {
+    let mut fixture = setup_rent().await;
+    let lessee = Keypair::from_bytes(&fixture.lessee.to_bytes()).unwrap();
+    set_rent(&mut fixture, 0).await;
+    accept_rent(&mut fixture, &lessee).await.unwrap();
+    let rent_end = get_rent_account(&mut fixture.context.banks_client, &fixture.base).await.rent_end;
+
+    assert!(extend_rent(&mut fixture, &lessee, u64::MAX).await.is_err());
+    assert_eq!(get_rent_account(&mut fixture.context.banks_client, &fixture.base).await.rent_end, rent_end);
+    extend_rent(&mut fixture, &lessee, RENT_TIME).await.unwrap();
+}
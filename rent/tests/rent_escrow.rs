@@ -0,0 +1,275 @@
+// run against the BPF build with `cargo test-bpf`
+#![cfg(feature = "test-bpf")]
+
+mod utils;
+
+use extend_rent::{
+    instruction,
+    pda::find_rent_escrow_address,
+    state::{BPS_DENOMINATOR, MARKETPLACE_FEE_BPS},
+};
+use solana_program::{pubkey::Pubkey, system_instruction};
+use solana_sdk::signature::{Keypair, Signer};
+use utils::*;
+
+const PRICE: u64 = 1_000;
+const RENT_TIME: u64 = 3600;
+
+async fn set_rent(fixture: &mut RentFixture) {
+    let now = get_unix_timestamp(&mut fixture.context.banks_client).await;
+    process_instructions(
+        &mut fixture.context.banks_client,
+        fixture.context.last_blockhash,
+        &[instruction::set_rent(
+            &program_id(),
+            &fixture.base,
+            &fixture.space_mint,
+            &fixture.lessor.pubkey(),
+            SPACE_X,
+            SPACE_Y,
+            PRICE,
+            0,
+            10 * RENT_TIME,
+            now + 100 * RENT_TIME,
+            true,
+        )],
+        &[&fixture.payer, &fixture.lessor],
+    ).await.unwrap();
+}
+
+async fn accept_rent(fixture: &mut RentFixture, lessee: &Keypair, rent_time: u64) -> Result<(), Box<dyn std::error::Error>> {
+    process_instructions(
+        &mut fixture.context.banks_client,
+        fixture.context.last_blockhash,
+        &[instruction::accept_rent(
+            &program_id(),
+            &fixture.base,
+            &fixture.space_mint,
+            &lessee.pubkey(),
+            &fixture.lessor.pubkey(),
+            SPACE_X,
+            SPACE_Y,
+            PRICE,
+            rent_time,
+            &fixture.neighborhood_creator,
+            &fixture.treasury,
+        )],
+        &[&fixture.payer, lessee],
+    ).await
+}
+
+async fn accept_rent_after(
+    fixture: &mut RentFixture,
+    lessee: &Keypair,
+    previous_lessor: &Pubkey,
+    previous_lessee: &Pubkey,
+) -> Result<(), Box<dyn std::error::Error>> {
+    process_instructions(
+        &mut fixture.context.banks_client,
+        fixture.context.last_blockhash,
+        &[instruction::accept_rent_after(
+            &program_id(),
+            &fixture.base,
+            &fixture.space_mint,
+            &lessee.pubkey(),
+            &fixture.lessor.pubkey(),
+            SPACE_X,
+            SPACE_Y,
+            PRICE,
+            RENT_TIME,
+            &fixture.neighborhood_creator,
+            &fixture.treasury,
+            previous_lessor,
+            previous_lessee,
+        )],
+        &[&fixture.payer, lessee],
+    ).await
+}
+
+async fn claim_rent(fixture: &mut RentFixture, lessor: &Keypair) -> Result<(), Box<dyn std::error::Error>> {
+    process_instructions(
+        &mut fixture.context.banks_client,
+        fixture.context.last_blockhash,
+        &[instruction::claim_rent(
+            &program_id(),
+            &fixture.base,
+            &lessor.pubkey(),
+            &fixture.lessee.pubkey(),
+            SPACE_X,
+            SPACE_Y,
+        )],
+        &[&fixture.payer, lessor],
+    ).await
+}
+
+#[tokio::test]
+async fn test_rent_is_escrowed_and_streamed() {
+    let mut fixture = setup_rent().await;
+    let lessee = Keypair::from_bytes(&fixture.lessee.to_bytes()).unwrap();
+    let lessor_wallet = Keypair::from_bytes(&fixture.lessor.to_bytes()).unwrap();
+    let lessor = lessor_wallet.pubkey();
+    let (rent_escrow, _) = find_rent_escrow_address(&program_id(), &fixture.base, SPACE_X, SPACE_Y);
+    set_rent(&mut fixture).await;
+
+    // the lessor is paid nothing up front
+    let lessor_lamports = get_balance(&mut fixture.context.banks_client, &lessor).await;
+    accept_rent(&mut fixture, &lessee, RENT_TIME).await.unwrap();
+    let banks_client = &mut fixture.context.banks_client;
+    assert_eq!(get_balance(banks_client, &lessor).await, lessor_lamports);
+    let rent_total = PRICE * RENT_TIME;
+    let amount = rent_total - rent_total * MARKETPLACE_FEE_BPS as u64 / BPS_DENOMINATOR;
+    let escrow = get_rent_escrow(banks_client, &fixture.base).await.unwrap();
+    assert_eq!(escrow.amount, amount);
+    assert_eq!(escrow.rent_end - escrow.rent_start, RENT_TIME);
+    let escrow_lamports = get_balance(banks_client, &rent_escrow).await;
+
+    // halfway through, the lessor claims what has accrued
+    warp_past(&mut fixture.context, escrow.rent_start + RENT_TIME / 2).await;
+    let now = get_unix_timestamp(&mut fixture.context.banks_client).await;
+    let accrued = amount * (now - escrow.rent_start) / RENT_TIME;
+    claim_rent(&mut fixture, &lessor_wallet).await.unwrap();
+    let banks_client = &mut fixture.context.banks_client;
+    assert_eq!(get_balance(banks_client, &lessor).await, lessor_lamports + accrued);
+    assert_eq!(get_rent_escrow(banks_client, &fixture.base).await.unwrap().claimed, accrued);
+
+    // after the rental the rest is released and the escrow closed, its rent going back to the lessee
+    warp_past(&mut fixture.context, escrow.rent_end).await;
+    let lessee_lamports = get_balance(&mut fixture.context.banks_client, &lessee.pubkey()).await;
+    claim_rent(&mut fixture, &lessor_wallet).await.unwrap();
+    let banks_client = &mut fixture.context.banks_client;
+    assert_eq!(get_balance(banks_client, &lessor).await, lessor_lamports + amount);
+    assert_eq!(get_balance(banks_client, &lessee.pubkey()).await, lessee_lamports + escrow_lamports - amount);
+    assert!(get_rent_escrow(banks_client, &fixture.base).await.is_none());
+}
+
+#[tokio::test]
+async fn test_rent_escrow_is_settled_before_the_next_rental() {
+    let mut fixture = setup_rent().await;
+    let lessee = Keypair::from_bytes(&fixture.lessee.to_bytes()).unwrap();
+    let lessor = Keypair::from_bytes(&fixture.lessor.to_bytes()).unwrap();
+    set_rent(&mut fixture).await;
+    accept_rent(&mut fixture, &lessee, RENT_TIME).await.unwrap();
+
+    // only the lessor of the rental can claim its rent
+    assert!(claim_rent(&mut fixture, &lessee).await.is_err());
+
+    let rent_end = get_rent_account(&mut fixture.context.banks_client, &fixture.base).await.rent_end;
+    warp_past(&mut fixture.context, rent_end).await;
+    claim_rent(&mut fixture, &lessor).await.unwrap();
+    accept_rent(&mut fixture, &lessee, 2 * RENT_TIME).await.unwrap();
+    let rent_account = get_rent_account(&mut fixture.context.banks_client, &fixture.base).await;
+    assert_eq!(rent_account.lessee, lessee.pubkey());
+    assert!(rent_account.rent_end > rent_end);
+}
+
+#[tokio::test]
+async fn test_expired_rent_escrow_is_settled_by_the_next_rental() {
+    let mut fixture = setup_rent().await;
+    let lessee = Keypair::from_bytes(&fixture.lessee.to_bytes()).unwrap();
+    let lessor = fixture.lessor.pubkey();
+    let other_lessee = Keypair::new();
+    process_instructions(
+        &mut fixture.context.banks_client,
+        fixture.context.last_blockhash,
+        &[system_instruction::transfer(&fixture.payer.pubkey(), &other_lessee.pubkey(), WALLET_LAMPORTS)],
+        &[&fixture.payer],
+    ).await.unwrap();
+    let (rent_escrow, _) = find_rent_escrow_address(&program_id(), &fixture.base, SPACE_X, SPACE_Y);
+    set_rent(&mut fixture).await;
+    accept_rent(&mut fixture, &lessee, RENT_TIME).await.unwrap();
+    let escrow = get_rent_escrow(&mut fixture.context.banks_client, &fixture.base).await.unwrap();
+    let escrow_lamports = get_balance(&mut fixture.context.banks_client, &rent_escrow).await;
+
+    // the escrow of a rental that has not ended yet is not taken over
+    let rent_account = get_rent_account(&mut fixture.context.banks_client, &fixture.base).await;
+    assert!(accept_rent_after(&mut fixture, &other_lessee, &lessor, &lessee.pubkey()).await.is_err());
+
+    // once it has ended, the next rental pays its lessor the rest and refunds its lessee the escrow account rent
+    warp_past(&mut fixture.context, rent_account.rent_end).await;
+    let lessor_lamports = get_balance(&mut fixture.context.banks_client, &lessor).await;
+    let lessee_lamports = get_balance(&mut fixture.context.banks_client, &lessee.pubkey()).await;
+    assert!(accept_rent_after(&mut fixture, &other_lessee, &lessor, &other_lessee.pubkey()).await.is_err());
+    accept_rent_after(&mut fixture, &other_lessee, &lessor, &lessee.pubkey()).await.unwrap();
+
+    let banks_client = &mut fixture.context.banks_client;
+    assert_eq!(get_balance(banks_client, &lessor).await, lessor_lamports + escrow.amount);
+    assert_eq!(get_balance(banks_client, &lessee.pubkey()).await, lessee_lamports + escrow_lamports - escrow.amount);
+    let next_escrow = get_rent_escrow(banks_client, &fixture.base).await.unwrap();
+    assert_eq!(next_escrow.lessee, other_lessee.pubkey());
+    assert_eq!(next_escrow.claimed, 0);
+    assert_eq!(get_balance(banks_client, &rent_escrow).await, escrow_lamports - escrow.amount + next_escrow.amount);
+}
+
+#[tokio::test]
+async fn test_accept_rent_rejects_overflowing_rent_total() {
+    let mut fixture = setup_rent().await;
+    let lessee = Keypair::from_bytes(&fixture.lessee.to_bytes()).unwrap();
+    // the rent total for RENT_TIME would wrap around to a few thousand lamports
+    let price = u64::MAX / RENT_TIME + 1;
+    let now = get_unix_timestamp(&mut fixture.context.banks_client).await;
+    process_instructions(
+        &mut fixture.context.banks_client,
+        fixture.context.last_blockhash,
+        &[instruction::set_rent(
+            &program_id(),
+            &fixture.base,
+            &fixture.space_mint,
+            &fixture.lessor.pubkey(),
+            SPACE_X,
+            SPACE_Y,
+            price,
+            0,
+            10 * RENT_TIME,
+            now + 100 * RENT_TIME,
+            true,
+        )],
+        &[&fixture.payer, &fixture.lessor],
+    ).await.unwrap();
+    assert!(process_instructions(
+        &mut fixture.context.banks_client,
+        fixture.context.last_blockhash,
+        &[instruction::accept_rent(
+            &program_id(),
+            &fixture.base,
+            &fixture.space_mint,
+            &lessee.pubkey(),
+            &fixture.lessor.pubkey(),
+            SPACE_X,
+            SPACE_Y,
+            price,
+            RENT_TIME,
+            &fixture.neighborhood_creator,
+            &fixture.treasury,
+        )],
+        &[&fixture.payer, &lessee],
+    ).await.is_err());
+}
+
+#[tokio::test]
+async fn test_prefunded_rent_escrow_does_not_block_rentals() {
+    let mut fixture = setup_rent().await;
+    let lessee = Keypair::from_bytes(&fixture.lessee.to_bytes()).unwrap();
+    let (rent_escrow, _) = find_rent_escrow_address(&program_id(), &fixture.base, SPACE_X, SPACE_Y);
+    set_rent(&mut fixture).await;
+
+    // lamports sent to the escrow address ahead of the rental count towards its rent exemption
+    process_instructions(
+        &mut fixture.context.banks_client,
+        fixture.context.last_blockhash,
+        &[system_instruction::transfer(&fixture.payer.pubkey(), &rent_escrow, 1)],
+        &[&fixture.payer],
+    ).await.unwrap();
+    accept_rent(&mut fixture, &lessee, RENT_TIME).await.unwrap();
+    let escrow = get_rent_escrow(&mut fixture.context.banks_client, &fixture.base).await.unwrap();
+    assert_eq!(escrow.lessee, lessee.pubkey());
+}
+
+#[tokio::test]
+async fn test_accept_rent_rejects_overflowing_rent_time() {
+    let mut fixture = setup_rent().await;
+    let lessee = Keypair::from_bytes(&fixture.lessee.to_bytes()).unwrap();
+    set_rent(&mut fixture).await;
+
+    assert!(accept_rent(&mut fixture, &lessee, u64::MAX).await.is_err());
+    accept_rent(&mut fixture, &lessee, RENT_TIME).await.unwrap();
+}
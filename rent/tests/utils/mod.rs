@@ -0,0 +1,237 @@
+#![allow(dead_code)]
+
+use std::str::FromStr;
+use borsh::BorshSerialize;
+use extend_rent::{
+    pda::{
+        find_neighborhood_metadata_address,
+        find_rent_account_address,
+        find_rent_escrow_address,
+        find_space_metadata_address,
+    },
+    processor::Processor,
+    state::{
+        MARKETPLACE_FEE_BPS,
+        MAX_MARKETPLACE_FEE_BPS,
+        NEIGHBORHOOD_METADATA_RESERVE,
+        RENT_PID,
+        SPACE_METADATA_RESERVE,
+        SPACE_PID,
+        Base,
+        NeighborhoodMetadata,
+        RentAccount,
+        RentEscrow,
+        SpaceMetadata,
+    },
+};
+use solana_program::{
+    borsh::try_from_slice_unchecked,
+    clock::Clock,
+    program_option::COption,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_program,
+};
+use solana_program_test::{processor, BanksClient, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    hash::Hash,
+    instruction::Instruction,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+use spl_associated_token_account::get_associated_token_address;
+
+pub const SPACE_X: i64 = 10;
+pub const SPACE_Y: i64 = 20;
+pub const NEIGHBORHOOD_X: i64 = 0;
+pub const NEIGHBORHOOD_Y: i64 = 0;
+pub const WALLET_LAMPORTS: u64 = 10_000_000_000;
+
+pub fn program_id() -> Pubkey {
+    Pubkey::from_str(RENT_PID).unwrap()
+}
+
+pub fn space_program_id() -> Pubkey {
+    Pubkey::from_str(SPACE_PID).unwrap()
+}
+
+pub fn program_test() -> ProgramTest {
+    ProgramTest::new("extend_rent", program_id(), processor!(Processor::process))
+}
+
+pub fn add_wallet(program_test: &mut ProgramTest) -> Keypair {
+    let wallet = Keypair::new();
+    program_test.add_account(
+        wallet.pubkey(),
+        Account {
+            lamports: WALLET_LAMPORTS,
+            data: vec![],
+            owner: system_program::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+    wallet
+}
+
+fn add_data_account(program_test: &mut ProgramTest, address: Pubkey, owner: Pubkey, data: Vec<u8>) {
+    program_test.add_account(
+        address,
+        Account {
+            lamports: Rent::default().minimum_balance(data.len()),
+            data,
+            owner,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+}
+
+// base as written by the space program's InitBase and UpdateTreasury
+pub fn add_base(program_test: &mut ProgramTest, treasury: &Pubkey) -> Pubkey {
+    let address = Pubkey::new_unique();
+    let data = Base {
+        neighborhood_count: 0,
+        authority: Pubkey::new_unique(),
+        authority_privileges: false,
+        max_marketplace_fee_bps: MAX_MARKETPLACE_FEE_BPS,
+        treasury: *treasury,
+        treasury_fee_share_bps: 0,
+        max_marketplace_fee_bps_set: true,
+    }.try_to_vec().unwrap();
+    add_data_account(program_test, address, space_program_id(), data);
+    address
+}
+
+// neighborhood metadata as written by the space program's InitNeighborhoodMetadata
+pub fn add_neighborhood(program_test: &mut ProgramTest, base: &Pubkey, creator: &Pubkey) {
+    let (address, bump) = find_neighborhood_metadata_address(&space_program_id(), base, NEIGHBORHOOD_X, NEIGHBORHOOD_Y);
+    let mut data = NeighborhoodMetadata {
+        bump,
+        creator: *creator,
+        candymachine_config: Pubkey::new_unique(),
+        candymachine_account: Pubkey::new_unique(),
+        neighborhood_name: [0; 64],
+        marketplace_fee_bps: MARKETPLACE_FEE_BPS,
+        marketplace_fee_bps_set: true,
+    }.try_to_vec().unwrap();
+    data.resize(NEIGHBORHOOD_METADATA_RESERVE, 0);
+    add_data_account(program_test, address, space_program_id(), data);
+}
+
+// space metadata as written by the space program's InitSpaceMetadata, with the space token held by owner
+pub fn add_space(program_test: &mut ProgramTest, base: &Pubkey, owner: &Pubkey) -> Pubkey {
+    let mint = Pubkey::new_unique();
+
+    let mut data = vec![0; spl_token::state::Mint::LEN];
+    spl_token::state::Mint {
+        mint_authority: COption::None,
+        supply: 1,
+        decimals: 0,
+        is_initialized: true,
+        freeze_authority: COption::None,
+    }.pack_into_slice(&mut data);
+    add_data_account(program_test, mint, spl_token::id(), data);
+
+    let mut data = vec![0; spl_token::state::Account::LEN];
+    spl_token::state::Account {
+        mint,
+        owner: *owner,
+        amount: 1,
+        delegate: COption::None,
+        state: spl_token::state::AccountState::Initialized,
+        is_native: COption::None,
+        delegated_amount: 0,
+        close_authority: COption::None,
+    }.pack_into_slice(&mut data);
+    add_data_account(program_test, get_associated_token_address(owner, &mint), spl_token::id(), data);
+
+    let (address, bump) = find_space_metadata_address(&space_program_id(), base, SPACE_X, SPACE_Y);
+    let mut data = SpaceMetadata {
+        bump,
+        mint,
+        price: 0,
+        space_x: SPACE_X,
+        space_y: SPACE_Y,
+        escrow_seller: Pubkey::default(),
+        payment_mint: Pubkey::default(),
+        listing_terms: false,
+    }.try_to_vec().unwrap();
+    data.resize(SPACE_METADATA_RESERVE, 0);
+    add_data_account(program_test, address, space_program_id(), data);
+
+    mint
+}
+
+pub async fn process_instructions(
+    banks_client: &mut BanksClient,
+    recent_blockhash: Hash,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut transaction = Transaction::new_with_payer(instructions, Some(&signers[0].pubkey()));
+    transaction.sign(signers, recent_blockhash);
+    banks_client.process_transaction(transaction).await?;
+    Ok(())
+}
+
+pub async fn get_unix_timestamp(banks_client: &mut BanksClient) -> u64 {
+    banks_client.get_sysvar::<Clock>().await.unwrap().unix_timestamp as u64
+}
+
+pub async fn warp_past(context: &mut ProgramTestContext, unix_timestamp: u64) {
+    let mut slot = context.banks_client.get_root_slot().await.unwrap();
+    while get_unix_timestamp(&mut context.banks_client).await <= unix_timestamp {
+        slot += 100;
+        context.warp_to_slot(slot).unwrap();
+    }
+}
+
+pub async fn get_balance(banks_client: &mut BanksClient, address: &Pubkey) -> u64 {
+    banks_client.get_balance(*address).await.unwrap()
+}
+
+pub async fn get_rent_account(banks_client: &mut BanksClient, base: &Pubkey) -> RentAccount {
+    let (address, _) = find_rent_account_address(&program_id(), base, SPACE_X, SPACE_Y);
+    let account = banks_client.get_account(address).await.unwrap().unwrap();
+    try_from_slice_unchecked(&account.data).unwrap()
+}
+
+pub async fn get_rent_escrow(banks_client: &mut BanksClient, base: &Pubkey) -> Option<RentEscrow> {
+    let (address, _) = find_rent_escrow_address(&program_id(), base, SPACE_X, SPACE_Y);
+    banks_client
+        .get_account(address)
+        .await
+        .unwrap()
+        .map(|account| try_from_slice_unchecked(&account.data).unwrap())
+}
+
+// a base with one neighborhood and one space at (SPACE_X, SPACE_Y) held by lessor, the context payer
+// pays transaction fees so wallet balances only move by what the program transfers
+pub struct RentFixture {
+    pub context: ProgramTestContext,
+    pub payer: Keypair,
+    pub base: Pubkey,
+    pub lessor: Keypair,
+    pub lessee: Keypair,
+    pub space_mint: Pubkey,
+    pub neighborhood_creator: Pubkey,
+    pub treasury: Pubkey,
+}
+
+pub async fn setup_rent() -> RentFixture {
+    let mut program_test = program_test();
+    let lessor = add_wallet(&mut program_test);
+    let lessee = add_wallet(&mut program_test);
+    let neighborhood_creator = add_wallet(&mut program_test).pubkey();
+    let treasury = add_wallet(&mut program_test).pubkey();
+    let base = add_base(&mut program_test, &treasury);
+    add_neighborhood(&mut program_test, &base, &neighborhood_creator);
+    let space_mint = add_space(&mut program_test, &base, &lessor.pubkey());
+
+    let context = program_test.start_with_context().await;
+    let payer = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+    RentFixture { context, payer, base, lessor, lessee, space_mint, neighborhood_creator, treasury }
+}
@@ -1,5 +1,32 @@
 use borsh::{BorshDeserialize, BorshSerialize};
-use solana_program::program_error::ProgramError;
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_program,
+    sysvar::rent,
+};
+use spl_associated_token_account::get_associated_token_address;
+use std::str::FromStr;
+use extend_common::utils::get_neighborhood_xy;
+
+use crate::{
+    pda::{
+        find_auction_address,
+        find_bid_address,
+        find_listing_address,
+        find_metaplex_metadata_address,
+        find_neighborhood_list_address,
+        find_neighborhood_metadata_address,
+        find_rent_account_address,
+        find_sell_delegate_address,
+        find_space_escrow_address,
+        find_space_metadata_address,
+        find_voucher_mint_address,
+        find_voucher_sink_address,
+    },
+    state::RENT_PID,
+};
 
 #[repr(C)]
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
@@ -30,16 +57,130 @@ pub struct ChangeOfferArgs {
     pub space_y: i64,
     pub price: u64,
     pub create: bool,
+    // default to price the listing in lamports
+    pub payment_mint: Pubkey,
+    // 0 for a listing that does not expire
+    pub expires_at: u64,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct ChangeEscrowOfferArgs {
+    pub space_x: i64,
+    pub space_y: i64,
+    pub price: u64,
+    pub create: bool,
 }
 
 #[repr(C)]
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
 pub struct AcceptOfferArgs {
+    pub space_x: i64,
+    pub space_y: i64,
+    // most the buyer will pay, dutch listings are bought at their current price
+    pub max_price: u64,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct PlaceBidArgs {
+    pub space_x: i64,
+    pub space_y: i64,
+    pub price: u64,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct CancelBidArgs {
+    pub space_x: i64,
+    pub space_y: i64,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct AcceptBidArgs {
+    pub space_x: i64,
+    pub space_y: i64,
+    pub price: u64,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct SetMarketplaceFeeArgs {
+    pub neighborhood_x: i64,
+    pub neighborhood_y: i64,
+    pub fee_bps: u16,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct SetMarketplaceFeeCapArgs {
+    pub max_fee_bps: u16,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct UpdateTreasuryArgs {
+    pub treasury_fee_share_bps: u16,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct CreateAuctionArgs {
+    pub space_x: i64,
+    pub space_y: i64,
+    pub reserve_price: u64,
+    pub end_time: u64,
+    pub min_increment: u64,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct PlaceAuctionBidArgs {
+    pub space_x: i64,
+    pub space_y: i64,
+    pub price: u64,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct SettleAuctionArgs {
+    pub space_x: i64,
+    pub space_y: i64,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct ChangeDutchOfferArgs {
+    pub space_x: i64,
+    pub space_y: i64,
+    pub start_price: u64,
+    pub end_price: u64,
+    pub start_time: u64,
+    pub end_time: u64,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct ClearExpiredOfferArgs {
+    pub space_x: i64,
+    pub space_y: i64,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct SpaceOffer {
     pub space_x: i64,
     pub space_y: i64,
     pub price: u64,
 }
 
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct ChangeOfferBatchArgs {
+    pub offers: Vec<SpaceOffer>,
+}
+
 #[repr(C)]
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
 pub struct InitVoucherSystemArgs {
@@ -80,7 +221,7 @@ pub enum SpaceInstruction {
     Accounts expected:
     0. [Writable, Signer] Base
     1. [Writable] Neighborhood list
-    2. [Signer] payer
+    2. [Signer, Writable] payer
     3. system program
     */
     InitBase,
@@ -92,14 +233,12 @@ pub enum SpaceInstruction {
     2. [Writable] neighborhood list
     3. candymachine_config
     4. candymachine_account
-    5. [Signer] creator
-    6. creator ATA for payment token
-    7. payment token mint
-    8. [Writable, signer] voucher_mint
-    9. system program
-    10. token program
-    11. rent program
-
+    5. [Signer, Writable] creator
+    6. [Writable] creator ATA for payment token
+    7. [Writable] payment token mint
+    8. system program
+    9. token program
+    10. rent program
     */
     InitNeighborhoodMetadata,
 
@@ -118,34 +257,53 @@ pub enum SpaceInstruction {
     InitSpaceMetadata,
 
     /*
-    Change sell offer
+    Change sell offer, priced in lamports or in the SPL token given by payment_mint,
+    that can not be accepted from expires_at on if it is set
     Accounts expected:
     0. Base account
     1. [Writable] space account
     2. [Signer, Writable] owner
-    3. token account
+    3. [Writable] token account
     4. sell delegate
     5. token program
+    6. [Writable] listing account
+    7. system program
+    8. rent account of the space, from the rent program
+    9. auction account of the space, listing is refused while an auction has not been settled
+    10. [Writable] seller recorded in the listing account, paid back its rent when it is closed or taken over
     */
     ChangeOffer,
 
     /*
-    Bob has sell offer for B, Alice takes it
+    Bob has sell offer for B, Alice takes it at the current listing price as long as it is at most her maximum price
     Accounts expected:
     0. Base account
     1. Neighborhood Metadata
     2. [Writable] Neighborhood creator
-    3. space account
+    3. [Writable] space account
     4. B mint account
-    5. [Signer] alice
+    5. [Signer, Writable] alice
     6. [Writable] alice ATA account for B
-    7. bob
-    8. [Writable] bob ATA account for B
-    9. [Signer] sell delegate
+    7. [Writable] bob
+    8. [Writable] bob ATA account for B, or the space escrow if B is escrowed
+    9. sell delegate
     10. system program
     11. token program
     12. associated token program
     13. rent program
+    14. [Writable] treasury
+    15. metaplex metadata of B
+    16. [Writable] listing account for B
+    17. rent account of B, from the rent program
+    Only if B has royalties and its primary sale has happened, for each verified creator with a share in the metaplex metadata:
+    18... [Writable] royalty creator
+    Only if the listing is priced in an SPL token, following the royalty creators:
+    payment mint
+    [Writable] alice ATA account for payment mint
+    [Writable] bob ATA account for payment mint
+    [Writable] Neighborhood creator ATA account for payment mint
+    [Writable] treasury ATA account for payment mint
+    [Writable] royalty creator ATA accounts for payment mint, in the order of the royalty creators
     */
     AcceptOffer,
 
@@ -159,12 +317,12 @@ pub enum SpaceInstruction {
     )
     Accounts expected:
     0. Base account
-    1. Neighborhood metadata[
-    2. [Signer] Neighborhood creator
+    1. Neighborhood metadata
+    2. [Signer, Writable] Neighborhood creator
     3. [Signer] Voucher mint authority
-    4. [Signer, Writable] voucher_mint
-    5. [Signer, Writable] source ATA for voucher token
-    6. [Signer, Writable] sink account for voucher token 
+    4. [Writable] voucher_mint
+    5. [Writable] source ATA for voucher token
+    6. [Writable] sink account for voucher token
     7. system program
     8. token program
     9. associated token program
@@ -189,8 +347,225 @@ pub enum SpaceInstruction {
     */
     UpdateAuthority,
 
+    /*
+    List space by moving it into a program-owned escrow, update the price of an escrowed
+    listing, or delist and return the space to the seller
+    Accounts expected:
+    0. Base account
+    1. [Writable] space account
+    2. [Signer, Writable] seller
+    3. [Writable] seller ATA account for space
+    4. [Writable] space escrow
+    5. sell delegate
+    6. space mint account
+    7. system program
+    8. token program
+    9. associated token program
+    10. rent program
+    11. rent account of the space, from the rent program
+    12. auction account of the space, listing is refused while an auction has not been settled
+    13. [Writable] listing account of the space, closed as escrowed listings have no terms
+    14. [Writable] seller recorded in the listing account, receives its rent
+    */
+    ChangeEscrowOffer,
+
+    /*
+    Place or change a bid on a space, holding the bid price in the bid account
+    Accounts expected:
+    0. Base account
+    1. space account
+    2. [Writable] bid account
+    3. [Signer, Writable] bidder
+    4. system program
+    */
+    PlaceBid,
+
+    /*
+    Cancel a bid, refunding the bid price and rent
+    Accounts expected:
+    0. Base account
+    1. [Writable] bid account
+    2. [Signer, Writable] bidder
+    */
+    CancelBid,
+
+    /*
+    Owner of B accepts a bid from bidder
+    Accounts expected:
+    0. Base account
+    1. Neighborhood Metadata
+    2. [Writable] Neighborhood creator
+    3. [Writable] space account
+    4. B mint account
+    5. [Signer, Writable] owner
+    6. [Writable] owner ATA account for B
+    7. [Writable] bidder
+    8. [Writable] bidder ATA account for B
+    9. [Writable] bid account
+    10. system program
+    11. token program
+    12. associated token program
+    13. rent program
+    14. [Writable] treasury
+    15. metaplex metadata of B
+    16. rent account of B, from the rent program
+    17. [Writable] listing account for B, closed if B is listed with terms
+    18. auction account of B, accepting is refused while an auction has not been settled
+    19. [Writable] seller recorded in the listing account for B, receives its rent
+    Only if B has royalties and its primary sale has happened, for each verified creator with a share in the metaplex metadata:
+    20... [Writable] royalty creator
+    */
+    AcceptBid,
+
+    /*
+    Set the fee in basis points paid to the neighborhood creator on sales, up to the cap in base,
+    neighborhoods initialized without a fee charge MARKETPLACE_FEE_BPS until this is run
+    Accounts expected:
+    0. Base account
+    1. [Writable] neighborhood metadata
+    2. [Signer] neighborhood creator
+    */
+    SetMarketplaceFee,
+
+    /*
+    Set the cap in basis points on every neighborhood's marketplace fee,
+    bases initialized without a cap use MAX_MARKETPLACE_FEE_BPS until this is run
+    Accounts expected:
+    0. [Writable] Base account
+    1. [Signer] base authority
+    */
+    SetMarketplaceFeeCap,
+
+    /*
+    Change the treasury and its share in basis points of marketplace fees,
+    bases initialized without a treasury use the base authority until this is run
+    Accounts expected:
+    0. [Writable] Base account
+    1. [Signer] base authority
+    2. New treasury
+    */
+    UpdateTreasury,
+
+    /*
+    Auction off a space until end time, approving the sell delegate on the seller's space ATA
+    Accounts expected:
+    0. Base account
+    1. [Writable] space account
+    2. [Writable] auction account
+    3. [Signer, Writable] seller
+    4. [Writable] seller ATA account for space
+    5. sell delegate
+    6. system program
+    7. token program
+    8. rent account of the space, from the rent program
+    9. [Writable] listing account of the space, closed as the auction voids any sell offer
+    10. [Writable] seller recorded in the listing account, receives its rent
+    */
+    CreateAuction,
+
+    /*
+    Outbid the highest bidder, holding the bid in the auction account and refunding the previous bid
+    Accounts expected:
+    0. Base account
+    1. [Writable] auction account
+    2. [Signer, Writable] bidder
+    3. [Writable] previous highest bidder, the seller if there are no bids
+    4. system program
+    */
+    PlaceAuctionBid,
+
+    /*
+    After the end time, move B to the highest bidder and pay seller, neighborhood creator, treasury
+    and royalty creators out of the highest bid, then close the auction. Refunds the highest bidder
+    if the seller no longer has B listed or has rented it out. Anyone can settle.
+    Accounts expected:
+    0. Base account
+    1. Neighborhood Metadata
+    2. [Writable] Neighborhood creator
+    3. [Writable] space account
+    4. B mint account
+    5. [Signer, Writable] payer for the winner's ATA account
+    6. [Writable] seller
+    7. [Writable] seller ATA account for B
+    8. [Writable] winner, the seller if there are no bids
+    9. [Writable] winner ATA account for B
+    10. [Writable] auction account
+    11. sell delegate
+    12. system program
+    13. token program
+    14. associated token program
+    15. rent program
+    16. [Writable] treasury
+    17. metaplex metadata of B
+    18. rent account of B, from the rent program
+    Only if B has royalties and its primary sale has happened, for each verified creator with a share in the metaplex metadata:
+    19... [Writable] royalty creator
+    */
+    SettleAuction,
+
+    /*
+    List B in lamports at a price declining linearly from start price to end price between start time and end time,
+    ChangeOffer with create false cancels the listing
+    Accounts expected:
+    0. Base account
+    1. [Writable] space account
+    2. [Signer, Writable] owner
+    3. [Writable] token account
+    4. [Writable] listing account
+    5. sell delegate
+    6. system program
+    7. token program
+    8. rent account of the space, from the rent program
+    9. auction account of the space, listing is refused while an auction has not been settled
+    10. [Writable] seller recorded in the listing account, paid back its rent if it is taken over
+    */
+    ChangeDutchOffer,
+
+    /*
+    Clear an expired listing and close its listing account, by the seller or by anyone as a crank.
+    Only a signing seller revokes the sell delegate as the token program requires the owner for that,
+    after a crank the cleared price keeps AcceptOffer from using the delegate until the seller relists or delists
+    Accounts expected:
+    0. Base account
+    1. [Writable] space account
+    2. [Writable] listing account
+    3. [Writable] seller, [Signer] to revoke the sell delegate
+    4. [Writable] seller token account
+    5. token program
+    */
+    ClearExpiredOffer,
+
+    /*
+    List several owned spaces in lamports at once, each at its own price
+    Accounts expected:
+    0. Base account
+    1. [Signer, Writable] owner
+    2. sell delegate
+    3. token program
+    4+6k. [Writable] space account of offers[k]
+    5+6k. [Writable] token account of owner for offers[k]
+    6+6k. rent account of the space of offers[k], from the rent program
+    7+6k. auction account of the space of offers[k], listing is refused while an auction has not been settled
+    8+6k. [Writable] listing account of offers[k], closed as batch listings have no terms
+    9+6k. [Writable] seller recorded in the listing account of offers[k], receives its rent
+    */
+    ChangeOfferBatch,
+
+    /*
+    Accounts expected:
+    0. Base account
+    1. [Writable] space account
+    2. token account
+    3. [Signer] owner
+    */
     TempAddxy, // elim
 
+    /*
+    Accounts expected:
+    0. Base account
+    1. [Writable] neighborhood metadata
+    2. [Signer] neighborhood creator
+    */
     ChangeNeighborhoodName, // elim
 }
 
@@ -207,7 +582,987 @@ impl SpaceInstruction {
             7 => Self::UpdateAuthority,
             8 => Self::ChangeNeighborhoodName, // elim?
             9 => Self::TempAddxy, // elim
+            10 => Self::ChangeEscrowOffer,
+            11 => Self::PlaceBid,
+            12 => Self::CancelBid,
+            13 => Self::AcceptBid,
+            14 => Self::SetMarketplaceFee,
+            15 => Self::SetMarketplaceFeeCap,
+            16 => Self::UpdateTreasury,
+            17 => Self::CreateAuction,
+            18 => Self::PlaceAuctionBid,
+            19 => Self::SettleAuction,
+            20 => Self::ChangeDutchOffer,
+            21 => Self::ClearExpiredOffer,
+            22 => Self::ChangeOfferBatch,
             _ => return Err(ProgramError::InvalidInstructionData),
         })
     }
+
+    pub fn pack(&self) -> u8 {
+        match self {
+            Self::InitBase => 0,
+            Self::InitNeighborhoodMetadata => 1,
+            Self::InitSpaceMetadata => 2,
+            Self::ChangeOffer => 3,
+            Self::AcceptOffer => 4,
+            Self::InitVoucherSystem => 5,
+            Self::RevokeAuthorityPrivileges => 6,
+            Self::UpdateAuthority => 7,
+            Self::ChangeNeighborhoodName => 8, // elim?
+            Self::TempAddxy => 9, // elim
+            Self::ChangeEscrowOffer => 10,
+            Self::PlaceBid => 11,
+            Self::CancelBid => 12,
+            Self::AcceptBid => 13,
+            Self::SetMarketplaceFee => 14,
+            Self::SetMarketplaceFeeCap => 15,
+            Self::UpdateTreasury => 16,
+            Self::CreateAuction => 17,
+            Self::PlaceAuctionBid => 18,
+            Self::SettleAuction => 19,
+            Self::ChangeDutchOffer => 20,
+            Self::ClearExpiredOffer => 21,
+            Self::ChangeOfferBatch => 22,
+        }
+    }
+}
+
+fn build_instruction<T: BorshSerialize>(
+    program_id: &Pubkey,
+    instruction: SpaceInstruction,
+    args: &T,
+    accounts: Vec<AccountMeta>,
+) -> Instruction {
+    let mut data = vec![instruction.pack()];
+    data.extend(args.try_to_vec().unwrap());
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    }
+}
+
+pub fn init_base(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    payer: &Pubkey,
+) -> Instruction {
+    let (neighborhood_list, _) = find_neighborhood_list_address(program_id, base);
+    build_instruction(
+        program_id,
+        SpaceInstruction::InitBase,
+        &InitBaseArgs {},
+        vec![
+            AccountMeta::new(*base, true),
+            AccountMeta::new(neighborhood_list, false),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn init_neighborhood_metadata(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    candymachine_config: &Pubkey,
+    candymachine_account: &Pubkey,
+    creator: &Pubkey,
+    payment_mint: &Pubkey,
+    neighborhood_x: i64,
+    neighborhood_y: i64,
+    price: u64,
+    neighborhood_name: [u8; 64],
+) -> Instruction {
+    let (neighborhood_metadata, _) =
+        find_neighborhood_metadata_address(program_id, base, neighborhood_x, neighborhood_y);
+    let (neighborhood_list, _) = find_neighborhood_list_address(program_id, base);
+    build_instruction(
+        program_id,
+        SpaceInstruction::InitNeighborhoodMetadata,
+        &InitNeighborhoodMetadataArgs {
+            neighborhood_x,
+            neighborhood_y,
+            price,
+            neighborhood_name,
+        },
+        vec![
+            AccountMeta::new(*base, false),
+            AccountMeta::new(neighborhood_metadata, false),
+            AccountMeta::new(neighborhood_list, false),
+            AccountMeta::new_readonly(*candymachine_config, false),
+            AccountMeta::new_readonly(*candymachine_account, false),
+            AccountMeta::new(*creator, true),
+            AccountMeta::new(get_associated_token_address(creator, payment_mint), false),
+            AccountMeta::new(*payment_mint, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(rent::id(), false),
+        ],
+    )
+}
+
+fn find_space_rent_account_address(base: &Pubkey, space_x: i64, space_y: i64) -> Pubkey {
+    let (address, _) =
+        find_rent_account_address(&Pubkey::from_str(RENT_PID).unwrap(), base, space_x, space_y);
+    address
+}
+
+pub fn init_space_metadata(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    space_mint: &Pubkey,
+    space_owner: &Pubkey,
+    space_x: i64,
+    space_y: i64,
+) -> Instruction {
+    let (space_metaplex_metadata, _) = find_metaplex_metadata_address(&metaplex_token_metadata::id(), space_mint);
+    let (space_metadata, _) = find_space_metadata_address(program_id, base, space_x, space_y);
+    let (neighborhood_x, neighborhood_y) = get_neighborhood_xy(space_x, space_y);
+    let (neighborhood_metadata, _) =
+        find_neighborhood_metadata_address(program_id, base, neighborhood_x, neighborhood_y);
+    build_instruction(
+        program_id,
+        SpaceInstruction::InitSpaceMetadata,
+        &InitSpaceMetadataArgs { space_x, space_y },
+        vec![
+            AccountMeta::new_readonly(*base, false),
+            AccountMeta::new_readonly(space_metaplex_metadata, false),
+            AccountMeta::new(space_metadata, false),
+            AccountMeta::new_readonly(*space_mint, false),
+            AccountMeta::new_readonly(neighborhood_metadata, false),
+            AccountMeta::new(*space_owner, true),
+            AccountMeta::new_readonly(get_associated_token_address(space_owner, space_mint), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn change_offer(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    space_mint: &Pubkey,
+    owner: &Pubkey,
+    space_x: i64,
+    space_y: i64,
+    price: u64,
+    create: bool,
+) -> Instruction {
+    change_token_offer(
+        program_id,
+        base,
+        space_mint,
+        owner,
+        owner,
+        &Pubkey::default(),
+        space_x,
+        space_y,
+        price,
+        create,
+        0,
+    )
+}
+
+// list in lamports until expires_at
+#[allow(clippy::too_many_arguments)]
+pub fn change_expiring_offer(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    space_mint: &Pubkey,
+    owner: &Pubkey,
+    space_x: i64,
+    space_y: i64,
+    price: u64,
+    expires_at: u64,
+) -> Instruction {
+    change_token_offer(
+        program_id,
+        base,
+        space_mint,
+        owner,
+        owner,
+        &Pubkey::default(),
+        space_x,
+        space_y,
+        price,
+        true,
+        expires_at,
+    )
+}
+
+// list with the price in units of payment_mint, listing_seller is the seller recorded in the space's
+// listing account if it has one, which is owner unless the space changed hands outside the program
+#[allow(clippy::too_many_arguments)]
+pub fn change_token_offer(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    space_mint: &Pubkey,
+    owner: &Pubkey,
+    listing_seller: &Pubkey,
+    payment_mint: &Pubkey,
+    space_x: i64,
+    space_y: i64,
+    price: u64,
+    create: bool,
+    expires_at: u64,
+) -> Instruction {
+    let (space_metadata, _) = find_space_metadata_address(program_id, base, space_x, space_y);
+    let (listing, _) = find_listing_address(program_id, base, space_x, space_y);
+    let (sell_delegate, _) = find_sell_delegate_address(program_id, base);
+    let (auction, _) = find_auction_address(program_id, base, space_x, space_y);
+    build_instruction(
+        program_id,
+        SpaceInstruction::ChangeOffer,
+        &ChangeOfferArgs {
+            space_x,
+            space_y,
+            price,
+            create,
+            payment_mint: *payment_mint,
+            expires_at,
+        },
+        vec![
+            AccountMeta::new_readonly(*base, false),
+            AccountMeta::new(space_metadata, false),
+            AccountMeta::new(*owner, true),
+            AccountMeta::new(get_associated_token_address(owner, space_mint), false),
+            AccountMeta::new_readonly(sell_delegate, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new(listing, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(find_space_rent_account_address(base, space_x, space_y), false),
+            AccountMeta::new_readonly(auction, false),
+            AccountMeta::new(*listing_seller, false),
+        ],
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn change_escrow_offer(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    space_mint: &Pubkey,
+    seller: &Pubkey,
+    listing_seller: &Pubkey,
+    space_x: i64,
+    space_y: i64,
+    price: u64,
+    create: bool,
+) -> Instruction {
+    let (space_metadata, _) = find_space_metadata_address(program_id, base, space_x, space_y);
+    let (escrow, _) = find_space_escrow_address(program_id, base, space_x, space_y);
+    let (sell_delegate, _) = find_sell_delegate_address(program_id, base);
+    let (auction, _) = find_auction_address(program_id, base, space_x, space_y);
+    let (listing, _) = find_listing_address(program_id, base, space_x, space_y);
+    build_instruction(
+        program_id,
+        SpaceInstruction::ChangeEscrowOffer,
+        &ChangeEscrowOfferArgs {
+            space_x,
+            space_y,
+            price,
+            create,
+        },
+        vec![
+            AccountMeta::new_readonly(*base, false),
+            AccountMeta::new(space_metadata, false),
+            AccountMeta::new(*seller, true),
+            AccountMeta::new(get_associated_token_address(seller, space_mint), false),
+            AccountMeta::new(escrow, false),
+            AccountMeta::new_readonly(sell_delegate, false),
+            AccountMeta::new_readonly(*space_mint, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+            AccountMeta::new_readonly(rent::id(), false),
+            AccountMeta::new_readonly(find_space_rent_account_address(base, space_x, space_y), false),
+            AccountMeta::new_readonly(auction, false),
+            AccountMeta::new(listing, false),
+            AccountMeta::new(*listing_seller, false),
+        ],
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn accept_offer(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    space_x: i64,
+    space_y: i64,
+    max_price: u64,
+    buyer: &Pubkey,
+    seller: &Pubkey,
+    space_mint: &Pubkey,
+    neighborhood_creator: &Pubkey,
+    treasury: &Pubkey,
+    royalty_creators: &[Pubkey],
+) -> Instruction {
+    accept_offer_from(
+        program_id,
+        base,
+        space_x,
+        space_y,
+        max_price,
+        buyer,
+        seller,
+        &get_associated_token_address(seller, space_mint),
+        space_mint,
+        neighborhood_creator,
+        treasury,
+        royalty_creators,
+    )
+}
+
+// accept a listing priced in payment_mint, paying from the buyer's ATA for that mint
+#[allow(clippy::too_many_arguments)]
+pub fn accept_token_offer(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    space_x: i64,
+    space_y: i64,
+    max_price: u64,
+    buyer: &Pubkey,
+    seller: &Pubkey,
+    space_mint: &Pubkey,
+    neighborhood_creator: &Pubkey,
+    treasury: &Pubkey,
+    payment_mint: &Pubkey,
+    royalty_creators: &[Pubkey],
+) -> Instruction {
+    let mut instruction = accept_offer(
+        program_id,
+        base,
+        space_x,
+        space_y,
+        max_price,
+        buyer,
+        seller,
+        space_mint,
+        neighborhood_creator,
+        treasury,
+        royalty_creators,
+    );
+    instruction.accounts.extend(vec![
+        AccountMeta::new_readonly(*payment_mint, false),
+        AccountMeta::new(get_associated_token_address(buyer, payment_mint), false),
+        AccountMeta::new(get_associated_token_address(seller, payment_mint), false),
+        AccountMeta::new(get_associated_token_address(neighborhood_creator, payment_mint), false),
+        AccountMeta::new(get_associated_token_address(treasury, payment_mint), false),
+    ]);
+    instruction.accounts.extend(
+        royalty_creators
+            .iter()
+            .map(|creator| AccountMeta::new(get_associated_token_address(creator, payment_mint), false)),
+    );
+    instruction
+}
+
+// accept a listing made with ChangeEscrowOffer
+#[allow(clippy::too_many_arguments)]
+pub fn accept_escrow_offer(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    space_x: i64,
+    space_y: i64,
+    max_price: u64,
+    buyer: &Pubkey,
+    seller: &Pubkey,
+    space_mint: &Pubkey,
+    neighborhood_creator: &Pubkey,
+    treasury: &Pubkey,
+    royalty_creators: &[Pubkey],
+) -> Instruction {
+    let (escrow, _) = find_space_escrow_address(program_id, base, space_x, space_y);
+    accept_offer_from(
+        program_id,
+        base,
+        space_x,
+        space_y,
+        max_price,
+        buyer,
+        seller,
+        &escrow,
+        space_mint,
+        neighborhood_creator,
+        treasury,
+        royalty_creators,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn accept_offer_from(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    space_x: i64,
+    space_y: i64,
+    max_price: u64,
+    buyer: &Pubkey,
+    seller: &Pubkey,
+    seller_token_account: &Pubkey,
+    space_mint: &Pubkey,
+    neighborhood_creator: &Pubkey,
+    treasury: &Pubkey,
+    royalty_creators: &[Pubkey],
+) -> Instruction {
+    let (neighborhood_x, neighborhood_y) = get_neighborhood_xy(space_x, space_y);
+    let (neighborhood_metadata, _) =
+        find_neighborhood_metadata_address(program_id, base, neighborhood_x, neighborhood_y);
+    let (space_metadata, _) = find_space_metadata_address(program_id, base, space_x, space_y);
+    let (sell_delegate, _) = find_sell_delegate_address(program_id, base);
+    let (listing, _) = find_listing_address(program_id, base, space_x, space_y);
+    let (space_metaplex_metadata, _) = find_metaplex_metadata_address(&metaplex_token_metadata::id(), space_mint);
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*base, false),
+        AccountMeta::new_readonly(neighborhood_metadata, false),
+        AccountMeta::new(*neighborhood_creator, false),
+        AccountMeta::new(space_metadata, false),
+        AccountMeta::new_readonly(*space_mint, false),
+        AccountMeta::new(*buyer, true),
+        AccountMeta::new(get_associated_token_address(buyer, space_mint), false),
+        AccountMeta::new(*seller, false),
+        AccountMeta::new(*seller_token_account, false),
+        AccountMeta::new_readonly(sell_delegate, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+        AccountMeta::new_readonly(rent::id(), false),
+        AccountMeta::new(*treasury, false),
+        AccountMeta::new_readonly(space_metaplex_metadata, false),
+        AccountMeta::new(listing, false),
+        AccountMeta::new_readonly(find_space_rent_account_address(base, space_x, space_y), false),
+    ];
+    accounts.extend(royalty_creators.iter().map(|creator| AccountMeta::new(*creator, false)));
+    build_instruction(
+        program_id,
+        SpaceInstruction::AcceptOffer,
+        &AcceptOfferArgs {
+            space_x,
+            space_y,
+            max_price,
+        },
+        accounts,
+    )
+}
+
+pub fn place_bid(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    bidder: &Pubkey,
+    space_x: i64,
+    space_y: i64,
+    price: u64,
+) -> Instruction {
+    let (space_metadata, _) = find_space_metadata_address(program_id, base, space_x, space_y);
+    let (bid, _) = find_bid_address(program_id, base, space_x, space_y, bidder);
+    build_instruction(
+        program_id,
+        SpaceInstruction::PlaceBid,
+        &PlaceBidArgs {
+            space_x,
+            space_y,
+            price,
+        },
+        vec![
+            AccountMeta::new_readonly(*base, false),
+            AccountMeta::new_readonly(space_metadata, false),
+            AccountMeta::new(bid, false),
+            AccountMeta::new(*bidder, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+pub fn cancel_bid(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    bidder: &Pubkey,
+    space_x: i64,
+    space_y: i64,
+) -> Instruction {
+    let (bid, _) = find_bid_address(program_id, base, space_x, space_y, bidder);
+    build_instruction(
+        program_id,
+        SpaceInstruction::CancelBid,
+        &CancelBidArgs { space_x, space_y },
+        vec![
+            AccountMeta::new_readonly(*base, false),
+            AccountMeta::new(bid, false),
+            AccountMeta::new(*bidder, true),
+        ],
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn accept_bid(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    space_x: i64,
+    space_y: i64,
+    price: u64,
+    owner: &Pubkey,
+    bidder: &Pubkey,
+    listing_seller: &Pubkey,
+    space_mint: &Pubkey,
+    neighborhood_creator: &Pubkey,
+    treasury: &Pubkey,
+    royalty_creators: &[Pubkey],
+) -> Instruction {
+    let (neighborhood_x, neighborhood_y) = get_neighborhood_xy(space_x, space_y);
+    let (neighborhood_metadata, _) =
+        find_neighborhood_metadata_address(program_id, base, neighborhood_x, neighborhood_y);
+    let (space_metadata, _) = find_space_metadata_address(program_id, base, space_x, space_y);
+    let (bid, _) = find_bid_address(program_id, base, space_x, space_y, bidder);
+    let (listing, _) = find_listing_address(program_id, base, space_x, space_y);
+    let (auction, _) = find_auction_address(program_id, base, space_x, space_y);
+    let (space_metaplex_metadata, _) = find_metaplex_metadata_address(&metaplex_token_metadata::id(), space_mint);
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*base, false),
+        AccountMeta::new_readonly(neighborhood_metadata, false),
+        AccountMeta::new(*neighborhood_creator, false),
+        AccountMeta::new(space_metadata, false),
+        AccountMeta::new_readonly(*space_mint, false),
+        AccountMeta::new(*owner, true),
+        AccountMeta::new(get_associated_token_address(owner, space_mint), false),
+        AccountMeta::new(*bidder, false),
+        AccountMeta::new(get_associated_token_address(bidder, space_mint), false),
+        AccountMeta::new(bid, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+        AccountMeta::new_readonly(rent::id(), false),
+        AccountMeta::new(*treasury, false),
+        AccountMeta::new_readonly(space_metaplex_metadata, false),
+        AccountMeta::new_readonly(find_space_rent_account_address(base, space_x, space_y), false),
+        AccountMeta::new(listing, false),
+        AccountMeta::new_readonly(auction, false),
+        AccountMeta::new(*listing_seller, false),
+    ];
+    accounts.extend(royalty_creators.iter().map(|creator| AccountMeta::new(*creator, false)));
+    build_instruction(
+        program_id,
+        SpaceInstruction::AcceptBid,
+        &AcceptBidArgs {
+            space_x,
+            space_y,
+            price,
+        },
+        accounts,
+    )
+}
+
+pub fn set_marketplace_fee(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    creator: &Pubkey,
+    neighborhood_x: i64,
+    neighborhood_y: i64,
+    fee_bps: u16,
+) -> Instruction {
+    let (neighborhood_metadata, _) =
+        find_neighborhood_metadata_address(program_id, base, neighborhood_x, neighborhood_y);
+    build_instruction(
+        program_id,
+        SpaceInstruction::SetMarketplaceFee,
+        &SetMarketplaceFeeArgs {
+            neighborhood_x,
+            neighborhood_y,
+            fee_bps,
+        },
+        vec![
+            AccountMeta::new_readonly(*base, false),
+            AccountMeta::new(neighborhood_metadata, false),
+            AccountMeta::new_readonly(*creator, true),
+        ],
+    )
+}
+
+pub fn set_marketplace_fee_cap(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    authority: &Pubkey,
+    max_fee_bps: u16,
+) -> Instruction {
+    build_instruction(
+        program_id,
+        SpaceInstruction::SetMarketplaceFeeCap,
+        &SetMarketplaceFeeCapArgs { max_fee_bps },
+        vec![
+            AccountMeta::new(*base, false),
+            AccountMeta::new_readonly(*authority, true),
+        ],
+    )
+}
+
+pub fn update_treasury(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    authority: &Pubkey,
+    treasury: &Pubkey,
+    treasury_fee_share_bps: u16,
+) -> Instruction {
+    build_instruction(
+        program_id,
+        SpaceInstruction::UpdateTreasury,
+        &UpdateTreasuryArgs { treasury_fee_share_bps },
+        vec![
+            AccountMeta::new(*base, false),
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new_readonly(*treasury, false),
+        ],
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn create_auction(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    space_mint: &Pubkey,
+    seller: &Pubkey,
+    listing_seller: &Pubkey,
+    space_x: i64,
+    space_y: i64,
+    reserve_price: u64,
+    end_time: u64,
+    min_increment: u64,
+) -> Instruction {
+    let (space_metadata, _) = find_space_metadata_address(program_id, base, space_x, space_y);
+    let (auction, _) = find_auction_address(program_id, base, space_x, space_y);
+    let (sell_delegate, _) = find_sell_delegate_address(program_id, base);
+    let (listing, _) = find_listing_address(program_id, base, space_x, space_y);
+    build_instruction(
+        program_id,
+        SpaceInstruction::CreateAuction,
+        &CreateAuctionArgs {
+            space_x,
+            space_y,
+            reserve_price,
+            end_time,
+            min_increment,
+        },
+        vec![
+            AccountMeta::new_readonly(*base, false),
+            AccountMeta::new(space_metadata, false),
+            AccountMeta::new(auction, false),
+            AccountMeta::new(*seller, true),
+            AccountMeta::new(get_associated_token_address(seller, space_mint), false),
+            AccountMeta::new_readonly(sell_delegate, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(find_space_rent_account_address(base, space_x, space_y), false),
+            AccountMeta::new(listing, false),
+            AccountMeta::new(*listing_seller, false),
+        ],
+    )
+}
+
+pub fn place_auction_bid(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    bidder: &Pubkey,
+    previous_bidder: &Pubkey,
+    space_x: i64,
+    space_y: i64,
+    price: u64,
+) -> Instruction {
+    let (auction, _) = find_auction_address(program_id, base, space_x, space_y);
+    build_instruction(
+        program_id,
+        SpaceInstruction::PlaceAuctionBid,
+        &PlaceAuctionBidArgs {
+            space_x,
+            space_y,
+            price,
+        },
+        vec![
+            AccountMeta::new_readonly(*base, false),
+            AccountMeta::new(auction, false),
+            AccountMeta::new(*bidder, true),
+            AccountMeta::new(*previous_bidder, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn settle_auction(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    space_x: i64,
+    space_y: i64,
+    payer: &Pubkey,
+    seller: &Pubkey,
+    winner: &Pubkey,
+    space_mint: &Pubkey,
+    neighborhood_creator: &Pubkey,
+    treasury: &Pubkey,
+    royalty_creators: &[Pubkey],
+) -> Instruction {
+    let (neighborhood_x, neighborhood_y) = get_neighborhood_xy(space_x, space_y);
+    let (neighborhood_metadata, _) =
+        find_neighborhood_metadata_address(program_id, base, neighborhood_x, neighborhood_y);
+    let (space_metadata, _) = find_space_metadata_address(program_id, base, space_x, space_y);
+    let (auction, _) = find_auction_address(program_id, base, space_x, space_y);
+    let (sell_delegate, _) = find_sell_delegate_address(program_id, base);
+    let (space_metaplex_metadata, _) = find_metaplex_metadata_address(&metaplex_token_metadata::id(), space_mint);
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*base, false),
+        AccountMeta::new_readonly(neighborhood_metadata, false),
+        AccountMeta::new(*neighborhood_creator, false),
+        AccountMeta::new(space_metadata, false),
+        AccountMeta::new_readonly(*space_mint, false),
+        AccountMeta::new(*payer, true),
+        AccountMeta::new(*seller, false),
+        AccountMeta::new(get_associated_token_address(seller, space_mint), false),
+        AccountMeta::new(*winner, false),
+        AccountMeta::new(get_associated_token_address(winner, space_mint), false),
+        AccountMeta::new(auction, false),
+        AccountMeta::new_readonly(sell_delegate, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+        AccountMeta::new_readonly(rent::id(), false),
+        AccountMeta::new(*treasury, false),
+        AccountMeta::new_readonly(space_metaplex_metadata, false),
+        AccountMeta::new_readonly(find_space_rent_account_address(base, space_x, space_y), false),
+    ];
+    accounts.extend(royalty_creators.iter().map(|creator| AccountMeta::new(*creator, false)));
+    build_instruction(
+        program_id,
+        SpaceInstruction::SettleAuction,
+        &SettleAuctionArgs { space_x, space_y },
+        accounts,
+    )
+}
+
+// list in lamports at a price declining from start_price to end_price between start_time and end_time,
+// listing_seller is the seller recorded in the space's listing account as in change_token_offer
+#[allow(clippy::too_many_arguments)]
+pub fn change_dutch_offer(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    space_mint: &Pubkey,
+    owner: &Pubkey,
+    listing_seller: &Pubkey,
+    space_x: i64,
+    space_y: i64,
+    start_price: u64,
+    end_price: u64,
+    start_time: u64,
+    end_time: u64,
+) -> Instruction {
+    let (space_metadata, _) = find_space_metadata_address(program_id, base, space_x, space_y);
+    let (listing, _) = find_listing_address(program_id, base, space_x, space_y);
+    let (sell_delegate, _) = find_sell_delegate_address(program_id, base);
+    let (auction, _) = find_auction_address(program_id, base, space_x, space_y);
+    build_instruction(
+        program_id,
+        SpaceInstruction::ChangeDutchOffer,
+        &ChangeDutchOfferArgs {
+            space_x,
+            space_y,
+            start_price,
+            end_price,
+            start_time,
+            end_time,
+        },
+        vec![
+            AccountMeta::new_readonly(*base, false),
+            AccountMeta::new(space_metadata, false),
+            AccountMeta::new(*owner, true),
+            AccountMeta::new(get_associated_token_address(owner, space_mint), false),
+            AccountMeta::new(listing, false),
+            AccountMeta::new_readonly(sell_delegate, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(find_space_rent_account_address(base, space_x, space_y), false),
+            AccountMeta::new_readonly(auction, false),
+            AccountMeta::new(*listing_seller, false),
+        ],
+    )
+}
+
+// revoke is set when the seller signs, leaving the sell delegate approved when cleared by a crank
+pub fn clear_expired_offer(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    seller: &Pubkey,
+    space_mint: &Pubkey,
+    space_x: i64,
+    space_y: i64,
+    revoke: bool,
+) -> Instruction {
+    let (space_metadata, _) = find_space_metadata_address(program_id, base, space_x, space_y);
+    let (listing, _) = find_listing_address(program_id, base, space_x, space_y);
+    build_instruction(
+        program_id,
+        SpaceInstruction::ClearExpiredOffer,
+        &ClearExpiredOfferArgs { space_x, space_y },
+        vec![
+            AccountMeta::new_readonly(*base, false),
+            AccountMeta::new(space_metadata, false),
+            AccountMeta::new(listing, false),
+            AccountMeta::new(*seller, revoke),
+            AccountMeta::new(get_associated_token_address(seller, space_mint), false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+    )
+}
+
+// space_mints[k] is the mint of the space listed by offers[k], listing_sellers[k] the seller recorded in its listing account
+pub fn change_offer_batch(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    owner: &Pubkey,
+    space_mints: &[Pubkey],
+    listing_sellers: &[Pubkey],
+    offers: Vec<SpaceOffer>,
+) -> Instruction {
+    // zipping unequal slices would silently drop offers or list them against the wrong accounts
+    assert_eq!(space_mints.len(), offers.len(), "one space mint per offer");
+    assert_eq!(listing_sellers.len(), offers.len(), "one listing seller per offer");
+    let (sell_delegate, _) = find_sell_delegate_address(program_id, base);
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*base, false),
+        AccountMeta::new(*owner, true),
+        AccountMeta::new_readonly(sell_delegate, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ];
+    for ((space_mint, listing_seller), offer) in space_mints.iter().zip(listing_sellers.iter()).zip(offers.iter()) {
+        let (space_metadata, _) = find_space_metadata_address(program_id, base, offer.space_x, offer.space_y);
+        accounts.push(AccountMeta::new(space_metadata, false));
+        accounts.push(AccountMeta::new(get_associated_token_address(owner, space_mint), false));
+        accounts.push(AccountMeta::new_readonly(find_space_rent_account_address(base, offer.space_x, offer.space_y), false));
+        let (auction, _) = find_auction_address(program_id, base, offer.space_x, offer.space_y);
+        accounts.push(AccountMeta::new_readonly(auction, false));
+        let (listing, _) = find_listing_address(program_id, base, offer.space_x, offer.space_y);
+        accounts.push(AccountMeta::new(listing, false));
+        accounts.push(AccountMeta::new(*listing_seller, false));
+    }
+    build_instruction(
+        program_id,
+        SpaceInstruction::ChangeOfferBatch,
+        &ChangeOfferBatchArgs { offers },
+        accounts,
+    )
+}
+
+pub fn init_voucher_system(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    creator: &Pubkey,
+    voucher_mint_auth: &Pubkey,
+    neighborhood_x: i64,
+    neighborhood_y: i64,
+) -> Instruction {
+    let (neighborhood_metadata, _) =
+        find_neighborhood_metadata_address(program_id, base, neighborhood_x, neighborhood_y);
+    let (voucher_mint, _) =
+        find_voucher_mint_address(program_id, base, neighborhood_x, neighborhood_y);
+    let (sink_account_voucher, _) =
+        find_voucher_sink_address(program_id, base, neighborhood_x, neighborhood_y);
+    build_instruction(
+        program_id,
+        SpaceInstruction::InitVoucherSystem,
+        &InitVoucherSystemArgs {
+            neighborhood_x,
+            neighborhood_y,
+        },
+        vec![
+            AccountMeta::new_readonly(*base, false),
+            AccountMeta::new_readonly(neighborhood_metadata, false),
+            AccountMeta::new(*creator, true),
+            AccountMeta::new_readonly(*voucher_mint_auth, true),
+            AccountMeta::new(voucher_mint, false),
+            AccountMeta::new(get_associated_token_address(voucher_mint_auth, &voucher_mint), false),
+            AccountMeta::new(sink_account_voucher, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+            AccountMeta::new_readonly(rent::id(), false),
+        ],
+    )
+}
+
+pub fn revoke_authority_privileges(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    revoker: &Pubkey,
+) -> Instruction {
+    build_instruction(
+        program_id,
+        SpaceInstruction::RevokeAuthorityPrivileges,
+        &RevokeAuthorityPrivilegesArgs {},
+        vec![
+            AccountMeta::new(*base, false),
+            AccountMeta::new_readonly(*revoker, true),
+        ],
+    )
+}
+
+pub fn update_authority(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    current_authority: &Pubkey,
+    new_authority: &Pubkey,
+) -> Instruction {
+    build_instruction(
+        program_id,
+        SpaceInstruction::UpdateAuthority,
+        &UpdateAuthorityArgs {},
+        vec![
+            AccountMeta::new(*base, false),
+            AccountMeta::new_readonly(*current_authority, true),
+            AccountMeta::new_readonly(*new_authority, false),
+        ],
+    )
+}
+
+pub fn temp_add_xy( // elim
+    program_id: &Pubkey,
+    base: &Pubkey,
+    space_mint: &Pubkey,
+    owner: &Pubkey,
+    space_x: i64,
+    space_y: i64,
+) -> Instruction {
+    let (space_metadata, _) = find_space_metadata_address(program_id, base, space_x, space_y);
+    build_instruction(
+        program_id,
+        SpaceInstruction::TempAddxy,
+        &TempAddxyArgs { space_x, space_y },
+        vec![
+            AccountMeta::new_readonly(*base, false),
+            AccountMeta::new(space_metadata, false),
+            AccountMeta::new_readonly(get_associated_token_address(owner, space_mint), false),
+            AccountMeta::new_readonly(*owner, true),
+        ],
+    )
+}
+
+pub fn change_neighborhood_name( // elim
+    program_id: &Pubkey,
+    base: &Pubkey,
+    creator: &Pubkey,
+    neighborhood_x: i64,
+    neighborhood_y: i64,
+    neighborhood_name: [u8; 64],
+) -> Instruction {
+    let (neighborhood_metadata, _) =
+        find_neighborhood_metadata_address(program_id, base, neighborhood_x, neighborhood_y);
+    build_instruction(
+        program_id,
+        SpaceInstruction::ChangeNeighborhoodName,
+        &ChangeNeighborhoodNameArgs {
+            neighborhood_x,
+            neighborhood_y,
+            neighborhood_name,
+        },
+        vec![
+            AccountMeta::new_readonly(*base, false),
+            AccountMeta::new(neighborhood_metadata, false),
+            AccountMeta::new_readonly(*creator, true),
+        ],
+    )
 }
@@ -1,6 +1,6 @@
+pub use extend_common::{error, pda, validation_utils};
+
 pub mod entrypoint;
-pub mod error;
 pub mod instruction;
 pub mod processor;
 pub mod state;
-pub mod validation_utils;
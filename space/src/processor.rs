@@ -8,7 +8,10 @@ use crate::{
     instruction::{
         AcceptOfferArgs, ChangeOfferArgs, SpaceInstruction, InitBaseArgs,
         InitSpaceMetadataArgs, InitNeighborhoodMetadataArgs, RevokeAuthorityPrivilegesArgs,
-        InitVoucherSystemArgs, UpdateAuthorityArgs,
+        InitVoucherSystemArgs, UpdateAuthorityArgs, ChangeEscrowOfferArgs,
+        PlaceBidArgs, CancelBidArgs, AcceptBidArgs, SetMarketplaceFeeArgs, SetMarketplaceFeeCapArgs,
+        UpdateTreasuryArgs, CreateAuctionArgs, PlaceAuctionBidArgs, SettleAuctionArgs,
+        ChangeDutchOfferArgs, ClearExpiredOfferArgs, ChangeOfferBatchArgs,
         TempAddxyArgs, ChangeNeighborhoodNameArgs // elim
     },
 };
@@ -21,6 +24,19 @@ pub mod accept_offer;
 pub mod init_voucher_system;
 pub mod revoke_authority_privileges;
 pub mod update_authority;
+pub mod change_escrow_offer;
+pub mod place_bid;
+pub mod cancel_bid;
+pub mod accept_bid;
+pub mod set_marketplace_fee;
+pub mod set_marketplace_fee_cap;
+pub mod update_treasury;
+pub mod create_auction;
+pub mod place_auction_bid;
+pub mod settle_auction;
+pub mod change_dutch_offer;
+pub mod clear_expired_offer;
+pub mod change_offer_batch;
 pub mod temp_add_xy; // elim
 pub mod change_nbdhoodname; // elim
 
@@ -78,6 +94,71 @@ impl Processor {
                 msg!("Instruction: update authority");
                 update_authority::process(program_id, accounts, &args)
             }
+            SpaceInstruction::ChangeEscrowOffer => {
+                let args = ChangeEscrowOfferArgs::try_from_slice(rest)?;
+                msg!("Instruction: change escrow offer");
+                change_escrow_offer::process(program_id, accounts, &args)
+            }
+            SpaceInstruction::PlaceBid => {
+                let args = PlaceBidArgs::try_from_slice(rest)?;
+                msg!("Instruction: place bid");
+                place_bid::process(program_id, accounts, &args)
+            }
+            SpaceInstruction::CancelBid => {
+                let args = CancelBidArgs::try_from_slice(rest)?;
+                msg!("Instruction: cancel bid");
+                cancel_bid::process(program_id, accounts, &args)
+            }
+            SpaceInstruction::AcceptBid => {
+                let args = AcceptBidArgs::try_from_slice(rest)?;
+                msg!("Instruction: accept bid");
+                accept_bid::process(program_id, accounts, &args)
+            }
+            SpaceInstruction::SetMarketplaceFee => {
+                let args = SetMarketplaceFeeArgs::try_from_slice(rest)?;
+                msg!("Instruction: set marketplace fee");
+                set_marketplace_fee::process(program_id, accounts, &args)
+            }
+            SpaceInstruction::SetMarketplaceFeeCap => {
+                let args = SetMarketplaceFeeCapArgs::try_from_slice(rest)?;
+                msg!("Instruction: set marketplace fee cap");
+                set_marketplace_fee_cap::process(program_id, accounts, &args)
+            }
+            SpaceInstruction::UpdateTreasury => {
+                let args = UpdateTreasuryArgs::try_from_slice(rest)?;
+                msg!("Instruction: update treasury");
+                update_treasury::process(program_id, accounts, &args)
+            }
+            SpaceInstruction::CreateAuction => {
+                let args = CreateAuctionArgs::try_from_slice(rest)?;
+                msg!("Instruction: create auction");
+                create_auction::process(program_id, accounts, &args)
+            }
+            SpaceInstruction::PlaceAuctionBid => {
+                let args = PlaceAuctionBidArgs::try_from_slice(rest)?;
+                msg!("Instruction: place auction bid");
+                place_auction_bid::process(program_id, accounts, &args)
+            }
+            SpaceInstruction::SettleAuction => {
+                let args = SettleAuctionArgs::try_from_slice(rest)?;
+                msg!("Instruction: settle auction");
+                settle_auction::process(program_id, accounts, &args)
+            }
+            SpaceInstruction::ChangeDutchOffer => {
+                let args = ChangeDutchOfferArgs::try_from_slice(rest)?;
+                msg!("Instruction: change dutch offer");
+                change_dutch_offer::process(program_id, accounts, &args)
+            }
+            SpaceInstruction::ClearExpiredOffer => {
+                let args = ClearExpiredOfferArgs::try_from_slice(rest)?;
+                msg!("Instruction: clear expired offer");
+                clear_expired_offer::process(program_id, accounts, &args)
+            }
+            SpaceInstruction::ChangeOfferBatch => {
+                let args = ChangeOfferBatchArgs::try_from_slice(rest)?;
+                msg!("Instruction: change offer batch");
+                change_offer_batch::process(program_id, accounts, &args)
+            }
 
             
             SpaceInstruction::ChangeNeighborhoodName => { // elim
@@ -0,0 +1,216 @@
+use borsh::{BorshSerialize};
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    entrypoint::ProgramResult,
+    borsh::try_from_slice_unchecked,
+    msg,
+    program::invoke,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_program,
+    sysvar::{rent},
+};
+use spl_associated_token_account;
+use spl_token;
+use extend_common::utils::{get_marketplace_fee, get_neighborhood_xy, split_fee};
+
+use crate::{
+    error::CustomError,
+    instruction::AcceptBidArgs,
+    pda::{create_bid_address, create_neighborhood_metadata_address, create_space_metadata_address},
+    processor::processor_utils::{
+        assert_not_auctioned,
+        assert_not_rented,
+        close_listing,
+        close_program_account,
+        get_royalties,
+        get_seller_proceeds,
+        transfer_lamports,
+    },
+    state::{
+        Base,
+        Bid,
+        NeighborhoodMetadata,
+        SpaceMetadata,
+    },
+    validation_utils::{assert_is_ata, assert_keys_equal, assert_owned_by},
+};
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: &AcceptBidArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let base = next_account_info(account_info_iter)?;
+    let neighborhood_metadata = next_account_info(account_info_iter)?;
+    let neighborhood_creator = next_account_info(account_info_iter)?;
+    let space_metadata = next_account_info(account_info_iter)?;
+    let space_mint = next_account_info(account_info_iter)?;
+    let owner = next_account_info(account_info_iter)?;
+    let owner_ata_space = next_account_info(account_info_iter)?;
+    let bidder = next_account_info(account_info_iter)?;
+    let bidder_ata_space = next_account_info(account_info_iter)?;
+    let bid = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+    let associated_token_program = next_account_info(account_info_iter)?;
+    let rent_program = next_account_info(account_info_iter)?;
+    let treasury = next_account_info(account_info_iter)?;
+    let space_metaplex_metadata = next_account_info(account_info_iter)?;
+    let rent_account = next_account_info(account_info_iter)?;
+    let listing = next_account_info(account_info_iter)?;
+    let auction = next_account_info(account_info_iter)?;
+    let listing_seller = next_account_info(account_info_iter)?;
+
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // check programs
+    assert_keys_equal(system_program::id(), *system_program.key)?;
+    assert_keys_equal(spl_token::id(), *token_program.key)?;
+    assert_keys_equal(spl_associated_token_account::id(), *associated_token_program.key)?;
+    assert_keys_equal(rent::id(), *rent_program.key)?;
+
+    //deserialize and verify base
+    assert_owned_by(base, program_id)?;
+    let base_data: Base = try_from_slice_unchecked(&base.data.borrow())?;
+
+    //deserialize and verify space metadata
+    let mut space_metadata_data: SpaceMetadata = try_from_slice_unchecked(&space_metadata.data.borrow_mut())?;
+
+    if space_metadata_data.mint != *space_mint.key {
+        msg!("Error: space account does not match mint account");
+        return Err(CustomError::MintMismatch.into());
+    }
+
+    let key = create_space_metadata_address(
+        program_id,
+        base.key,
+        args.space_x,
+        args.space_y,
+        space_metadata_data.bump,
+    )?;
+    assert_keys_equal(key, *space_metadata.key)?;
+
+    //deserialize and verify neighborhood metadata
+    let neighborhood_metadata_data: NeighborhoodMetadata = try_from_slice_unchecked(&neighborhood_metadata.data.borrow_mut())?;
+    let (neighborhood_x, neighborhood_y) = get_neighborhood_xy(args.space_x, args.space_y);
+    let key = create_neighborhood_metadata_address(
+        program_id,
+        base.key,
+        neighborhood_x,
+        neighborhood_y,
+        neighborhood_metadata_data.bump,
+    )?;
+    assert_keys_equal(key, *neighborhood_metadata.key)?;
+
+    // check neighborhood creator is passed in correctly
+    assert_keys_equal(neighborhood_metadata_data.creator, *neighborhood_creator.key)?;
+
+    // check treasury is passed in correctly
+    assert_keys_equal(base_data.get_treasury(), *treasury.key)?;
+
+    // check royalty creators are passed in correctly
+    let royalties = get_royalties(account_info_iter, space_metaplex_metadata, space_mint.key, args.price)?;
+
+    //deserialize and verify bid
+    let bid_data: Bid = try_from_slice_unchecked(&bid.data.borrow())?;
+    let key = create_bid_address(
+        program_id,
+        base.key,
+        args.space_x,
+        args.space_y,
+        bidder.key,
+        bid_data.bump,
+    )?;
+    assert_keys_equal(key, *bid.key)?;
+
+    // check user price equals bid price
+    if bid_data.price != args.price {
+        msg!("bid price is {}", bid_data.price);
+        msg!("args price is {}", args.price);
+        msg!("Error: bid has changed");
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    // check owner holds the space
+    assert_is_ata(owner_ata_space, owner.key, space_mint.key)?;
+    let owner_ata_space_data = spl_token::state::Account::unpack(&owner_ata_space.data.borrow())?;
+    if owner_ata_space_data.amount != 1 {
+        msg!("Error: token account does not own token");
+        return Err(CustomError::MissingTokenOwner.into());
+    }
+
+    // check space is not rented out or auctioned, a sale would take the space from the auction's bidders
+    assert_not_rented(rent_account, base.key, args.space_x, args.space_y)?;
+    assert_not_auctioned(program_id, auction, base.key, args.space_x, args.space_y)?;
+
+    // create ATA if necessary
+    if bidder_ata_space.data_len() == 0 {
+        invoke(
+            &spl_associated_token_account::create_associated_token_account(
+                owner.key,
+                bidder.key,
+                space_mint.key,
+            ),
+            &[
+                owner.clone(),
+                bidder_ata_space.clone(),
+                bidder.clone(),
+                space_mint.clone(),
+                system_program.clone(),
+                token_program.clone(),
+                rent_program.clone(),
+                associated_token_program.clone(),
+            ],
+        )?;
+    }
+    assert_is_ata(bidder_ata_space, bidder.key, space_mint.key)?;
+
+    // transfer NFT from owner to bidder
+    invoke(
+        &spl_token::instruction::transfer(
+            token_program.key,
+            owner_ata_space.key,
+            bidder_ata_space.key,
+            owner.key,
+            &[],
+            1,
+        )?,
+        &[
+            token_program.clone(),
+            owner_ata_space.clone(),
+            bidder_ata_space.clone(),
+            owner.clone(),
+        ],
+    )?;
+
+    // pay owner, neighborhood creator, treasury and royalty creators out of the bid, returning its rent to the bidder
+    let marketplace_fee = get_marketplace_fee(
+        args.price,
+        neighborhood_metadata_data.get_marketplace_fee_bps(),
+        base_data.get_max_marketplace_fee_bps(),
+    )?;
+    let (creator_fee, treasury_fee) = split_fee(marketplace_fee, base_data.treasury_fee_share_bps)?;
+    let proceeds = get_seller_proceeds(args.price, marketplace_fee, &royalties)?;
+    transfer_lamports(bid, owner, proceeds)?;
+    transfer_lamports(bid, neighborhood_creator, creator_fee)?;
+    transfer_lamports(bid, treasury, treasury_fee)?;
+    for (creator, royalty) in royalties {
+        transfer_lamports(bid, creator, royalty)?;
+    }
+    close_program_account(bid, bidder)?;
+
+    // any sell offer is void now that the space changed hands, its listing account rent goes back to the seller who listed it
+    close_listing(program_id, base.key, listing, listing_seller, args.space_x, args.space_y)?;
+    space_metadata_data.price = 0;
+    space_metadata_data.payment_mint = Pubkey::default();
+    space_metadata_data.escrow_seller = Pubkey::default();
+    space_metadata_data.listing_terms = false;
+    space_metadata_data.serialize(&mut *space_metadata.data.borrow_mut())?;
+
+    Ok(())
+}
@@ -3,6 +3,7 @@ use solana_program::{
     account_info::{AccountInfo, next_account_info},
     entrypoint::ProgramResult,
     borsh::try_from_slice_unchecked,
+    clock::Clock,
     msg,
     program::{invoke, invoke_signed},
     program_error::ProgramError,
@@ -10,24 +11,31 @@ use solana_program::{
     pubkey::Pubkey,
     system_instruction,
     system_program,
-    sysvar::{rent},
+    sysvar::{rent, Sysvar},
 };
 use spl_associated_token_account;
 use spl_token;
+use extend_common::utils::{get_dutch_price, get_marketplace_fee, get_neighborhood_xy, split_fee};
 
 use crate::{
     error::CustomError,
     instruction::AcceptOfferArgs,
+    pda::{
+        create_neighborhood_metadata_address,
+        create_space_metadata_address,
+        find_listing_address,
+        find_sell_delegate_address,
+        find_space_escrow_address,
+    },
+    processor::processor_utils::{assert_not_rented, close_program_account, get_royalties, get_seller_proceeds},
     state::{
-        MARKETPLACE_FEE,
-        NEIGHBORHOOD_METADATA_SEED,
-        SPACE_METADATA_SEED,
         SELL_DELEGATE_SEED,
+        Base,
+        Listing,
         NeighborhoodMetadata,
         SpaceMetadata,
     },
-    validation_utils::{assert_is_ata, assert_keys_equal},
-    processor::processor_utils::{get_neighborhood_xy},
+    validation_utils::{assert_is_ata, assert_keys_equal, assert_owned_by},
 };
 
 pub fn process(
@@ -50,6 +58,10 @@ pub fn process(
     let token_program = next_account_info(account_info_iter)?;
     let associated_token_program = next_account_info(account_info_iter)?;
     let rent_program = next_account_info(account_info_iter)?;
+    let treasury = next_account_info(account_info_iter)?;
+    let space_metaplex_metadata = next_account_info(account_info_iter)?;
+    let listing = next_account_info(account_info_iter)?;
+    let rent_account = next_account_info(account_info_iter)?;
 
     // check programs
     assert_keys_equal(system_program::id(), *system_program.key)?;
@@ -57,6 +69,10 @@ pub fn process(
     assert_keys_equal(spl_associated_token_account::id(), *associated_token_program.key)?;
     assert_keys_equal(rent::id(), *rent_program.key)?;
 
+    //deserialize and verify base
+    assert_owned_by(base, program_id)?;
+    let base_data: Base = try_from_slice_unchecked(&base.data.borrow())?;
+
     //deserialize and verify space metadata
     let mut space_metadata_data: SpaceMetadata = try_from_slice_unchecked(&space_metadata.data.borrow_mut())?;
     
@@ -65,32 +81,32 @@ pub fn process(
         return Err(CustomError::MintMismatch.into());
     }
 
-    let seeds_space_metadata = &[
-        &base.key.to_bytes(),
-        SPACE_METADATA_SEED,
-        &args.space_x.to_le_bytes(),
-        &args.space_y.to_le_bytes(),
-        &[space_metadata_data.bump],
-    ];
-    let key = Pubkey::create_program_address(seeds_space_metadata, program_id)?;
+    let key = create_space_metadata_address(
+        program_id,
+        base.key,
+        args.space_x,
+        args.space_y,
+        space_metadata_data.bump,
+    )?;
     assert_keys_equal(key, *space_metadata.key)?;
 
     //deserialize and verify neighborhood metadata
     let neighborhood_metadata_data: NeighborhoodMetadata = try_from_slice_unchecked(&neighborhood_metadata.data.borrow_mut())?;
     let (neighborhood_x, neighborhood_y) = get_neighborhood_xy(args.space_x, args.space_y);
-    let seeds_neighborhood_metadata = &[
-        &base.key.to_bytes(),
-        NEIGHBORHOOD_METADATA_SEED,
-        &neighborhood_x.to_le_bytes(),
-        &neighborhood_y.to_le_bytes(),
-        &[neighborhood_metadata_data.bump],
-    ];
-    let key = Pubkey::create_program_address(seeds_neighborhood_metadata, program_id)?;
+    let key = create_neighborhood_metadata_address(
+        program_id,
+        base.key,
+        neighborhood_x,
+        neighborhood_y,
+        neighborhood_metadata_data.bump,
+    )?;
     assert_keys_equal(key, *neighborhood_metadata.key)?;
 
+    // check space is not rented out
+    assert_not_rented(rent_account, base.key, args.space_x, args.space_y)?;
+
     // verify sell_delegate
-    let seeds_sell_delegate = &[&base.key.to_bytes(), SELL_DELEGATE_SEED];
-    let (key, bump_sell_delegate) = Pubkey::find_program_address(seeds_sell_delegate, program_id);
+    let (key, bump_sell_delegate) = find_sell_delegate_address(program_id, base.key);
     assert_keys_equal(key, *sell_delegate.key)?;
     let seeds_sell_delegate = &[&base.key.to_bytes(), SELL_DELEGATE_SEED, &[bump_sell_delegate]];
 
@@ -114,28 +130,85 @@ pub fn process(
         )?;
     }
     
-    // check ATAs
+    // check ATAs, bob's token account is the escrow for escrowed listings
+    let (escrow, _) = find_space_escrow_address(program_id, base.key, args.space_x, args.space_y);
+    let escrowed = *bob_ata_space.key == escrow;
     assert_is_ata(alice_ata_space, alice.key, space_mint.key)?;
-    assert_is_ata(bob_ata_space, bob.key, space_mint.key)?;
+    if !escrowed {
+        assert_is_ata(bob_ata_space, bob.key, space_mint.key)?;
+    }
 
     // check neighborhood creator is passed in correctly
     assert_keys_equal(neighborhood_metadata_data.creator, *neighborhood_creator.key)?;
 
-    // check that B is listed
-    let bob_ata_space_data = spl_token::state::Account::unpack(&bob_ata_space.data.borrow())?;
-    if !bob_ata_space_data.delegate.contains(sell_delegate.key) {
+    // check treasury is passed in correctly
+    assert_keys_equal(base_data.get_treasury(), *treasury.key)?;
+
+    // current listing price, subject to the terms in the listing account if the listing has any
+    let (key, _) = find_listing_address(program_id, base.key, args.space_x, args.space_y);
+    assert_keys_equal(key, *listing.key)?;
+    let price = if space_metadata_data.listing_terms {
+        assert_owned_by(listing, program_id)?;
+        let listing_data: Listing = try_from_slice_unchecked(&listing.data.borrow())?;
+        assert_keys_equal(listing_data.seller, *bob.key)?;
+        let now_ts = Clock::get()?.unix_timestamp as u64;
+        if listing_data.expires_at != 0 && now_ts >= listing_data.expires_at {
+            msg!("Error: listing has expired");
+            return Err(CustomError::ListingExpired.into());
+        }
+        if listing_data.end_time != 0 {
+            get_dutch_price(
+                listing_data.start_price,
+                listing_data.end_price,
+                listing_data.start_time,
+                listing_data.end_time,
+                now_ts,
+            )
+        } else {
+            space_metadata_data.price
+        }
+    } else {
+        space_metadata_data.price
+    };
+
+    // check royalty creators are passed in correctly
+    let royalties = get_royalties(account_info_iter, space_metaplex_metadata, space_mint.key, price)?;
+
+    // check that B is listed, a cleared price means the space is not for sale even if the
+    // sell delegate is still approved, e.g. while it is auctioned
+    if space_metadata_data.price == 0 {
         msg!("Error: token not listed");
         return Err(ProgramError::InvalidAccountData);
     }
+    if escrowed {
+        if space_metadata_data.escrow_seller != *bob.key {
+            msg!("Error: token not escrowed by seller");
+            return Err(ProgramError::InvalidAccountData);
+        }
+    }
+    else {
+        let bob_ata_space_data = spl_token::state::Account::unpack(&bob_ata_space.data.borrow())?;
+        if !bob_ata_space_data.delegate.contains(sell_delegate.key) {
+            msg!("Error: token not listed");
+            return Err(ProgramError::InvalidAccountData);
+        }
+    }
 
-    // check user price equals true price
-    if space_metadata_data.price != args.price {
-        msg!("space metadata price is {}",space_metadata_data.price);
-        msg!("args price is {}",args.price);
+    // check true price is at most the user's maximum price
+    if price > args.max_price {
+        msg!("listing price is {}",price);
+        msg!("args max price is {}",args.max_price);
         msg!("Error: listing has changed");
         return Err(ProgramError::InvalidInstructionData);
     }
 
+    // check user pays in the mint the listing is priced in, no payment mint for lamports
+    let payment_mint = next_account_info(account_info_iter).ok();
+    if space_metadata_data.payment_mint != payment_mint.map_or(Pubkey::default(), |mint| *mint.key) {
+        msg!("Error: listing payment mint has changed");
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
     // transfer NFT from bob to alice
     invoke_signed(
         &spl_token::instruction::transfer(
@@ -155,37 +228,137 @@ pub fn process(
         &[seeds_sell_delegate],
     )?;
 
-    let marketplace_fee: u64 = (args.price as f64 * MARKETPLACE_FEE) as u64;
-    
-    // transfer SOL from alice to bob
-    invoke(
-        &system_instruction::transfer(
-            alice.key,
-            bob.key,
-            args.price - marketplace_fee,
-        ),
-        &[
-            alice.clone(),
-            bob.clone(),
-            system_program.clone(),
-        ],
-    )?;
+    // close escrow, returning its rent to bob
+    if escrowed {
+        invoke_signed(
+            &spl_token::instruction::close_account(
+                token_program.key,
+                bob_ata_space.key,
+                bob.key,
+                sell_delegate.key,
+                &[],
+            )?,
+            &[
+                token_program.clone(),
+                bob_ata_space.clone(),
+                bob.clone(),
+                sell_delegate.clone(),
+            ],
+            &[seeds_sell_delegate],
+        )?;
+        space_metadata_data.escrow_seller = Pubkey::default();
+    }
 
-    // transfer marketplace fee to neighborhood creator
-    invoke(
-        &system_instruction::transfer(
-            alice.key,
-            neighborhood_creator.key,
-            marketplace_fee,
-        ),
-        &[
-            alice.clone(),
-            neighborhood_creator.clone(),
-            system_program.clone(),
-        ],
+    let marketplace_fee = get_marketplace_fee(
+        price,
+        neighborhood_metadata_data.get_marketplace_fee_bps(),
+        base_data.get_max_marketplace_fee_bps(),
     )?;
 
+    let (creator_fee, treasury_fee) = split_fee(marketplace_fee, base_data.treasury_fee_share_bps)?;
+    let proceeds = get_seller_proceeds(price, marketplace_fee, &royalties)?;
+
+    if let Some(payment_mint) = payment_mint {
+        let alice_ata_payment = next_account_info(account_info_iter)?;
+        let bob_ata_payment = next_account_info(account_info_iter)?;
+        let creator_ata_payment = next_account_info(account_info_iter)?;
+        let treasury_ata_payment = next_account_info(account_info_iter)?;
+
+        assert_is_ata(alice_ata_payment, alice.key, payment_mint.key)?;
+        let decimals = spl_token::state::Mint::unpack(&payment_mint.data.borrow())?.decimals;
+
+        let mut payments = vec![
+            (bob, bob_ata_payment, proceeds),
+            (neighborhood_creator, creator_ata_payment, creator_fee),
+            (treasury, treasury_ata_payment, treasury_fee),
+        ];
+        for (creator, royalty) in royalties {
+            payments.push((creator, next_account_info(account_info_iter)?, royalty));
+        }
+
+        // transfer tokens from alice to bob, neighborhood creator, treasury and royalty creators,
+        // creating their ATAs for payment mint if necessary
+        for (wallet, wallet_ata, amount) in payments {
+            if amount == 0 {
+                continue;
+            }
+            if wallet_ata.data_len() == 0 {
+                invoke(
+                    &spl_associated_token_account::create_associated_token_account(
+                        alice.key,
+                        wallet.key,
+                        payment_mint.key,
+                    ),
+                    &[
+                        alice.clone(),
+                        wallet_ata.clone(),
+                        wallet.clone(),
+                        payment_mint.clone(),
+                        system_program.clone(),
+                        token_program.clone(),
+                        rent_program.clone(),
+                        associated_token_program.clone(),
+                    ],
+                )?;
+            }
+            assert_is_ata(wallet_ata, wallet.key, payment_mint.key)?;
+
+            invoke(
+                &spl_token::instruction::transfer_checked(
+                    token_program.key,
+                    alice_ata_payment.key,
+                    payment_mint.key,
+                    wallet_ata.key,
+                    alice.key,
+                    &[],
+                    amount,
+                    decimals,
+                )?,
+                &[
+                    token_program.clone(),
+                    alice_ata_payment.clone(),
+                    payment_mint.clone(),
+                    wallet_ata.clone(),
+                    alice.clone(),
+                ],
+            )?;
+        }
+    } else {
+        let mut payments = vec![
+            (bob, proceeds),
+            (neighborhood_creator, creator_fee),
+            (treasury, treasury_fee),
+        ];
+        payments.extend(royalties);
+
+        // transfer SOL from alice to bob, neighborhood creator, treasury and royalty creators
+        for (wallet, amount) in payments {
+            if amount == 0 {
+                continue;
+            }
+            invoke(
+                &system_instruction::transfer(
+                    alice.key,
+                    wallet.key,
+                    amount,
+                ),
+                &[
+                    alice.clone(),
+                    wallet.clone(),
+                    system_program.clone(),
+                ],
+            )?;
+        }
+    }
+
+    // close listing account, returning its rent to bob
+    if space_metadata_data.listing_terms {
+        close_program_account(listing, bob)?;
+    }
+
     space_metadata_data.price = 0;
+    space_metadata_data.payment_mint = Pubkey::default();
+    space_metadata_data.listing_terms = false;
     space_metadata_data.serialize(&mut *space_metadata.data.borrow_mut())?;
 
     msg!("done!");
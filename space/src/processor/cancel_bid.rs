@@ -0,0 +1,47 @@
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    entrypoint::ProgramResult,
+    borsh::try_from_slice_unchecked,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use crate::{
+    instruction::CancelBidArgs,
+    pda::create_bid_address,
+    processor::processor_utils::close_program_account,
+    state::Bid,
+    validation_utils::assert_keys_equal,
+};
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: &CancelBidArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let base = next_account_info(account_info_iter)?;
+    let bid = next_account_info(account_info_iter)?;
+    let bidder = next_account_info(account_info_iter)?;
+
+    if !bidder.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    //deserialize and verify bid
+    let bid_data: Bid = try_from_slice_unchecked(&bid.data.borrow())?;
+    let key = create_bid_address(
+        program_id,
+        base.key,
+        args.space_x,
+        args.space_y,
+        bidder.key,
+        bid_data.bump,
+    )?;
+    assert_keys_equal(key, *bid.key)?;
+
+    // refund price and rent to bidder
+    close_program_account(bid, bidder)?;
+
+    Ok(())
+}
@@ -0,0 +1,126 @@
+use borsh::{BorshSerialize};
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    entrypoint::ProgramResult,
+    borsh::try_from_slice_unchecked,
+    msg,
+    program::invoke,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_program,
+};
+use spl_token;
+
+use crate::{
+    instruction::ChangeDutchOfferArgs,
+    pda::{create_space_metadata_address, find_sell_delegate_address},
+    processor::processor_utils::{assert_not_auctioned, assert_not_rented, assert_space_held, write_listing},
+    state::{Listing, SpaceMetadata},
+    validation_utils::assert_keys_equal,
+};
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: &ChangeDutchOfferArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let base = next_account_info(account_info_iter)?;
+    let space_metadata = next_account_info(account_info_iter)?;
+    let owner = next_account_info(account_info_iter)?;
+    let ata_account = next_account_info(account_info_iter)?;
+    let listing = next_account_info(account_info_iter)?;
+    let sell_delegate = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+    let rent_account = next_account_info(account_info_iter)?;
+    let auction = next_account_info(account_info_iter)?;
+    let listing_seller = next_account_info(account_info_iter)?;
+
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // check programs
+    assert_keys_equal(system_program::id(), *system_program.key)?;
+    assert_keys_equal(spl_token::id(), *token_program.key)?;
+
+    //deserialize and verify space metadata
+    let mut space_metadata_data: SpaceMetadata = try_from_slice_unchecked(&space_metadata.data.borrow())?;
+    let key = create_space_metadata_address(
+        program_id,
+        base.key,
+        args.space_x,
+        args.space_y,
+        space_metadata_data.bump,
+    )?;
+    assert_keys_equal(key, *space_metadata.key)?;
+
+    //verify sell delegate
+    let (key, _) = find_sell_delegate_address(program_id, base.key);
+    assert_keys_equal(key, *sell_delegate.key)?;
+
+    // check space is held by owner, not escrowed
+    assert_space_held(&space_metadata_data, ata_account, owner.key)?;
+
+    // check space is not rented out or auctioned
+    assert_not_rented(rent_account, base.key, args.space_x, args.space_y)?;
+    assert_not_auctioned(program_id, auction, base.key, args.space_x, args.space_y)?;
+
+    // check schedule, price only declines and never reaches zero
+    if args.start_time >= args.end_time {
+        msg!("Error: dutch listing must end after it starts");
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    if args.end_price == 0 || args.end_price > args.start_price {
+        msg!("Error: dutch listing end price must be positive and at most the start price");
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    // approve delegate
+    invoke(
+        &spl_token::instruction::approve(
+            token_program.key,
+            ata_account.key,
+            sell_delegate.key,
+            owner.key,
+            &[],
+            1,
+        )?,
+        &[
+            token_program.clone(),
+            ata_account.clone(),
+            sell_delegate.clone(),
+            owner.clone(),
+        ],
+    )?;
+
+    // write schedule to the space's listing account
+    write_listing(
+        program_id,
+        base.key,
+        listing,
+        owner,
+        listing_seller,
+        system_program,
+        args.space_x,
+        args.space_y,
+        Listing {
+            bump: 0,
+            seller: *owner.key,
+            start_price: args.start_price,
+            end_price: args.end_price,
+            start_time: args.start_time,
+            end_time: args.end_time,
+            expires_at: 0,
+        },
+    )?;
+
+    // write to space data, dutch listings are priced in lamports
+    space_metadata_data.price = args.start_price;
+    space_metadata_data.payment_mint = Pubkey::default();
+    space_metadata_data.listing_terms = true;
+    space_metadata_data.serialize(&mut *space_metadata.data.borrow_mut())?;
+
+    Ok(())
+}
@@ -0,0 +1,241 @@
+use borsh::{BorshSerialize};
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    entrypoint::ProgramResult,
+    borsh::try_from_slice_unchecked,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_program,
+    sysvar::rent,
+};
+use spl_associated_token_account;
+use spl_token;
+
+use crate::{
+    error::CustomError,
+    instruction::ChangeEscrowOfferArgs,
+    pda::{create_space_metadata_address, find_sell_delegate_address, find_space_escrow_address},
+    processor::processor_utils::{assert_not_auctioned, assert_not_rented, close_listing, create_pda_account},
+    state::{
+        SELL_DELEGATE_SEED,
+        SPACE_ESCROW_SEED,
+        SpaceMetadata,
+    },
+    validation_utils::{assert_is_ata, assert_keys_equal},
+};
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: &ChangeEscrowOfferArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let base = next_account_info(account_info_iter)?;
+    let space_metadata = next_account_info(account_info_iter)?;
+    let seller = next_account_info(account_info_iter)?;
+    let seller_ata = next_account_info(account_info_iter)?;
+    let escrow = next_account_info(account_info_iter)?;
+    let sell_delegate = next_account_info(account_info_iter)?;
+    let space_mint = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+    let associated_token_program = next_account_info(account_info_iter)?;
+    let rent_program = next_account_info(account_info_iter)?;
+    let rent_account = next_account_info(account_info_iter)?;
+    let auction = next_account_info(account_info_iter)?;
+    let listing = next_account_info(account_info_iter)?;
+    let listing_seller = next_account_info(account_info_iter)?;
+
+    if !seller.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // check programs
+    assert_keys_equal(system_program::id(), *system_program.key)?;
+    assert_keys_equal(spl_token::id(), *token_program.key)?;
+    assert_keys_equal(spl_associated_token_account::id(), *associated_token_program.key)?;
+    assert_keys_equal(rent::id(), *rent_program.key)?;
+
+    //deserialize and verify space metadata
+    let mut space_metadata_data: SpaceMetadata = try_from_slice_unchecked(&space_metadata.data.borrow_mut())?;
+    let key = create_space_metadata_address(
+        program_id,
+        base.key,
+        args.space_x,
+        args.space_y,
+        space_metadata_data.bump,
+    )?;
+    assert_keys_equal(key, *space_metadata.key)?;
+
+    if space_metadata_data.mint != *space_mint.key {
+        msg!("Error: space account does not match mint account");
+        return Err(CustomError::MintMismatch.into());
+    }
+
+    // verify sell delegate and escrow
+    let (key, bump_sell_delegate) = find_sell_delegate_address(program_id, base.key);
+    assert_keys_equal(key, *sell_delegate.key)?;
+    let seeds_sell_delegate = &[&base.key.to_bytes(), SELL_DELEGATE_SEED, &[bump_sell_delegate]];
+    let (key, bump_escrow) = find_space_escrow_address(program_id, base.key, args.space_x, args.space_y);
+    assert_keys_equal(key, *escrow.key)?;
+
+    let escrowed = space_metadata_data.escrow_seller != Pubkey::default();
+    if escrowed && space_metadata_data.escrow_seller != *seller.key {
+        msg!("Error: space is escrowed by another seller");
+        return Err(CustomError::WrongAuthority.into());
+    }
+
+    if args.create {
+        if !escrowed {
+            //check ATAs
+            assert_is_ata(seller_ata, seller.key, space_mint.key)?;
+
+            // check NFT owned
+            let seller_ata_data = spl_token::state::Account::unpack_from_slice(&seller_ata.data.borrow())?;
+            if seller_ata_data.amount != 1 {
+                msg!("Error: token account does not own token");
+                return Err(CustomError::MissingTokenOwner.into());
+            }
+
+            // check space is not rented out or auctioned
+            assert_not_rented(rent_account, base.key, args.space_x, args.space_y)?;
+            assert_not_auctioned(program_id, auction, base.key, args.space_x, args.space_y)?;
+
+            // create escrow token account owned by the sell delegate
+            let seeds_escrow = &[
+                &base.key.to_bytes(),
+                SPACE_ESCROW_SEED,
+                &args.space_x.to_le_bytes(),
+                &args.space_y.to_le_bytes(),
+                &[bump_escrow],
+            ];
+            create_pda_account(
+                seller,
+                escrow,
+                system_program,
+                spl_token::state::Account::LEN,
+                token_program.key,
+                seeds_escrow,
+            )?;
+            invoke(
+                &spl_token::instruction::initialize_account(
+                    token_program.key,
+                    escrow.key,
+                    space_mint.key,
+                    sell_delegate.key,
+                )?,
+                &[
+                    token_program.clone(),
+                    escrow.clone(),
+                    space_mint.clone(),
+                    sell_delegate.clone(),
+                    rent_program.clone(),
+                ],
+            )?;
+
+            // revoke any sell delegate approved by a direct listing, it would outlive the listing on the emptied ATA
+            invoke(
+                &spl_token::instruction::revoke(token_program.key, seller_ata.key, seller.key, &[])?,
+                &[token_program.clone(), seller_ata.clone(), seller.clone()],
+            )?;
+
+            // move NFT into escrow
+            invoke(
+                &spl_token::instruction::transfer(
+                    token_program.key,
+                    seller_ata.key,
+                    escrow.key,
+                    seller.key,
+                    &[],
+                    1,
+                )?,
+                &[
+                    token_program.clone(),
+                    seller_ata.clone(),
+                    escrow.clone(),
+                    seller.clone(),
+                ],
+            )?;
+            space_metadata_data.escrow_seller = *seller.key;
+        }
+        // escrowed listings are priced in lamports
+        space_metadata_data.price = args.price;
+        space_metadata_data.payment_mint = Pubkey::default();
+        space_metadata_data.listing_terms = false;
+    } else {
+        if !escrowed {
+            msg!("Error: space is not escrowed");
+            return Err(CustomError::InvalidListing.into());
+        }
+
+        // create ATA if necessary
+        if seller_ata.data_len() == 0 {
+            invoke(
+                &spl_associated_token_account::create_associated_token_account(
+                    seller.key,
+                    seller.key,
+                    space_mint.key,
+                ),
+                &[
+                    seller.clone(),
+                    space_mint.clone(),
+                    seller_ata.clone(),
+                    system_program.clone(),
+                    token_program.clone(),
+                    rent_program.clone(),
+                    associated_token_program.clone(),
+                ],
+            )?;
+        }
+        assert_is_ata(seller_ata, seller.key, space_mint.key)?;
+
+        // return NFT to seller and close escrow
+        invoke_signed(
+            &spl_token::instruction::transfer(
+                token_program.key,
+                escrow.key,
+                seller_ata.key,
+                sell_delegate.key,
+                &[],
+                1,
+            )?,
+            &[
+                token_program.clone(),
+                escrow.clone(),
+                seller_ata.clone(),
+                sell_delegate.clone(),
+            ],
+            &[seeds_sell_delegate],
+        )?;
+        invoke_signed(
+            &spl_token::instruction::close_account(
+                token_program.key,
+                escrow.key,
+                seller.key,
+                sell_delegate.key,
+                &[],
+            )?,
+            &[
+                token_program.clone(),
+                escrow.clone(),
+                seller.clone(),
+                sell_delegate.clone(),
+            ],
+            &[seeds_sell_delegate],
+        )?;
+        space_metadata_data.escrow_seller = Pubkey::default();
+        space_metadata_data.price = 0;
+        space_metadata_data.payment_mint = Pubkey::default();
+        space_metadata_data.listing_terms = false;
+    }
+
+    // escrowed listings have no terms, so a listing account left by a direct listing is void
+    close_listing(program_id, base.key, listing, listing_seller, args.space_x, args.space_y)?;
+
+    space_metadata_data.serialize(&mut *space_metadata.data.borrow_mut())?;
+
+    Ok(())
+}
@@ -10,10 +10,8 @@ use solana_program::{
 
 use crate::{
     instruction::ChangeNeighborhoodNameArgs,
-    state::{
-        NEIGHBORHOOD_METADATA_SEED,
-        NeighborhoodMetadata,
-    },
+    pda::create_neighborhood_metadata_address,
+    state::NeighborhoodMetadata,
     validation_utils::{assert_keys_equal},
 };
 
@@ -39,14 +37,13 @@ pub fn process(
         return Err(ProgramError::InvalidAccountData);
     }
 
-    let seeds_neighborhood_metadata = &[
-        &base.key.to_bytes(),
-        NEIGHBORHOOD_METADATA_SEED,
-        &args.neighborhood_x.to_le_bytes(),
-        &args.neighborhood_y.to_le_bytes(),
-        &[neighborhood_metadata_data.bump],
-    ];
-    let key = Pubkey::create_program_address(seeds_neighborhood_metadata, program_id)?;
+    let key = create_neighborhood_metadata_address(
+        program_id,
+        base.key,
+        args.neighborhood_x,
+        args.neighborhood_y,
+        neighborhood_metadata_data.bump,
+    )?;
     assert_keys_equal(key, *neighborhood_metadata.key)?;
 
     // Write new name
@@ -3,23 +3,22 @@ use solana_program::{
     account_info::{AccountInfo, next_account_info},
     entrypoint::ProgramResult,
     borsh::try_from_slice_unchecked,
+    clock::Clock,
     msg,
     program::invoke,
     program_error::ProgramError,
-    program_pack::Pack,
     pubkey::Pubkey,
+    system_program,
+    sysvar::Sysvar,
 };
 use spl_token;
 
 use crate::{
-    error::CustomError,
     instruction::ChangeOfferArgs,
-    state::{
-        SPACE_METADATA_SEED,
-        SELL_DELEGATE_SEED,
-        SpaceMetadata,
-    },
-    validation_utils::{assert_is_ata, assert_keys_equal},
+    pda::{create_space_metadata_address, find_sell_delegate_address},
+    processor::processor_utils::{assert_not_auctioned, assert_not_rented, assert_space_held, close_listing, write_listing},
+    state::{Listing, SpaceMetadata},
+    validation_utils::assert_keys_equal,
 };
 
 pub fn process(
@@ -34,6 +33,11 @@ pub fn process(
     let ata_account = next_account_info(account_info_iter)?;
     let sell_delegate = next_account_info(account_info_iter)?;
     let token_program = next_account_info(account_info_iter)?;
+    let listing = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let rent_account = next_account_info(account_info_iter)?;
+    let auction = next_account_info(account_info_iter)?;
+    let listing_seller = next_account_info(account_info_iter)?;
 
     if !owner.is_signer {
         return Err(ProgramError::MissingRequiredSignature);
@@ -41,33 +45,37 @@ pub fn process(
     
     // check programs
     assert_keys_equal(spl_token::id(), *token_program.key)?;
+    assert_keys_equal(system_program::id(), *system_program.key)?;
 
     //deserialize and verify space metadata
     let mut space_metadata_data: SpaceMetadata = try_from_slice_unchecked(&space_metadata.data.borrow_mut())?;
 
-    let seeds_space_metadata = &[
-        &base.key.to_bytes(),
-        SPACE_METADATA_SEED,
-        &args.space_x.to_le_bytes(),
-        &args.space_y.to_le_bytes(),
-        &[space_metadata_data.bump],
-    ];
-    let key = Pubkey::create_program_address(seeds_space_metadata, program_id)?;
+    let key = create_space_metadata_address(
+        program_id,
+        base.key,
+        args.space_x,
+        args.space_y,
+        space_metadata_data.bump,
+    )?;
     assert_keys_equal(key, *space_metadata.key)?;
 
     //verify sell delegate
-    let seeds_sell_delegate = &[&base.key.to_bytes(), SELL_DELEGATE_SEED];
-    let (key, _) = Pubkey::find_program_address(seeds_sell_delegate, program_id);
+    let (key, _) = find_sell_delegate_address(program_id, base.key);
     assert_keys_equal(key, *sell_delegate.key)?;
 
-    //check ATAs
-    assert_is_ata(ata_account, owner.key, &space_metadata_data.mint)?;
+    // check NFT owned and not escrowed
+    assert_space_held(&space_metadata_data, ata_account, owner.key)?;
 
-    // check NFT owned
-    let ata_data = spl_token::state::Account::unpack_from_slice(&ata_account.data.borrow())?;
-    if ata_data.amount != 1 {
-        msg!("Error: token account does not own token");
-        return Err(CustomError::MissingTokenOwner.into());
+    // check expiry
+    if args.create && args.expires_at != 0 && args.expires_at <= Clock::get()?.unix_timestamp as u64 {
+        msg!("Error: listing expiry has passed");
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    // check space is not rented out or auctioned, delisting is always allowed
+    if args.create {
+        assert_not_rented(rent_account, base.key, args.space_x, args.space_y)?;
+        assert_not_auctioned(program_id, auction, base.key, args.space_x, args.space_y)?;
     }
 
     // main code
@@ -99,11 +107,42 @@ pub fn process(
 
     // write to space data
     if args.create {
-        // if creating sell offer, set price
+        // if creating sell offer, set price and the mint it is paid in
         space_metadata_data.price = args.price;
+        space_metadata_data.payment_mint = args.payment_mint;
+        space_metadata_data.listing_terms = args.expires_at != 0;
+
+        // write expiry to the space's listing account
+        if args.expires_at != 0 {
+            write_listing(
+                program_id,
+                base.key,
+                listing,
+                owner,
+                listing_seller,
+                system_program,
+                args.space_x,
+                args.space_y,
+                Listing {
+                    bump: 0,
+                    seller: *owner.key,
+                    start_price: args.price,
+                    end_price: args.price,
+                    start_time: 0,
+                    end_time: 0,
+                    expires_at: args.expires_at,
+                },
+            )?;
+        } else {
+            // a listing without terms leaves nothing in the listing account
+            close_listing(program_id, base.key, listing, listing_seller, args.space_x, args.space_y)?;
+        }
     }
     else{
+        close_listing(program_id, base.key, listing, listing_seller, args.space_x, args.space_y)?;
         space_metadata_data.price = 0;
+        space_metadata_data.payment_mint = Pubkey::default();
+        space_metadata_data.listing_terms = false;
     }
 
     space_metadata_data.serialize(&mut *space_metadata.data.borrow_mut())?;
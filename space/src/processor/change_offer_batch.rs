@@ -0,0 +1,102 @@
+use borsh::{BorshSerialize};
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    entrypoint::ProgramResult,
+    borsh::try_from_slice_unchecked,
+    program::invoke,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+use spl_token;
+
+use crate::{
+    instruction::ChangeOfferBatchArgs,
+    pda::{create_space_metadata_address, find_sell_delegate_address},
+    processor::processor_utils::{assert_not_auctioned, assert_not_rented, assert_space_held, close_listing},
+    state::SpaceMetadata,
+    validation_utils::assert_keys_equal,
+};
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: &ChangeOfferBatchArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let base = next_account_info(account_info_iter)?;
+    let owner = next_account_info(account_info_iter)?;
+    let sell_delegate = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // check programs
+    assert_keys_equal(spl_token::id(), *token_program.key)?;
+
+    //verify sell delegate once for the whole batch
+    let (key, _) = find_sell_delegate_address(program_id, base.key);
+    assert_keys_equal(key, *sell_delegate.key)?;
+
+    // listing accounts to close once every delegate is approved, as a CPI fails after lamports were moved directly
+    let mut listings = Vec::new();
+    for offer in args.offers.iter() {
+        let space_metadata = next_account_info(account_info_iter)?;
+        let ata_account = next_account_info(account_info_iter)?;
+        let rent_account = next_account_info(account_info_iter)?;
+        let auction = next_account_info(account_info_iter)?;
+        let listing = next_account_info(account_info_iter)?;
+        let listing_seller = next_account_info(account_info_iter)?;
+
+        //deserialize and verify space metadata
+        let mut space_metadata_data: SpaceMetadata = try_from_slice_unchecked(&space_metadata.data.borrow())?;
+        let key = create_space_metadata_address(
+            program_id,
+            base.key,
+            offer.space_x,
+            offer.space_y,
+            space_metadata_data.bump,
+        )?;
+        assert_keys_equal(key, *space_metadata.key)?;
+
+        // check NFT owned and not escrowed
+        assert_space_held(&space_metadata_data, ata_account, owner.key)?;
+
+        // check space is not rented out or auctioned
+        assert_not_rented(rent_account, base.key, offer.space_x, offer.space_y)?;
+        assert_not_auctioned(program_id, auction, base.key, offer.space_x, offer.space_y)?;
+
+        // approve delegate
+        invoke(
+            &spl_token::instruction::approve(
+                token_program.key,
+                ata_account.key,
+                sell_delegate.key,
+                owner.key,
+                &[],
+                1,
+            )?,
+            &[
+                token_program.clone(),
+                ata_account.clone(),
+                sell_delegate.clone(),
+                owner.clone(),
+            ],
+        )?;
+
+        // write to space data, batch listings are priced in lamports without terms
+        space_metadata_data.price = offer.price;
+        space_metadata_data.payment_mint = Pubkey::default();
+        space_metadata_data.listing_terms = false;
+        space_metadata_data.serialize(&mut *space_metadata.data.borrow_mut())?;
+        listings.push((listing, listing_seller, offer));
+    }
+
+    // listings without terms leave nothing in the listing account
+    for (listing, listing_seller, offer) in listings {
+        close_listing(program_id, base.key, listing, listing_seller, offer.space_x, offer.space_y)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,92 @@
+use borsh::{BorshSerialize};
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    entrypoint::ProgramResult,
+    borsh::try_from_slice_unchecked,
+    clock::Clock,
+    msg,
+    program::invoke,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+use spl_token;
+
+use crate::{
+    instruction::ClearExpiredOfferArgs,
+    pda::{create_listing_address, create_space_metadata_address},
+    processor::processor_utils::close_program_account,
+    state::{Listing, SpaceMetadata},
+    validation_utils::{assert_is_ata, assert_keys_equal, assert_owned_by},
+};
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: &ClearExpiredOfferArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let base = next_account_info(account_info_iter)?;
+    let space_metadata = next_account_info(account_info_iter)?;
+    let listing = next_account_info(account_info_iter)?;
+    let seller = next_account_info(account_info_iter)?;
+    let seller_ata = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+
+    //deserialize and verify space metadata
+    let mut space_metadata_data: SpaceMetadata = try_from_slice_unchecked(&space_metadata.data.borrow())?;
+    let key = create_space_metadata_address(
+        program_id,
+        base.key,
+        args.space_x,
+        args.space_y,
+        space_metadata_data.bump,
+    )?;
+    assert_keys_equal(key, *space_metadata.key)?;
+
+    if !space_metadata_data.listing_terms {
+        msg!("Error: listing does not expire");
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    //deserialize and verify listing
+    assert_owned_by(listing, program_id)?;
+    let listing_data: Listing = try_from_slice_unchecked(&listing.data.borrow())?;
+    let key = create_listing_address(
+        program_id,
+        base.key,
+        args.space_x,
+        args.space_y,
+        listing_data.bump,
+    )?;
+    assert_keys_equal(key, *listing.key)?;
+    assert_keys_equal(listing_data.seller, *seller.key)?;
+
+    // check listing has expired
+    let now_ts = Clock::get()?.unix_timestamp as u64;
+    if listing_data.expires_at == 0 || now_ts < listing_data.expires_at {
+        msg!("Error: listing has not expired");
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    // the token program only lets the seller revoke the sell delegate, so it is revoked when the seller clears
+    // their own listing, a crank clearing it only voids the price which keeps AcceptOffer from using the delegate
+    if seller.is_signer {
+        assert_keys_equal(spl_token::id(), *token_program.key)?;
+        assert_is_ata(seller_ata, seller.key, &space_metadata_data.mint)?;
+        invoke(
+            &spl_token::instruction::revoke(token_program.key, seller_ata.key, seller.key, &[])?,
+            &[token_program.clone(), seller_ata.clone(), seller.clone()],
+        )?;
+    }
+
+    // close listing account, returning its rent to the seller
+    close_program_account(listing, seller)?;
+
+    space_metadata_data.price = 0;
+    space_metadata_data.payment_mint = Pubkey::default();
+    space_metadata_data.listing_terms = false;
+    space_metadata_data.serialize(&mut *space_metadata.data.borrow_mut())?;
+
+    Ok(())
+}
@@ -0,0 +1,145 @@
+use borsh::{BorshSerialize};
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    entrypoint::ProgramResult,
+    borsh::try_from_slice_unchecked,
+    clock::Clock,
+    msg,
+    program::invoke,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_program,
+    sysvar::Sysvar,
+};
+use spl_token;
+
+use crate::{
+    error::CustomError,
+    instruction::CreateAuctionArgs,
+    pda::{create_space_metadata_address, find_auction_address, find_sell_delegate_address},
+    processor::processor_utils::{assert_not_rented, close_listing, create_pda_account},
+    state::{
+        AUCTION_RESERVE,
+        AUCTION_SEED,
+        Auction,
+        SpaceMetadata,
+    },
+    validation_utils::{assert_is_ata, assert_keys_equal},
+};
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: &CreateAuctionArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let base = next_account_info(account_info_iter)?;
+    let space_metadata = next_account_info(account_info_iter)?;
+    let auction = next_account_info(account_info_iter)?;
+    let seller = next_account_info(account_info_iter)?;
+    let seller_ata_space = next_account_info(account_info_iter)?;
+    let sell_delegate = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+    let rent_account = next_account_info(account_info_iter)?;
+    let listing = next_account_info(account_info_iter)?;
+    let listing_seller = next_account_info(account_info_iter)?;
+
+    if !seller.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // check programs
+    assert_keys_equal(system_program::id(), *system_program.key)?;
+    assert_keys_equal(spl_token::id(), *token_program.key)?;
+
+    //deserialize and verify space metadata
+    let mut space_metadata_data: SpaceMetadata = try_from_slice_unchecked(&space_metadata.data.borrow())?;
+    let key = create_space_metadata_address(
+        program_id,
+        base.key,
+        args.space_x,
+        args.space_y,
+        space_metadata_data.bump,
+    )?;
+    assert_keys_equal(key, *space_metadata.key)?;
+
+    //verify sell delegate
+    let (key, _) = find_sell_delegate_address(program_id, base.key);
+    assert_keys_equal(key, *sell_delegate.key)?;
+
+    // check seller holds the space
+    assert_is_ata(seller_ata_space, seller.key, &space_metadata_data.mint)?;
+    let seller_ata_space_data = spl_token::state::Account::unpack(&seller_ata_space.data.borrow())?;
+    if seller_ata_space_data.amount != 1 {
+        msg!("Error: token account does not own token");
+        return Err(CustomError::MissingTokenOwner.into());
+    }
+
+    // check space is not rented out
+    assert_not_rented(rent_account, base.key, args.space_x, args.space_y)?;
+
+    let now_ts = Clock::get()?.unix_timestamp as u64;
+    if args.end_time <= now_ts {
+        msg!("Error: auction end time has passed");
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    // verify auction, only one auction per space at a time
+    let (key, bump_auction) = find_auction_address(program_id, base.key, args.space_x, args.space_y);
+    assert_keys_equal(key, *auction.key)?;
+    if auction.data_len() != 0 {
+        msg!("Error: space is already being auctioned");
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
+    // create auction account
+    let seeds_auction = &[
+        &base.key.to_bytes(),
+        AUCTION_SEED,
+        &args.space_x.to_le_bytes(),
+        &args.space_y.to_le_bytes(),
+        &[bump_auction],
+    ];
+    create_pda_account(seller, auction, system_program, AUCTION_RESERVE, program_id, seeds_auction)?;
+
+    // approve delegate so the space can be moved to the winner at settlement
+    invoke(
+        &spl_token::instruction::approve(
+            token_program.key,
+            seller_ata_space.key,
+            sell_delegate.key,
+            seller.key,
+            &[],
+            1,
+        )?,
+        &[
+            token_program.clone(),
+            seller_ata_space.clone(),
+            sell_delegate.clone(),
+            seller.clone(),
+        ],
+    )?;
+
+    let mut auction_data: Auction = try_from_slice_unchecked(&auction.data.borrow())?;
+    auction_data.bump = bump_auction;
+    auction_data.seller = *seller.key;
+    auction_data.space_x = args.space_x;
+    auction_data.space_y = args.space_y;
+    auction_data.reserve_price = args.reserve_price;
+    auction_data.min_increment = args.min_increment;
+    auction_data.end_time = args.end_time;
+    auction_data.highest_bidder = *seller.key;
+    auction_data.highest_bid = 0;
+    auction_data.serialize(&mut *auction.data.borrow_mut())?;
+
+    // any sell offer is void while the space is auctioned, along with the terms in its listing account
+    close_listing(program_id, base.key, listing, listing_seller, args.space_x, args.space_y)?;
+    space_metadata_data.price = 0;
+    space_metadata_data.payment_mint = Pubkey::default();
+    space_metadata_data.listing_terms = false;
+    space_metadata_data.serialize(&mut *space_metadata.data.borrow_mut())?;
+
+    Ok(())
+}
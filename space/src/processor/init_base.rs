@@ -13,8 +13,10 @@ use solana_program::{
 
 use crate::{
     instruction::InitBaseArgs,
+    pda::find_neighborhood_list_address,
     state::{
         BASE_RESERVE,
+        MAX_MARKETPLACE_FEE_BPS,
         NEIGHBORHOOD_LIST_SEED,
         NEIGHBORHOOD_LIST_RESERVE,
         Base,
@@ -43,12 +45,7 @@ pub fn process(
     }
 
     // verify neighborhood list account
-    let seeds_neighborhood_list = &[
-        &base.key.to_bytes(),
-        NEIGHBORHOOD_LIST_SEED,
-    ];
-    let (key, bump_neighborhood_list) =
-        Pubkey::find_program_address(seeds_neighborhood_list, program_id);
+    let (key, bump_neighborhood_list) = find_neighborhood_list_address(program_id, base.key);
     assert_keys_equal(key, *neighborhood_list.key)?;
     let seeds_neighborhood_list = &[
         &base.key.to_bytes(),
@@ -105,6 +102,10 @@ pub fn process(
     base_data.neighborhood_count = 0;
     base_data.authority = *payer.key;
     base_data.authority_privileges = true;
+    base_data.max_marketplace_fee_bps = MAX_MARKETPLACE_FEE_BPS;
+    base_data.max_marketplace_fee_bps_set = true;
+    base_data.treasury = *payer.key;
+    base_data.treasury_fee_share_bps = 0;
     base_data.serialize(&mut *base.data.borrow_mut())?;
 
     let mut neighborhood_list_data: NeighborhoodList = try_from_slice_unchecked(&neighborhood_list.data.borrow_mut())?;
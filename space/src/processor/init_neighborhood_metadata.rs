@@ -14,15 +14,17 @@ use solana_program::{
     sysvar::{Sysvar, rent},
 };
 use spl_token;
+use extend_common::utils::get_neighborhood_xy;
 
 use crate::{
     instruction::InitNeighborhoodMetadataArgs,
-    processor::processor_utils::{get_neighborhood_creation_price, get_space_xy_from_name, get_neighborhood_xy},
+    pda::{create_neighborhood_list_address, find_neighborhood_metadata_address},
+    processor::processor_utils::{get_neighborhood_creation_price, get_space_xy_from_name},
     state::{
         EXTEND_TOKEN_MINT,
+        MARKETPLACE_FEE_BPS,
         NEIGHBORHOOD_METADATA_SEED,
         NEIGHBORHOOD_METADATA_RESERVE,
-        NEIGHBORHOOD_LIST_SEED,
         Base,
         NeighborhoodMetadata,
         NeighborhoodList,
@@ -60,14 +62,12 @@ pub fn process(
     assert_keys_equal(rent::id(), *rent_sysvar_info.key)?;
 
     // verify neighborhood metadata
-    let seeds_neighborhood_metadata = &[
-        &base.key.to_bytes(),
-        NEIGHBORHOOD_METADATA_SEED,
-        &args.neighborhood_x.to_le_bytes(),
-        &args.neighborhood_y.to_le_bytes(),
-    ];
-    let (key, bump_neighborhood_metadata) =
-        Pubkey::find_program_address(seeds_neighborhood_metadata, program_id);
+    let (key, bump_neighborhood_metadata) = find_neighborhood_metadata_address(
+        program_id,
+        base.key,
+        args.neighborhood_x,
+        args.neighborhood_y,
+    );
     assert_keys_equal(key, *neighborhood_metadata.key)?;
     let seeds_neighborhood_metadata = &[
         &base.key.to_bytes(),
@@ -79,12 +79,7 @@ pub fn process(
 
     // verify neighborhood list account
     let mut neighborhood_list_data: NeighborhoodList = try_from_slice_unchecked(&neighborhood_list.data.borrow_mut())?;
-    let seeds_neighborhood_list = &[
-        &base.key.to_bytes(),
-        NEIGHBORHOOD_LIST_SEED,
-        &[neighborhood_list_data.bump],
-    ];
-    let key = Pubkey::create_program_address(seeds_neighborhood_list, program_id)?;
+    let key = create_neighborhood_list_address(program_id, base.key, neighborhood_list_data.bump)?;
     assert_keys_equal(key, *neighborhood_list.key)?;
 
     // verify candymachine config matches neighborhood_x, neighborhood_y in the case of first name
@@ -170,6 +165,8 @@ pub fn process(
     neighborhood_metadata_data.candymachine_config= *candymachine_config.key;
     neighborhood_metadata_data.candymachine_account = *candymachine_account.key;
     neighborhood_metadata_data.neighborhood_name = args.neighborhood_name;
+    neighborhood_metadata_data.marketplace_fee_bps = MARKETPLACE_FEE_BPS;
+    neighborhood_metadata_data.marketplace_fee_bps_set = true;
     neighborhood_metadata_data.serialize(&mut *neighborhood_metadata.data.borrow_mut())?;
     
     // write to neighborhood list
@@ -12,13 +12,14 @@ use solana_program::{
     system_instruction, system_program,
     sysvar::rent::Rent,
 };
+use extend_common::utils::get_neighborhood_xy;
 
 use crate::{
     error::CustomError,
     instruction::InitSpaceMetadataArgs,
-    processor::processor_utils::{get_neighborhood_xy, get_space_xy_from_name},
+    pda::{create_neighborhood_metadata_address, find_metaplex_metadata_address, find_space_metadata_address},
+    processor::processor_utils::get_space_xy_from_name,
     state::{
-        NEIGHBORHOOD_METADATA_SEED,
         SPACE_METADATA_SEED,
         SPACE_METADATA_RESERVE,
         NeighborhoodMetadata,
@@ -53,25 +54,17 @@ pub fn process(
     // deserialize and verify neighborhood metadata
     let neighborhood_metadata_data: NeighborhoodMetadata = try_from_slice_unchecked(&neighborhood_metadata.data.borrow())?;
     let (neighborhood_x, neighborhood_y) = get_neighborhood_xy(args.space_x, args.space_y);
-    let seeds_neighborhood_metadata = &[
-        &base.key.to_bytes(),
-        NEIGHBORHOOD_METADATA_SEED,
-        &neighborhood_x.to_le_bytes(),
-        &neighborhood_y.to_le_bytes(),
-        &[neighborhood_metadata_data.bump],
-    ];
-
-    let neighborhood_key = Pubkey::create_program_address(seeds_neighborhood_metadata, program_id)?;
+    let neighborhood_key = create_neighborhood_metadata_address(
+        program_id,
+        base.key,
+        neighborhood_x,
+        neighborhood_y,
+        neighborhood_metadata_data.bump,
+    )?;
     assert_keys_equal(neighborhood_key, *neighborhood_metadata.key)?;
 
     // verify space metadata
-    let seeds_space_metadata = &[
-        &base.key.to_bytes(),
-        SPACE_METADATA_SEED,
-        &args.space_x.to_le_bytes(),
-        &args.space_y.to_le_bytes(),
-    ];
-    let (key, space_bump) = Pubkey::find_program_address(seeds_space_metadata, program_id);
+    let (key, space_bump) = find_space_metadata_address(program_id, base.key, args.space_x, args.space_y);
     assert_keys_equal(key, *space_metadata.key)?;
     let seeds_space_metadata = &[
         &base.key.to_bytes(),
@@ -82,14 +75,7 @@ pub fn process(
     ];
 
     // deserialize and verify metaplex metadata
-    let (metadata_key, _) = Pubkey::find_program_address(
-        &[
-            b"metadata",
-            metaplex_token_metadata::id().as_ref(),
-            space_mint.key.as_ref(),
-        ],
-        &metaplex_token_metadata::id(),
-    );
+    let (metadata_key, _) = find_metaplex_metadata_address(&metaplex_token_metadata::id(), space_mint.key);
     assert_keys_equal(metadata_key, *space_metaplex_metadata.key)?;
     let space_metaplex_metadata_data = Metadata::from_account_info(
         space_metaplex_metadata,
@@ -16,9 +16,9 @@ use spl_token;
 
 use crate::{
     instruction::InitVoucherSystemArgs,
+    pda::{create_neighborhood_metadata_address, find_voucher_mint_address, find_voucher_sink_address},
     state::{
         NEIGHBORHOOD_SIZE,
-        NEIGHBORHOOD_METADATA_SEED,
         VOUCHER_MINT_SEED,
         VOUCHER_SINK_SEED,
         NeighborhoodMetadata,
@@ -64,25 +64,18 @@ pub fn process(
     
     // deserialize and verify neighborhood metadata
     let neighborhood_metadata_data: NeighborhoodMetadata = try_from_slice_unchecked(&neighborhood_metadata.data.borrow())?;
-    let seeds_neighborhood_metadata = &[
-        &base.key.to_bytes(),
-        NEIGHBORHOOD_METADATA_SEED,
-        &args.neighborhood_x.to_le_bytes(),
-        &args.neighborhood_y.to_le_bytes(),
-        &[neighborhood_metadata_data.bump],
-    ];
-    let key = Pubkey::create_program_address(seeds_neighborhood_metadata, program_id)?;
+    let key = create_neighborhood_metadata_address(
+        program_id,
+        base.key,
+        args.neighborhood_x,
+        args.neighborhood_y,
+        neighborhood_metadata_data.bump,
+    )?;
     assert_keys_equal(key, *neighborhood_metadata.key)?;
 
     // verify voucher mint
-    let seeds_voucher_mint = &[
-        &base.key.to_bytes(),
-        VOUCHER_MINT_SEED,
-        &args.neighborhood_x.to_le_bytes(),
-        &args.neighborhood_y.to_le_bytes(),
-    ];
-    let (key, bump_voucher_mint) = 
-        Pubkey::find_program_address(seeds_voucher_mint, program_id);
+    let (key, bump_voucher_mint) =
+        find_voucher_mint_address(program_id, base.key, args.neighborhood_x, args.neighborhood_y);
     assert_keys_equal(key, *voucher_mint.key)?;
     let seeds_voucher_mint = &[
         &base.key.to_bytes(),
@@ -93,14 +86,8 @@ pub fn process(
     ];
 
     // verify sink account
-    let seeds_sink_account = &[
-        &base.key.to_bytes(),
-        VOUCHER_SINK_SEED,
-        &args.neighborhood_x.to_le_bytes(),
-        &args.neighborhood_y.to_le_bytes(),
-    ];
-    let (key, bump_voucher_sink) = 
-        Pubkey::find_program_address(seeds_sink_account, program_id);
+    let (key, bump_voucher_sink) =
+        find_voucher_sink_address(program_id, base.key, args.neighborhood_x, args.neighborhood_y);
     assert_keys_equal(key, *sink_account_voucher.key)?;
     let seeds_sink_account = &[
         &base.key.to_bytes(),
@@ -0,0 +1,102 @@
+use borsh::{BorshSerialize};
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    entrypoint::ProgramResult,
+    borsh::try_from_slice_unchecked,
+    clock::Clock,
+    msg,
+    program::invoke,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_instruction,
+    system_program,
+    sysvar::Sysvar,
+};
+
+use crate::{
+    instruction::PlaceAuctionBidArgs,
+    pda::create_auction_address,
+    processor::processor_utils::transfer_lamports,
+    state::{AUCTION_EXTENSION_WINDOW, Auction},
+    validation_utils::{assert_keys_equal, assert_owned_by},
+};
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: &PlaceAuctionBidArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let base = next_account_info(account_info_iter)?;
+    let auction = next_account_info(account_info_iter)?;
+    let bidder = next_account_info(account_info_iter)?;
+    let previous_bidder = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    if !bidder.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // check programs
+    assert_keys_equal(system_program::id(), *system_program.key)?;
+
+    //deserialize and verify auction
+    assert_owned_by(auction, program_id)?;
+    let mut auction_data: Auction = try_from_slice_unchecked(&auction.data.borrow())?;
+    let key = create_auction_address(
+        program_id,
+        base.key,
+        args.space_x,
+        args.space_y,
+        auction_data.bump,
+    )?;
+    assert_keys_equal(key, *auction.key)?;
+    assert_keys_equal(auction_data.highest_bidder, *previous_bidder.key)?;
+
+    let now_ts = Clock::get()?.unix_timestamp as u64;
+    if now_ts >= auction_data.end_time {
+        msg!("Error: auction has ended");
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    // check bid meets the reserve, or beats the highest bid by the minimum increment
+    let min_price = if auction_data.highest_bid == 0 {
+        auction_data.reserve_price.max(1)
+    } else {
+        auction_data
+            .highest_bid
+            .saturating_add(auction_data.min_increment.max(1))
+    };
+    if args.price < min_price {
+        msg!("minimum bid is {}", min_price);
+        msg!("Error: bid too low");
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    // hold the new bid in the auction account and refund the previous one
+    invoke(
+        &system_instruction::transfer(
+            bidder.key,
+            auction.key,
+            args.price,
+        ),
+        &[
+            bidder.clone(),
+            auction.clone(),
+            system_program.clone(),
+        ],
+    )?;
+    transfer_lamports(auction, previous_bidder, auction_data.highest_bid)?;
+
+    auction_data.highest_bidder = *bidder.key;
+    auction_data.highest_bid = args.price;
+
+    // extend auction if the bid landed in its final minutes
+    if auction_data.end_time - now_ts <= AUCTION_EXTENSION_WINDOW {
+        auction_data.end_time = now_ts + AUCTION_EXTENSION_WINDOW;
+    }
+
+    auction_data.serialize(&mut *auction.data.borrow_mut())?;
+
+    Ok(())
+}
@@ -0,0 +1,112 @@
+use borsh::{BorshSerialize};
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    entrypoint::ProgramResult,
+    borsh::try_from_slice_unchecked,
+    msg,
+    program::invoke,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_instruction,
+    system_program,
+};
+
+use crate::{
+    instruction::PlaceBidArgs,
+    pda::{create_space_metadata_address, find_bid_address},
+    processor::processor_utils::{create_pda_account, transfer_lamports},
+    state::{
+        BID_RESERVE,
+        BID_SEED,
+        Bid,
+        SpaceMetadata,
+    },
+    validation_utils::assert_keys_equal,
+};
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: &PlaceBidArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let base = next_account_info(account_info_iter)?;
+    let space_metadata = next_account_info(account_info_iter)?;
+    let bid = next_account_info(account_info_iter)?;
+    let bidder = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    if !bidder.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // check programs
+    assert_keys_equal(system_program::id(), *system_program.key)?;
+
+    //deserialize and verify space metadata
+    let space_metadata_data: SpaceMetadata = try_from_slice_unchecked(&space_metadata.data.borrow())?;
+    let key = create_space_metadata_address(
+        program_id,
+        base.key,
+        args.space_x,
+        args.space_y,
+        space_metadata_data.bump,
+    )?;
+    assert_keys_equal(key, *space_metadata.key)?;
+
+    if args.price == 0 {
+        msg!("Error: bid price must be positive");
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    // verify bid
+    let (key, bump_bid) = find_bid_address(program_id, base.key, args.space_x, args.space_y, bidder.key);
+    assert_keys_equal(key, *bid.key)?;
+
+    let mut bid_data: Bid;
+    if bid.data_len() == 0 {
+        // create bid account holding rent plus the bid price
+        let seeds_bid = &[
+            &base.key.to_bytes(),
+            BID_SEED,
+            &args.space_x.to_le_bytes(),
+            &args.space_y.to_le_bytes(),
+            &bidder.key.to_bytes(),
+            &[bump_bid],
+        ];
+        create_pda_account(bidder, bid, system_program, BID_RESERVE, program_id, seeds_bid)?;
+        invoke(
+            &system_instruction::transfer(bidder.key, bid.key, args.price),
+            &[bidder.clone(), bid.clone(), system_program.clone()],
+        )?;
+        bid_data = try_from_slice_unchecked(&bid.data.borrow())?;
+        bid_data.bump = bump_bid;
+        bid_data.bidder = *bidder.key;
+        bid_data.space_x = args.space_x;
+        bid_data.space_y = args.space_y;
+    } else {
+        // change existing bid, topping up or refunding the difference
+        bid_data = try_from_slice_unchecked(&bid.data.borrow())?;
+        if args.price > bid_data.price {
+            invoke(
+                &system_instruction::transfer(
+                    bidder.key,
+                    bid.key,
+                    args.price - bid_data.price,
+                ),
+                &[
+                    bidder.clone(),
+                    bid.clone(),
+                    system_program.clone(),
+                ],
+            )?;
+        } else {
+            transfer_lamports(bid, bidder, bid_data.price - args.price)?;
+        }
+    }
+    bid_data.price = args.price;
+
+    bid_data.serialize(&mut *bid.data.borrow_mut())?;
+
+    Ok(())
+}
@@ -1,29 +1,28 @@
+use metaplex_token_metadata::state::Metadata;
+use borsh::BorshSerialize;
 use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    borsh::try_from_slice_unchecked,
+    clock::Clock,
+    entrypoint::ProgramResult,
     msg,
+    program::invoke,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_instruction,
+    sysvar::{rent::Rent, Sysvar},
 };
-use std::cmp;
-use crate::{
-    state::{
-        NEIGHBORHOOD_SIZE,
-    },
-};
+use std::{cmp, convert::TryFrom, str::FromStr};
 
-pub fn floor_divide(x: i64, y: usize) -> i64 {
-    if x >= 0{
-        return x / y as i64;
-    }
-    else {
-        let mut ans = x / y as i64;
-        if x % y as i64 != 0{
-            ans -= 1;
-        }
-        return ans;
-    }
-}
+pub use extend_common::utils::{close_program_account, create_pda_account, transfer_lamports};
 
-pub fn get_neighborhood_xy(x: i64, y: i64) -> (i64, i64){
-    return (floor_divide(x, NEIGHBORHOOD_SIZE), floor_divide(y, NEIGHBORHOOD_SIZE));
-}
+use crate::{
+    error::CustomError,
+    pda::{find_auction_address, find_listing_address, find_metaplex_metadata_address, find_rent_account_address},
+    state::{BPS_DENOMINATOR, LISTING_RESERVE, LISTING_SEED, RENT_PID, Listing, SpaceMetadata},
+    validation_utils::{assert_is_ata, assert_keys_equal, assert_owned_by, assert_rent_account},
+};
 
 pub fn get_space_xy_from_name(name: &str) -> (i64, i64) {
     let split = name.split('(');
@@ -42,4 +41,179 @@ pub fn get_neighborhood_creation_price(n_x: i64, n_y: i64) -> u64 {
     let price = 400000+400000*cmp::max(dist-3, 0);
     
     return price;
-}
\ No newline at end of file
+}
+
+// write the terms of owner's listing to the space's listing account, creating it paid by owner if necessary, and
+// taking over a listing account left by another seller by paying its rent back to them through listing_seller,
+// as closing it first would move lamports out of it directly ahead of the transfer that funds it again
+#[allow(clippy::too_many_arguments)]
+pub fn write_listing<'a>(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    listing: &AccountInfo<'a>,
+    owner: &AccountInfo<'a>,
+    listing_seller: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    space_x: i64,
+    space_y: i64,
+    mut listing_data: Listing,
+) -> ProgramResult {
+    let (key, bump_listing) = find_listing_address(program_id, base, space_x, space_y);
+    assert_keys_equal(key, *listing.key)?;
+    if listing.data_len() == 0 {
+        let seeds_listing = &[
+            &base.to_bytes(),
+            LISTING_SEED,
+            &space_x.to_le_bytes(),
+            &space_y.to_le_bytes(),
+            &[bump_listing],
+        ];
+        create_pda_account(owner, listing, system_program, LISTING_RESERVE, program_id, seeds_listing)?;
+    } else if listing.lamports() == 0 {
+        // the listing account was closed earlier in this transaction and would be purged at its end
+        invoke(
+            &system_instruction::transfer(owner.key, listing.key, Rent::default().minimum_balance(LISTING_RESERVE)),
+            &[owner.clone(), listing.clone(), system_program.clone()],
+        )?;
+    } else {
+        assert_owned_by(listing, program_id)?;
+        let previous_listing_data: Listing = try_from_slice_unchecked(&listing.data.borrow())?;
+        if previous_listing_data.seller != *owner.key {
+            assert_keys_equal(previous_listing_data.seller, *listing_seller.key)?;
+            invoke(
+                &system_instruction::transfer(owner.key, listing_seller.key, listing.lamports()),
+                &[owner.clone(), listing_seller.clone(), system_program.clone()],
+            )?;
+        }
+    }
+
+    listing_data.bump = bump_listing;
+    listing_data.seller = *owner.key;
+    listing_data.serialize(&mut *listing.data.borrow_mut())?;
+    Ok(())
+}
+
+// check owner holds the space in their token account to list it there, escrowed spaces are listed through ChangeEscrowOffer
+pub fn assert_space_held(space_metadata_data: &SpaceMetadata, ata_account: &AccountInfo, owner: &Pubkey) -> ProgramResult {
+    if space_metadata_data.escrow_seller != Pubkey::default() {
+        msg!("Error: space is escrowed, change the escrowed offer instead");
+        return Err(CustomError::InvalidListing.into());
+    }
+    assert_is_ata(ata_account, owner, &space_metadata_data.mint)?;
+    let ata_data = spl_token::state::Account::unpack_from_slice(&ata_account.data.borrow())?;
+    if ata_data.amount != 1 {
+        msg!("Error: token account does not own token");
+        return Err(CustomError::MissingTokenOwner.into());
+    }
+    Ok(())
+}
+
+// close the space's listing account if it has one, returning its rent to listing_seller, which has to be the
+// seller recorded in it as the listing may have been left by an earlier holder of the space
+pub fn close_listing(
+    program_id: &Pubkey,
+    base: &Pubkey,
+    listing: &AccountInfo,
+    listing_seller: &AccountInfo,
+    space_x: i64,
+    space_y: i64,
+) -> ProgramResult {
+    let (key, _) = find_listing_address(program_id, base, space_x, space_y);
+    assert_keys_equal(key, *listing.key)?;
+    if listing.data_len() != 0 && listing.lamports() != 0 {
+        assert_owned_by(listing, program_id)?;
+        let listing_data: Listing = try_from_slice_unchecked(&listing.data.borrow())?;
+        assert_keys_equal(listing_data.seller, *listing_seller.key)?;
+        close_program_account(listing, listing_seller)?;
+    }
+    Ok(())
+}
+
+// royalties owed on a sale at price to each verified creator with a share in the space's metaplex
+// metadata, reading the creators from account_info_iter in metadata order
+pub fn get_royalties<'a, 'b, I: Iterator<Item = &'a AccountInfo<'b>>>(
+    account_info_iter: &mut I,
+    space_metaplex_metadata: &AccountInfo,
+    space_mint: &Pubkey,
+    price: u64,
+) -> Result<Vec<(&'a AccountInfo<'b>, u64)>, ProgramError> {
+    // deserialize and verify metaplex metadata
+    let (metadata_key, _) = find_metaplex_metadata_address(&metaplex_token_metadata::id(), space_mint);
+    assert_keys_equal(metadata_key, *space_metaplex_metadata.key)?;
+    assert_owned_by(space_metaplex_metadata, &metaplex_token_metadata::id())?;
+    let space_metaplex_metadata_data = Metadata::from_account_info(space_metaplex_metadata)?;
+
+    let mut royalties = Vec::new();
+    let seller_fee_basis_points = space_metaplex_metadata_data.data.seller_fee_basis_points;
+    // as in metaplex, royalties are only owed on secondary sales, the creators being paid by the primary sale itself
+    if seller_fee_basis_points == 0 || !space_metaplex_metadata_data.primary_sale_happened {
+        return Ok(royalties);
+    }
+    let total = (price as u128)
+        .checked_mul(seller_fee_basis_points as u128)
+        .and_then(|total| total.checked_div(BPS_DENOMINATOR as u128))
+        .ok_or(ProgramError::InvalidArgument)?;
+    for creator in space_metaplex_metadata_data.data.creators.unwrap_or_default() {
+        if !creator.verified || creator.share == 0 {
+            continue;
+        }
+        let creator_account = next_account_info(account_info_iter)?;
+        assert_keys_equal(creator.address, *creator_account.key)?;
+        let royalty = total
+            .checked_mul(creator.share as u128)
+            .and_then(|royalty| royalty.checked_div(100))
+            .ok_or(ProgramError::InvalidArgument)?;
+        royalties.push((creator_account, u64::try_from(royalty).map_err(|_| ProgramError::InvalidArgument)?));
+    }
+    Ok(royalties)
+}
+
+// what the seller keeps of price after the marketplace fee and royalties
+pub fn get_seller_proceeds(price: u64, marketplace_fee: u64, royalties: &[(&AccountInfo, u64)]) -> Result<u64, ProgramError> {
+    royalties
+        .iter()
+        .try_fold(marketplace_fee, |total, (_, royalty)| total.checked_add(*royalty))
+        .and_then(|total| price.checked_sub(total))
+        .ok_or_else(|| {
+            msg!("Error: marketplace fee and royalties exceed price");
+            ProgramError::InvalidArgument
+        })
+}
+
+// whether the space is rented out right now, going by its rent account in the rent program,
+// which only exists once the space has been listed for rent
+pub fn is_rented(rent_account: &AccountInfo, base: &Pubkey, space_x: i64, space_y: i64) -> Result<bool, ProgramError> {
+    if rent_account.data_len() == 0 {
+        let (key, _) = find_rent_account_address(&Pubkey::from_str(RENT_PID).unwrap(), base, space_x, space_y);
+        assert_keys_equal(key, *rent_account.key)?;
+        return Ok(false);
+    }
+    let rent_account_data = assert_rent_account(rent_account, base, space_x, space_y)?;
+    Ok(rent_account_data.rent_end > Clock::get()?.unix_timestamp as u64)
+}
+
+// refuse to list or sell a space while it is rented out
+pub fn assert_not_rented(rent_account: &AccountInfo, base: &Pubkey, space_x: i64, space_y: i64) -> ProgramResult {
+    if is_rented(rent_account, base, space_x, space_y)? {
+        msg!("Error: space is rented out");
+        return Err(CustomError::UnterminatedRent.into());
+    }
+    Ok(())
+}
+
+// refuse to list a space while an auction of it has not been settled, a sale would take the space from its bidders
+pub fn assert_not_auctioned(
+    program_id: &Pubkey,
+    auction: &AccountInfo,
+    base: &Pubkey,
+    space_x: i64,
+    space_y: i64,
+) -> ProgramResult {
+    let (key, _) = find_auction_address(program_id, base, space_x, space_y);
+    assert_keys_equal(key, *auction.key)?;
+    if auction.data_len() != 0 {
+        msg!("Error: space is being auctioned");
+        return Err(CustomError::InvalidListing.into());
+    }
+    Ok(())
+}
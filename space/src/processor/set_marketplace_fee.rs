@@ -0,0 +1,65 @@
+use borsh::{BorshSerialize};
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    borsh::try_from_slice_unchecked,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use crate::{
+    error::CustomError,
+    instruction::SetMarketplaceFeeArgs,
+    pda::create_neighborhood_metadata_address,
+    state::{Base, NeighborhoodMetadata},
+    validation_utils::{assert_keys_equal, assert_owned_by},
+};
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: &SetMarketplaceFeeArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let base = next_account_info(account_info_iter)?;
+    let neighborhood_metadata = next_account_info(account_info_iter)?;
+    let creator = next_account_info(account_info_iter)?;
+
+    if !creator.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    //deserialize and verify base
+    assert_owned_by(base, program_id)?;
+    let base_data: Base = try_from_slice_unchecked(&base.data.borrow())?;
+
+    //deserialize and verify neighborhood metadata
+    let mut neighborhood_metadata_data: NeighborhoodMetadata = try_from_slice_unchecked(&neighborhood_metadata.data.borrow())?;
+    let key = create_neighborhood_metadata_address(
+        program_id,
+        base.key,
+        args.neighborhood_x,
+        args.neighborhood_y,
+        neighborhood_metadata_data.bump,
+    )?;
+    assert_keys_equal(key, *neighborhood_metadata.key)?;
+
+    // check that signer is creator of neighborhood
+    if neighborhood_metadata_data.creator != *creator.key {
+        msg!("Error: signer is not the neighborhood creator");
+        return Err(CustomError::WrongAuthority.into());
+    }
+
+    if args.fee_bps > base_data.get_max_marketplace_fee_bps() {
+        msg!("Error: fee is above the cap of {} bps", base_data.get_max_marketplace_fee_bps());
+        return Err(CustomError::FeeAboveCap.into());
+    }
+
+    // write to neighborhood metadata
+    neighborhood_metadata_data.marketplace_fee_bps = args.fee_bps;
+    neighborhood_metadata_data.marketplace_fee_bps_set = true;
+    neighborhood_metadata_data.serialize(&mut *neighborhood_metadata.data.borrow_mut())?;
+
+    Ok(())
+}
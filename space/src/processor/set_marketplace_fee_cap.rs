@@ -0,0 +1,48 @@
+use borsh::{BorshSerialize};
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    borsh::try_from_slice_unchecked,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use crate::{
+    instruction::SetMarketplaceFeeCapArgs,
+    state::{BPS_DENOMINATOR, Base},
+    validation_utils::{assert_keys_equal, assert_owned_by},
+};
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: &SetMarketplaceFeeCapArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let base = next_account_info(account_info_iter)?;
+    let authority = next_account_info(account_info_iter)?;
+
+    if !authority.is_signer {
+        msg!("Error: Missing signer");
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    assert_owned_by(base, program_id)?;
+    let mut base_data: Base = try_from_slice_unchecked(&base.data.borrow())?;
+
+    // check that signer is authority of base
+    assert_keys_equal(base_data.authority, *authority.key)?;
+
+    if args.max_fee_bps as u64 > BPS_DENOMINATOR {
+        msg!("Error: fee cap is above 100%");
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    // write to base
+    base_data.max_marketplace_fee_bps = args.max_fee_bps;
+    base_data.max_marketplace_fee_bps_set = true;
+    base_data.serialize(&mut *base.data.borrow_mut())?;
+
+    Ok(())
+}
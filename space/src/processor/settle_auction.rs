@@ -0,0 +1,230 @@
+use borsh::{BorshSerialize};
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    entrypoint::ProgramResult,
+    borsh::try_from_slice_unchecked,
+    clock::Clock,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_program,
+    sysvar::{rent, Sysvar},
+};
+use spl_associated_token_account;
+use spl_token;
+use extend_common::utils::{get_marketplace_fee, get_neighborhood_xy, split_fee};
+
+use crate::{
+    error::CustomError,
+    instruction::SettleAuctionArgs,
+    pda::{
+        create_auction_address,
+        create_neighborhood_metadata_address,
+        create_space_metadata_address,
+        find_sell_delegate_address,
+    },
+    processor::processor_utils::{
+        close_program_account,
+        get_royalties,
+        get_seller_proceeds,
+        is_rented,
+        transfer_lamports,
+    },
+    state::{
+        SELL_DELEGATE_SEED,
+        Auction,
+        Base,
+        NeighborhoodMetadata,
+        SpaceMetadata,
+    },
+    validation_utils::{assert_is_ata, assert_keys_equal, assert_owned_by},
+};
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: &SettleAuctionArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let base = next_account_info(account_info_iter)?;
+    let neighborhood_metadata = next_account_info(account_info_iter)?;
+    let neighborhood_creator = next_account_info(account_info_iter)?;
+    let space_metadata = next_account_info(account_info_iter)?;
+    let space_mint = next_account_info(account_info_iter)?;
+    let payer = next_account_info(account_info_iter)?;
+    let seller = next_account_info(account_info_iter)?;
+    let seller_ata_space = next_account_info(account_info_iter)?;
+    let winner = next_account_info(account_info_iter)?;
+    let winner_ata_space = next_account_info(account_info_iter)?;
+    let auction = next_account_info(account_info_iter)?;
+    let sell_delegate = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+    let associated_token_program = next_account_info(account_info_iter)?;
+    let rent_program = next_account_info(account_info_iter)?;
+    let treasury = next_account_info(account_info_iter)?;
+    let space_metaplex_metadata = next_account_info(account_info_iter)?;
+    let rent_account = next_account_info(account_info_iter)?;
+
+    if !payer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // check programs
+    assert_keys_equal(system_program::id(), *system_program.key)?;
+    assert_keys_equal(spl_token::id(), *token_program.key)?;
+    assert_keys_equal(spl_associated_token_account::id(), *associated_token_program.key)?;
+    assert_keys_equal(rent::id(), *rent_program.key)?;
+
+    //deserialize and verify base
+    assert_owned_by(base, program_id)?;
+    let base_data: Base = try_from_slice_unchecked(&base.data.borrow())?;
+
+    //deserialize and verify space metadata
+    let mut space_metadata_data: SpaceMetadata = try_from_slice_unchecked(&space_metadata.data.borrow())?;
+
+    if space_metadata_data.mint != *space_mint.key {
+        msg!("Error: space account does not match mint account");
+        return Err(CustomError::MintMismatch.into());
+    }
+
+    let key = create_space_metadata_address(
+        program_id,
+        base.key,
+        args.space_x,
+        args.space_y,
+        space_metadata_data.bump,
+    )?;
+    assert_keys_equal(key, *space_metadata.key)?;
+
+    //deserialize and verify neighborhood metadata
+    let neighborhood_metadata_data: NeighborhoodMetadata = try_from_slice_unchecked(&neighborhood_metadata.data.borrow())?;
+    let (neighborhood_x, neighborhood_y) = get_neighborhood_xy(args.space_x, args.space_y);
+    let key = create_neighborhood_metadata_address(
+        program_id,
+        base.key,
+        neighborhood_x,
+        neighborhood_y,
+        neighborhood_metadata_data.bump,
+    )?;
+    assert_keys_equal(key, *neighborhood_metadata.key)?;
+
+    //deserialize and verify auction
+    assert_owned_by(auction, program_id)?;
+    let auction_data: Auction = try_from_slice_unchecked(&auction.data.borrow())?;
+    let key = create_auction_address(
+        program_id,
+        base.key,
+        args.space_x,
+        args.space_y,
+        auction_data.bump,
+    )?;
+    assert_keys_equal(key, *auction.key)?;
+
+    let now_ts = Clock::get()?.unix_timestamp as u64;
+    if now_ts < auction_data.end_time {
+        msg!("Error: auction has not ended");
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    // verify sell_delegate
+    let (key, bump_sell_delegate) = find_sell_delegate_address(program_id, base.key);
+    assert_keys_equal(key, *sell_delegate.key)?;
+    let seeds_sell_delegate = &[&base.key.to_bytes(), SELL_DELEGATE_SEED, &[bump_sell_delegate]];
+
+    // check seller, winner, neighborhood creator and treasury are passed in correctly
+    assert_keys_equal(auction_data.seller, *seller.key)?;
+    assert_keys_equal(auction_data.highest_bidder, *winner.key)?;
+    assert_keys_equal(neighborhood_metadata_data.creator, *neighborhood_creator.key)?;
+    assert_keys_equal(base_data.get_treasury(), *treasury.key)?;
+
+    // no bids, close the auction
+    if auction_data.highest_bid == 0 {
+        close_program_account(auction, seller)?;
+        return Ok(());
+    }
+
+    // refund the highest bidder if the seller moved the space, revoked the delegate or rented the space out
+    assert_is_ata(seller_ata_space, seller.key, space_mint.key)?;
+    let seller_ata_space_data = spl_token::state::Account::unpack(&seller_ata_space.data.borrow())?;
+    if seller_ata_space_data.amount != 1
+        || !seller_ata_space_data.delegate.contains(sell_delegate.key)
+        || is_rented(rent_account, base.key, args.space_x, args.space_y)?
+    {
+        msg!("space is no longer auctioned by seller, refunding highest bid");
+        transfer_lamports(auction, winner, auction_data.highest_bid)?;
+        close_program_account(auction, seller)?;
+        return Ok(());
+    }
+
+    // check royalty creators are passed in correctly
+    let royalties = get_royalties(account_info_iter, space_metaplex_metadata, space_mint.key, auction_data.highest_bid)?;
+
+    // create ATA if necessary
+    if winner_ata_space.data_len() == 0 {
+        invoke(
+            &spl_associated_token_account::create_associated_token_account(
+                payer.key,
+                winner.key,
+                space_mint.key,
+            ),
+            &[
+                payer.clone(),
+                winner_ata_space.clone(),
+                winner.clone(),
+                space_mint.clone(),
+                system_program.clone(),
+                token_program.clone(),
+                rent_program.clone(),
+                associated_token_program.clone(),
+            ],
+        )?;
+    }
+    assert_is_ata(winner_ata_space, winner.key, space_mint.key)?;
+
+    // transfer NFT from seller to winner
+    invoke_signed(
+        &spl_token::instruction::transfer(
+            token_program.key,
+            seller_ata_space.key,
+            winner_ata_space.key,
+            sell_delegate.key,
+            &[],
+            1,
+        )?,
+        &[
+            token_program.clone(),
+            seller_ata_space.clone(),
+            winner_ata_space.clone(),
+            sell_delegate.clone(),
+        ],
+        &[seeds_sell_delegate],
+    )?;
+
+    // pay seller, neighborhood creator, treasury and royalty creators out of the highest bid,
+    // returning the auction rent to the seller
+    let marketplace_fee = get_marketplace_fee(
+        auction_data.highest_bid,
+        neighborhood_metadata_data.get_marketplace_fee_bps(),
+        base_data.get_max_marketplace_fee_bps(),
+    )?;
+    let (creator_fee, treasury_fee) = split_fee(marketplace_fee, base_data.treasury_fee_share_bps)?;
+    let proceeds = get_seller_proceeds(auction_data.highest_bid, marketplace_fee, &royalties)?;
+    transfer_lamports(auction, neighborhood_creator, creator_fee)?;
+    transfer_lamports(auction, treasury, treasury_fee)?;
+    for (creator, royalty) in royalties {
+        transfer_lamports(auction, creator, royalty)?;
+    }
+    transfer_lamports(auction, seller, proceeds)?;
+    close_program_account(auction, seller)?;
+
+    // any sell offer is void now that the space changed hands
+    space_metadata_data.price = 0;
+    space_metadata_data.payment_mint = Pubkey::default();
+    space_metadata_data.listing_terms = false;
+    space_metadata_data.serialize(&mut *space_metadata.data.borrow_mut())?;
+
+    Ok(())
+}
@@ -13,10 +13,8 @@ use spl_token;
 use crate::{
     error::CustomError,
     instruction::TempAddxyArgs,
-    state::{
-        SPACE_METADATA_SEED,
-        SpaceMetadata,
-    },
+    pda::create_space_metadata_address,
+    state::SpaceMetadata,
     validation_utils::{assert_is_ata, assert_keys_equal},
 };
 
@@ -38,14 +36,13 @@ pub fn process(
     //deserialize and verify space metadata
     let mut space_metadata_data: SpaceMetadata = try_from_slice_unchecked(&space_metadata.data.borrow_mut())?;
 
-    let seeds_space_metadata = &[
-        &base.key.to_bytes(),
-        SPACE_METADATA_SEED,
-        &args.space_x.to_le_bytes(),
-        &args.space_y.to_le_bytes(),
-        &[space_metadata_data.bump],
-    ];
-    let key = Pubkey::create_program_address(seeds_space_metadata, program_id)?;
+    let key = create_space_metadata_address(
+        program_id,
+        base.key,
+        args.space_x,
+        args.space_y,
+        space_metadata_data.bump,
+    )?;
     assert_keys_equal(key, *space_metadata.key)?;
 
 
@@ -1,51 +0,0 @@
-use solana_program::{
-    account_info::AccountInfo,
-    entrypoint::ProgramResult,
-    msg,
-    program_error::ProgramError,
-    program_pack::{IsInitialized, Pack},
-    pubkey::Pubkey,
-};
-use spl_associated_token_account::get_associated_token_address;
-use spl_token;
-use spl_token::state::Account;
-
-use super::error::CustomError;
-
-pub fn assert_is_ata(ata: &AccountInfo, wallet: &Pubkey, mint: &Pubkey) -> ProgramResult {
-    assert_owned_by(ata, &spl_token::id())?;
-    let ata_account: Account = assert_initialized(ata)?;
-    assert_keys_equal(ata_account.owner, *wallet)?;
-    assert_keys_equal(get_associated_token_address(wallet, mint), *ata.key)?;
-    Ok(())
-}
-
-pub fn assert_keys_equal(key1: Pubkey, key2: Pubkey) -> ProgramResult {
-    if key1 != key2 {
-        msg!("Error: unexpected public key, validation utils");
-        Err(CustomError::PublicKeyMismatch.into())
-    } else {
-        Ok(())
-    }
-}
-
-pub fn assert_initialized<T: Pack + IsInitialized>(
-    account_info: &AccountInfo,
-) -> Result<T, ProgramError> {
-    let account: T = T::unpack_unchecked(&account_info.data.borrow())?;
-    if !account.is_initialized() {
-        msg!("Error: account uninitialized, validation utils");
-        Err(CustomError::UninitializedAccount.into())
-    } else {
-        Ok(account)
-    }
-}
-
-pub fn assert_owned_by(account: &AccountInfo, owner: &Pubkey) -> ProgramResult {
-    if account.owner != owner {
-        msg!("Error: owner mismatch, validation utils");
-        Err(CustomError::OwnerAccountMismatch.into())
-    } else {
-        Ok(())
-    }
-}
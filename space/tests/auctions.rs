@@ -0,0 +1,370 @@
+// run against the BPF build with `cargo test-bpf`
+#![cfg(feature = "test-bpf")]
+
+mod utils;
+
+use extend_space::{
+    instruction,
+    pda::{find_auction_address, find_listing_address, find_space_metadata_address},
+    state::{AUCTION_EXTENSION_WINDOW, AUCTION_RESERVE, BPS_DENOMINATOR, MARKETPLACE_FEE_BPS},
+};
+use solana_program::{pubkey::Pubkey, rent::Rent};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::signature::{Keypair, Signer};
+use spl_associated_token_account::get_associated_token_address;
+use utils::*;
+
+const RESERVE_PRICE: u64 = 1_000_000_000;
+const MIN_INCREMENT: u64 = 100_000_000;
+const DURATION: u64 = 3600;
+
+async fn create_auction(
+    context: &mut ProgramTestContext,
+    base: &Pubkey,
+    space_mint: &Pubkey,
+    seller: &Keypair,
+    end_time: u64,
+) -> Result<(), Box<dyn std::error::Error>> {
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::create_auction(
+            &program_id(),
+            base,
+            space_mint,
+            &seller.pubkey(),
+            &seller.pubkey(),
+            SPACE_X,
+            SPACE_Y,
+            RESERVE_PRICE,
+            end_time,
+            MIN_INCREMENT,
+        )],
+        &[seller],
+    ).await
+}
+
+async fn place_auction_bid(
+    context: &mut ProgramTestContext,
+    base: &Pubkey,
+    bidder: &Keypair,
+    previous_bidder: &Pubkey,
+    price: u64,
+) -> Result<(), Box<dyn std::error::Error>> {
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::place_auction_bid(
+            &program_id(),
+            base,
+            &bidder.pubkey(),
+            previous_bidder,
+            SPACE_X,
+            SPACE_Y,
+            price,
+        )],
+        &[bidder],
+    ).await
+}
+
+async fn settle_auction(
+    context: &mut ProgramTestContext,
+    base: &Pubkey,
+    space_mint: &Pubkey,
+    seller: &Pubkey,
+    winner: &Pubkey,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let payer = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::settle_auction(
+            &program_id(),
+            base,
+            SPACE_X,
+            SPACE_Y,
+            &payer.pubkey(),
+            seller,
+            winner,
+            space_mint,
+            &payer.pubkey(),
+            &payer.pubkey(),
+            &[],
+        )],
+        &[&payer],
+    ).await
+}
+
+async fn change_offer(
+    context: &mut ProgramTestContext,
+    base: &Pubkey,
+    space_mint: &Pubkey,
+    seller: &Keypair,
+    price: u64,
+) -> Result<(), Box<dyn std::error::Error>> {
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::change_offer(&program_id(), base, space_mint, &seller.pubkey(), SPACE_X, SPACE_Y, price, true)],
+        &[seller],
+    ).await
+}
+
+#[tokio::test]
+async fn test_auction_bids_and_refunds() {
+    let SpaceFixture { mut context, base, seller, buyer, space_mint } = setup_space().await;
+    let base = base.pubkey();
+    let other_bidder = Keypair::new();
+    let (auction, _) = find_auction_address(&program_id(), &base, SPACE_X, SPACE_Y);
+    let auction_rent = Rent::default().minimum_balance(AUCTION_RESERVE);
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[solana_program::system_instruction::transfer(
+            &buyer.pubkey(),
+            &other_bidder.pubkey(),
+            5 * RESERVE_PRICE,
+        )],
+        &[&buyer],
+    ).await.unwrap();
+
+    let now = get_unix_timestamp(&mut context.banks_client).await;
+    assert!(create_auction(&mut context, &base, &space_mint, &seller, now).await.is_err());
+    create_auction(&mut context, &base, &space_mint, &seller, now + DURATION).await.unwrap();
+    assert!(create_auction(&mut context, &base, &space_mint, &seller, now + DURATION + 1).await.is_err());
+    let auction_data = get_auction(&mut context.banks_client, &auction).await;
+    assert_eq!(auction_data.seller, seller.pubkey());
+    assert_eq!(auction_data.highest_bidder, seller.pubkey());
+    assert_eq!(auction_data.end_time, now + DURATION);
+
+    // the sell delegate is approved for the auction, but the space is not listed
+    let creator = context.payer.pubkey();
+    assert!(process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::accept_offer(
+            &program_id(),
+            &base,
+            SPACE_X,
+            SPACE_Y,
+            0,
+            &buyer.pubkey(),
+            &seller.pubkey(),
+            &space_mint,
+            &creator,
+            &creator,
+            &[],
+        )],
+        &[&buyer],
+    ).await.is_err());
+
+    // below reserve, then the first bid at reserve
+    assert!(place_auction_bid(&mut context, &base, &buyer, &seller.pubkey(), RESERVE_PRICE - 1).await.is_err());
+    place_auction_bid(&mut context, &base, &buyer, &seller.pubkey(), RESERVE_PRICE).await.unwrap();
+    assert_eq!(context.banks_client.get_balance(auction).await.unwrap(), auction_rent + RESERVE_PRICE);
+
+    // outbidding needs the minimum increment and refunds the previous bidder
+    let buyer_lamports = context.banks_client.get_balance(buyer.pubkey()).await.unwrap();
+    let price = RESERVE_PRICE + MIN_INCREMENT;
+    assert!(place_auction_bid(&mut context, &base, &other_bidder, &buyer.pubkey(), price - 1).await.is_err());
+    assert!(place_auction_bid(&mut context, &base, &other_bidder, &seller.pubkey(), price).await.is_err());
+    place_auction_bid(&mut context, &base, &other_bidder, &buyer.pubkey(), price).await.unwrap();
+    assert_eq!(context.banks_client.get_balance(buyer.pubkey()).await.unwrap(), buyer_lamports + RESERVE_PRICE);
+    assert_eq!(context.banks_client.get_balance(auction).await.unwrap(), auction_rent + price);
+
+    let auction_data = get_auction(&mut context.banks_client, &auction).await;
+    assert_eq!(auction_data.highest_bidder, other_bidder.pubkey());
+    assert_eq!(auction_data.highest_bid, price);
+    assert_eq!(auction_data.end_time, now + DURATION);
+
+    assert!(settle_auction(&mut context, &base, &space_mint, &seller.pubkey(), &other_bidder.pubkey()).await.is_err());
+}
+
+#[tokio::test]
+async fn test_auction_extends_on_late_bid() {
+    let SpaceFixture { mut context, base, seller, buyer, space_mint } = setup_space().await;
+    let base = base.pubkey();
+    let (auction, _) = find_auction_address(&program_id(), &base, SPACE_X, SPACE_Y);
+
+    let now = get_unix_timestamp(&mut context.banks_client).await;
+    create_auction(&mut context, &base, &space_mint, &seller, now + 60).await.unwrap();
+    place_auction_bid(&mut context, &base, &buyer, &seller.pubkey(), RESERVE_PRICE).await.unwrap();
+    let after = get_unix_timestamp(&mut context.banks_client).await;
+
+    let end_time = get_auction(&mut context.banks_client, &auction).await.end_time;
+    assert!(end_time >= now + AUCTION_EXTENSION_WINDOW);
+    assert!(end_time <= after + AUCTION_EXTENSION_WINDOW);
+}
+
+#[tokio::test]
+async fn test_settle_auction() {
+    let SpaceFixture { mut context, base, seller, buyer, space_mint } = setup_space().await;
+    let base = base.pubkey();
+    let creator = context.payer.pubkey();
+    let (auction, _) = find_auction_address(&program_id(), &base, SPACE_X, SPACE_Y);
+
+    let now = get_unix_timestamp(&mut context.banks_client).await;
+    create_auction(&mut context, &base, &space_mint, &seller, now + DURATION).await.unwrap();
+    place_auction_bid(&mut context, &base, &buyer, &seller.pubkey(), RESERVE_PRICE).await.unwrap();
+    let end_time = get_auction(&mut context.banks_client, &auction).await.end_time;
+    warp_past(&mut context, end_time).await;
+
+    // bids after the end are refused, placed by the buyer as a failed transaction may still charge its fee
+    // after its error is returned, which would throw off the seller balances below
+    assert!(place_auction_bid(&mut context, &base, &buyer, &buyer.pubkey(), 2 * RESERVE_PRICE).await.is_err());
+    assert!(settle_auction(&mut context, &base, &space_mint, &seller.pubkey(), &seller.pubkey()).await.is_err());
+
+    let seller_lamports = context.banks_client.get_balance(seller.pubkey()).await.unwrap();
+    let auction_lamports = context.banks_client.get_balance(auction).await.unwrap();
+    let creator_lamports = context.banks_client.get_balance(creator).await.unwrap();
+    settle_auction(&mut context, &base, &space_mint, &seller.pubkey(), &buyer.pubkey()).await.unwrap();
+
+    let banks_client = &mut context.banks_client;
+    let seller_ata = get_associated_token_address(&seller.pubkey(), &space_mint);
+    let buyer_ata = get_associated_token_address(&buyer.pubkey(), &space_mint);
+    assert_eq!(get_token_account(banks_client, &seller_ata).await.amount, 0);
+    assert_eq!(get_token_account(banks_client, &buyer_ata).await.amount, 1);
+    assert!(banks_client.get_account(auction).await.unwrap().is_none());
+
+    // seller gets the bid less the marketplace fee and the auction rent back, creator paid the ATA
+    let fee = RESERVE_PRICE * MARKETPLACE_FEE_BPS as u64 / BPS_DENOMINATOR;
+    assert_eq!(
+        banks_client.get_balance(seller.pubkey()).await.unwrap(),
+        seller_lamports + auction_lamports - fee,
+    );
+    let ata_rent = banks_client.get_balance(buyer_ata).await.unwrap();
+    assert!(banks_client.get_balance(creator).await.unwrap() > creator_lamports + fee - ata_rent - 10_000);
+}
+
+#[tokio::test]
+async fn test_settle_auction_refunds_when_space_moved() {
+    let SpaceFixture { mut context, base, seller, buyer, space_mint } = setup_space().await;
+    let base = base.pubkey();
+    let (auction, _) = find_auction_address(&program_id(), &base, SPACE_X, SPACE_Y);
+
+    let now = get_unix_timestamp(&mut context.banks_client).await;
+    create_auction(&mut context, &base, &space_mint, &seller, now + DURATION).await.unwrap();
+    place_auction_bid(&mut context, &base, &buyer, &seller.pubkey(), RESERVE_PRICE).await.unwrap();
+
+    // seller pulls the space out of the auction
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::change_offer(&program_id(), &base, &space_mint, &seller.pubkey(), SPACE_X, SPACE_Y, 0, false)],
+        &[&seller],
+    ).await.unwrap();
+
+    let end_time = get_auction(&mut context.banks_client, &auction).await.end_time;
+    warp_past(&mut context, end_time).await;
+    let buyer_lamports = context.banks_client.get_balance(buyer.pubkey()).await.unwrap();
+    settle_auction(&mut context, &base, &space_mint, &seller.pubkey(), &buyer.pubkey()).await.unwrap();
+
+    let seller_ata = get_associated_token_address(&seller.pubkey(), &space_mint);
+    assert_eq!(get_token_account(&mut context.banks_client, &seller_ata).await.amount, 1);
+    assert_eq!(context.banks_client.get_balance(buyer.pubkey()).await.unwrap(), buyer_lamports + RESERVE_PRICE);
+    assert!(context.banks_client.get_account(auction).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_space_cannot_be_listed_until_auction_is_settled() {
+    let SpaceFixture { mut context, base, seller, space_mint, .. } = setup_space().await;
+    let base = base.pubkey();
+    let (auction, _) = find_auction_address(&program_id(), &base, SPACE_X, SPACE_Y);
+
+    let now = get_unix_timestamp(&mut context.banks_client).await;
+    create_auction(&mut context, &base, &space_mint, &seller, now + DURATION).await.unwrap();
+
+    // none of the ways to list the space get around the auction
+    let listings = [
+        instruction::change_offer(&program_id(), &base, &space_mint, &seller.pubkey(), SPACE_X, SPACE_Y, RESERVE_PRICE, true),
+        instruction::change_dutch_offer(
+            &program_id(),
+            &base,
+            &space_mint,
+            &seller.pubkey(),
+            &seller.pubkey(),
+            SPACE_X,
+            SPACE_Y,
+            2 * RESERVE_PRICE,
+            RESERVE_PRICE,
+            now,
+            now + DURATION,
+        ),
+        instruction::change_escrow_offer(
+            &program_id(),
+            &base,
+            &space_mint,
+            &seller.pubkey(),
+            &seller.pubkey(),
+            SPACE_X,
+            SPACE_Y,
+            RESERVE_PRICE,
+            true,
+        ),
+        instruction::change_offer_batch(
+            &program_id(),
+            &base,
+            &seller.pubkey(),
+            &[space_mint],
+            &[seller.pubkey()],
+            vec![instruction::SpaceOffer { space_x: SPACE_X, space_y: SPACE_Y, price: RESERVE_PRICE }],
+        ),
+    ];
+    for listing in listings.iter() {
+        assert!(process_instructions(
+            &mut context.banks_client,
+            context.last_blockhash,
+            std::slice::from_ref(listing),
+            &[&seller],
+        ).await.is_err());
+    }
+
+    // the auction has to be settled once it ends before the space can be listed again
+    let end_time = get_auction(&mut context.banks_client, &auction).await.end_time;
+    warp_past(&mut context, end_time).await;
+    assert!(change_offer(&mut context, &base, &space_mint, &seller, RESERVE_PRICE + 1).await.is_err());
+    settle_auction(&mut context, &base, &space_mint, &seller.pubkey(), &seller.pubkey()).await.unwrap();
+    change_offer(&mut context, &base, &space_mint, &seller, RESERVE_PRICE + 2).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_create_auction_closes_listing() {
+    let SpaceFixture { mut context, base, seller, space_mint, .. } = setup_space().await;
+    let base = base.pubkey();
+    let (listing, _) = find_listing_address(&program_id(), &base, SPACE_X, SPACE_Y);
+    let (space_metadata, _) = find_space_metadata_address(&program_id(), &base, SPACE_X, SPACE_Y);
+
+    let now = get_unix_timestamp(&mut context.banks_client).await;
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::change_expiring_offer(&program_id(), &base, &space_mint, &seller.pubkey(), SPACE_X, SPACE_Y, RESERVE_PRICE, now + DURATION)],
+        &[&seller],
+    ).await.unwrap();
+    assert!(context.banks_client.get_account(listing).await.unwrap().is_some());
+
+    create_auction(&mut context, &base, &space_mint, &seller, now + DURATION).await.unwrap();
+    let space_metadata_data = get_space_metadata(&mut context.banks_client, &space_metadata).await;
+    assert_eq!(space_metadata_data.price, 0);
+    assert!(!space_metadata_data.listing_terms);
+    assert!(context.banks_client.get_account(listing).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_prefunded_auction_account_does_not_block_create_auction() {
+    let SpaceFixture { mut context, base, seller, space_mint, .. } = setup_space().await;
+    let base = base.pubkey();
+    let (auction, _) = find_auction_address(&program_id(), &base, SPACE_X, SPACE_Y);
+
+    // lamports sent to the auction address ahead of the auction count towards its rent exemption
+    let payer = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[solana_program::system_instruction::transfer(&payer.pubkey(), &auction, 1)],
+        &[&payer],
+    ).await.unwrap();
+    let now = get_unix_timestamp(&mut context.banks_client).await;
+    create_auction(&mut context, &base, &space_mint, &seller, now + DURATION).await.unwrap();
+    assert_eq!(get_auction(&mut context.banks_client, &auction).await.seller, seller.pubkey());
+}
@@ -0,0 +1,237 @@
+// run against the BPF build with `cargo test-bpf`
+#![cfg(feature = "test-bpf")]
+
+mod utils;
+
+use extend_space::{
+    instruction,
+    pda::{find_bid_address, find_listing_address, find_space_metadata_address},
+    state::{BID_RESERVE, BPS_DENOMINATOR, MARKETPLACE_FEE_BPS, Bid},
+};
+use solana_program::{borsh::try_from_slice_unchecked, pubkey::Pubkey, rent::Rent};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::signature::{Keypair, Signer};
+use spl_associated_token_account::get_associated_token_address;
+use utils::*;
+
+const PRICE: u64 = 1_000_000_000;
+
+async fn place_bid(
+    context: &mut ProgramTestContext,
+    base: &Pubkey,
+    bidder: &Keypair,
+    price: u64,
+) -> Result<(), Box<dyn std::error::Error>> {
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::place_bid(&program_id(), base, &bidder.pubkey(), SPACE_X, SPACE_Y, price)],
+        &[bidder],
+    ).await
+}
+
+async fn accept_bid(
+    context: &mut ProgramTestContext,
+    base: &Pubkey,
+    space_mint: &Pubkey,
+    owner: &Keypair,
+    bidder: &Pubkey,
+    price: u64,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let creator = context.payer.pubkey();
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::accept_bid(
+            &program_id(),
+            base,
+            SPACE_X,
+            SPACE_Y,
+            price,
+            &owner.pubkey(),
+            bidder,
+            &owner.pubkey(),
+            space_mint,
+            &creator,
+            &creator,
+            &[],
+        )],
+        &[owner],
+    ).await
+}
+
+#[tokio::test]
+async fn test_place_change_and_cancel_bid() {
+    let SpaceFixture { mut context, base, buyer, .. } = setup_space().await;
+    let base = base.pubkey();
+    let (bid, bump) = find_bid_address(&program_id(), &base, SPACE_X, SPACE_Y, &buyer.pubkey());
+    let bid_rent = Rent::default().minimum_balance(BID_RESERVE);
+
+    place_bid(&mut context, &base, &buyer, PRICE).await.unwrap();
+    let bid_account = get_account(&mut context.banks_client, &bid).await;
+    assert_eq!(bid_account.lamports, bid_rent + PRICE);
+    let bid_data: Bid = try_from_slice_unchecked(&bid_account.data).unwrap();
+    assert_eq!(bid_data.bump, bump);
+    assert_eq!(bid_data.bidder, buyer.pubkey());
+    assert_eq!((bid_data.space_x, bid_data.space_y), (SPACE_X, SPACE_Y));
+    assert_eq!(bid_data.price, PRICE);
+
+    place_bid(&mut context, &base, &buyer, 2 * PRICE).await.unwrap();
+    assert_eq!(context.banks_client.get_balance(bid).await.unwrap(), bid_rent + 2 * PRICE);
+
+    place_bid(&mut context, &base, &buyer, PRICE / 2).await.unwrap();
+    assert_eq!(context.banks_client.get_balance(bid).await.unwrap(), bid_rent + PRICE / 2);
+
+    let buyer_lamports = context.banks_client.get_balance(buyer.pubkey()).await.unwrap();
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::cancel_bid(&program_id(), &base, &buyer.pubkey(), SPACE_X, SPACE_Y)],
+        &[&buyer],
+    ).await.unwrap();
+    assert!(context.banks_client.get_account(bid).await.unwrap().is_none());
+    assert!(context.banks_client.get_balance(buyer.pubkey()).await.unwrap() > buyer_lamports + PRICE / 2);
+}
+
+#[tokio::test]
+async fn test_accept_bid() {
+    let SpaceFixture { mut context, base, seller, buyer, space_mint } = setup_space().await;
+    let base = base.pubkey();
+    let creator = context.payer.pubkey();
+    let (bid, _) = find_bid_address(&program_id(), &base, SPACE_X, SPACE_Y, &buyer.pubkey());
+    let (space_metadata, _) = find_space_metadata_address(&program_id(), &base, SPACE_X, SPACE_Y);
+
+    place_bid(&mut context, &base, &buyer, PRICE).await.unwrap();
+
+    let buyer_lamports = context.banks_client.get_balance(buyer.pubkey()).await.unwrap();
+    let creator_lamports = context.banks_client.get_balance(creator).await.unwrap();
+    let bid_lamports = context.banks_client.get_balance(bid).await.unwrap();
+    accept_bid(&mut context, &base, &space_mint, &seller, &buyer.pubkey(), PRICE).await.unwrap();
+
+    let banks_client = &mut context.banks_client;
+    let seller_ata = get_associated_token_address(&seller.pubkey(), &space_mint);
+    let buyer_ata = get_associated_token_address(&buyer.pubkey(), &space_mint);
+    assert_eq!(get_token_account(banks_client, &seller_ata).await.amount, 0);
+    assert_eq!(get_token_account(banks_client, &buyer_ata).await.amount, 1);
+    assert!(banks_client.get_account(bid).await.unwrap().is_none());
+
+    // creator gets the marketplace fee, bidder gets the bid rent back
+    let fee = PRICE * MARKETPLACE_FEE_BPS as u64 / BPS_DENOMINATOR;
+    assert_eq!(banks_client.get_balance(creator).await.unwrap(), creator_lamports + fee);
+    assert_eq!(
+        banks_client.get_balance(buyer.pubkey()).await.unwrap(),
+        buyer_lamports + bid_lamports - PRICE,
+    );
+    assert_eq!(get_space_metadata(banks_client, &space_metadata).await.price, 0);
+}
+
+#[tokio::test]
+async fn test_accept_bid_clears_listing() {
+    let SpaceFixture { mut context, base, seller, buyer, space_mint } = setup_space().await;
+    let base = base.pubkey();
+    let (listing, _) = find_listing_address(&program_id(), &base, SPACE_X, SPACE_Y);
+    let (space_metadata, _) = find_space_metadata_address(&program_id(), &base, SPACE_X, SPACE_Y);
+
+    // listed in tokens until some time, then sold to a bidder instead
+    let payment_mint = create_payment_mint(&mut context, 6).await;
+    let now = get_unix_timestamp(&mut context.banks_client).await;
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::change_token_offer(
+            &program_id(),
+            &base,
+            &space_mint,
+            &seller.pubkey(),
+            &seller.pubkey(),
+            &payment_mint,
+            SPACE_X,
+            SPACE_Y,
+            2 * PRICE,
+            true,
+            now + 3600,
+        )],
+        &[&seller],
+    ).await.unwrap();
+    place_bid(&mut context, &base, &buyer, PRICE).await.unwrap();
+    accept_bid(&mut context, &base, &space_mint, &seller, &buyer.pubkey(), PRICE).await.unwrap();
+
+    let banks_client = &mut context.banks_client;
+    assert!(banks_client.get_account(listing).await.unwrap().is_none());
+    let space_metadata_data = get_space_metadata(banks_client, &space_metadata).await;
+    assert_eq!(space_metadata_data.price, 0);
+    assert_eq!(space_metadata_data.payment_mint, Pubkey::default());
+    assert_eq!(space_metadata_data.escrow_seller, Pubkey::default());
+    assert!(!space_metadata_data.listing_terms);
+}
+
+#[tokio::test]
+async fn test_accept_bid_rejects_changed_price() {
+    let SpaceFixture { mut context, base, seller, buyer, space_mint } = setup_space().await;
+    let base = base.pubkey();
+
+    place_bid(&mut context, &base, &buyer, PRICE).await.unwrap();
+    place_bid(&mut context, &base, &buyer, PRICE / 2).await.unwrap();
+    assert!(accept_bid(&mut context, &base, &space_mint, &seller, &buyer.pubkey(), PRICE).await.is_err());
+}
+
+#[tokio::test]
+async fn test_accept_bid_rejects_auctioned_space() {
+    let SpaceFixture { mut context, base, seller, buyer, space_mint } = setup_space().await;
+    let base = base.pubkey();
+
+    let now = get_unix_timestamp(&mut context.banks_client).await;
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::create_auction(
+            &program_id(),
+            &base,
+            &space_mint,
+            &seller.pubkey(),
+            &seller.pubkey(),
+            SPACE_X,
+            SPACE_Y,
+            PRICE,
+            now + 3600,
+            PRICE / 10,
+        )],
+        &[&seller],
+    ).await.unwrap();
+
+    // a direct bid can not take the space from the auction's bidders
+    place_bid(&mut context, &base, &buyer, PRICE).await.unwrap();
+    assert!(accept_bid(&mut context, &base, &space_mint, &seller, &buyer.pubkey(), PRICE).await.is_err());
+    let seller_ata = get_associated_token_address(&seller.pubkey(), &space_mint);
+    assert_eq!(get_token_account(&mut context.banks_client, &seller_ata).await.amount, 1);
+}
+
+#[tokio::test]
+async fn test_accept_bid_requires_space_owner() {
+    let SpaceFixture { mut context, base, buyer, space_mint, .. } = setup_space().await;
+    let base = base.pubkey();
+
+    place_bid(&mut context, &base, &buyer, PRICE).await.unwrap();
+    assert!(accept_bid(&mut context, &base, &space_mint, &buyer, &buyer.pubkey(), PRICE).await.is_err());
+}
+
+#[tokio::test]
+async fn test_prefunded_bid_account_does_not_block_bidder() {
+    let SpaceFixture { mut context, base, buyer, .. } = setup_space().await;
+    let base = base.pubkey();
+    let (bid, _) = find_bid_address(&program_id(), &base, SPACE_X, SPACE_Y, &buyer.pubkey());
+
+    // lamports sent to the bid address ahead of the bid count towards its rent exemption
+    let payer = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[solana_program::system_instruction::transfer(&payer.pubkey(), &bid, 1)],
+        &[&payer],
+    ).await.unwrap();
+    place_bid(&mut context, &base, &buyer, PRICE).await.unwrap();
+    assert_eq!(
+        context.banks_client.get_balance(bid).await.unwrap(),
+        Rent::default().minimum_balance(BID_RESERVE) + PRICE,
+    );
+}
@@ -0,0 +1,152 @@
+// run against the BPF build with `cargo test-bpf`
+#![cfg(feature = "test-bpf")]
+
+mod utils;
+
+use extend_space::{
+    instruction,
+    pda::{find_listing_address, find_space_metadata_address},
+    state::{BPS_DENOMINATOR, MARKETPLACE_FEE_BPS},
+};
+use solana_program::pubkey::Pubkey;
+use solana_program_test::ProgramTestContext;
+use solana_sdk::signature::{Keypair, Signer};
+use spl_associated_token_account::get_associated_token_address;
+use utils::*;
+
+const PRICE: u64 = 1_000_000_000;
+const DURATION: u64 = 3600;
+
+#[allow(clippy::too_many_arguments)]
+async fn change_dutch_offer(
+    context: &mut ProgramTestContext,
+    base: &Pubkey,
+    space_mint: &Pubkey,
+    seller: &Keypair,
+    start_price: u64,
+    end_price: u64,
+    start_time: u64,
+    end_time: u64,
+) -> Result<(), Box<dyn std::error::Error>> {
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::change_dutch_offer(
+            &program_id(),
+            base,
+            space_mint,
+            &seller.pubkey(),
+            &seller.pubkey(),
+            SPACE_X,
+            SPACE_Y,
+            start_price,
+            end_price,
+            start_time,
+            end_time,
+        )],
+        &[seller],
+    ).await
+}
+
+async fn accept_offer(
+    context: &mut ProgramTestContext,
+    base: &Pubkey,
+    space_mint: &Pubkey,
+    seller: &Keypair,
+    buyer: &Keypair,
+    max_price: u64,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let creator = context.payer.pubkey();
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::accept_offer(
+            &program_id(),
+            base,
+            SPACE_X,
+            SPACE_Y,
+            max_price,
+            &buyer.pubkey(),
+            &seller.pubkey(),
+            space_mint,
+            &creator,
+            &creator,
+            &[],
+        )],
+        &[buyer],
+    ).await
+}
+
+#[tokio::test]
+async fn test_dutch_offer_price_declines() {
+    let SpaceFixture { mut context, base, seller, buyer, space_mint } = setup_space().await;
+    let base = base.pubkey();
+    let (listing, _) = find_listing_address(&program_id(), &base, SPACE_X, SPACE_Y);
+    let (space_metadata, _) = find_space_metadata_address(&program_id(), &base, SPACE_X, SPACE_Y);
+
+    let now = get_unix_timestamp(&mut context.banks_client).await;
+    change_dutch_offer(&mut context, &base, &space_mint, &seller, 2 * PRICE, PRICE, now, now + DURATION).await.unwrap();
+    let space_metadata_data = get_space_metadata(&mut context.banks_client, &space_metadata).await;
+    assert_eq!(space_metadata_data.price, 2 * PRICE);
+    assert!(space_metadata_data.listing_terms);
+
+    // the price has not declined to the end price yet
+    assert!(accept_offer(&mut context, &base, &space_mint, &seller, &buyer, PRICE).await.is_err());
+
+    warp_past(&mut context, now + DURATION).await;
+    let seller_lamports = context.banks_client.get_balance(seller.pubkey()).await.unwrap();
+    let listing_lamports = context.banks_client.get_balance(listing).await.unwrap();
+    accept_offer(&mut context, &base, &space_mint, &seller, &buyer, 2 * PRICE).await.unwrap();
+
+    // buyer pays the end price, the seller gets the listing account rent back
+    let fee = PRICE * MARKETPLACE_FEE_BPS as u64 / BPS_DENOMINATOR;
+    let banks_client = &mut context.banks_client;
+    assert_eq!(
+        banks_client.get_balance(seller.pubkey()).await.unwrap(),
+        seller_lamports + PRICE - fee + listing_lamports,
+    );
+    let buyer_ata = get_associated_token_address(&buyer.pubkey(), &space_mint);
+    assert_eq!(get_token_account(banks_client, &buyer_ata).await.amount, 1);
+    assert!(banks_client.get_account(listing).await.unwrap().is_none());
+    let space_metadata_data = get_space_metadata(banks_client, &space_metadata).await;
+    assert_eq!(space_metadata_data.price, 0);
+    assert!(!space_metadata_data.listing_terms);
+}
+
+#[tokio::test]
+async fn test_change_dutch_offer_checks_schedule() {
+    let SpaceFixture { mut context, base, seller, space_mint, .. } = setup_space().await;
+    let base = base.pubkey();
+
+    let now = get_unix_timestamp(&mut context.banks_client).await;
+    assert!(change_dutch_offer(&mut context, &base, &space_mint, &seller, PRICE, 0, now, now + DURATION).await.is_err());
+    assert!(change_dutch_offer(&mut context, &base, &space_mint, &seller, PRICE, 2 * PRICE, now, now + DURATION).await.is_err());
+    assert!(change_dutch_offer(&mut context, &base, &space_mint, &seller, 2 * PRICE, PRICE, now + DURATION, now).await.is_err());
+    change_dutch_offer(&mut context, &base, &space_mint, &seller, 2 * PRICE, PRICE, now, now + DURATION).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_change_offer_replaces_dutch_offer() {
+    let SpaceFixture { mut context, base, seller, buyer, space_mint } = setup_space().await;
+    let base = base.pubkey();
+    let (space_metadata, _) = find_space_metadata_address(&program_id(), &base, SPACE_X, SPACE_Y);
+
+    let now = get_unix_timestamp(&mut context.banks_client).await;
+    change_dutch_offer(&mut context, &base, &space_mint, &seller, 2 * PRICE, PRICE, now, now + DURATION).await.unwrap();
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::change_offer(&program_id(), &base, &space_mint, &seller.pubkey(), SPACE_X, SPACE_Y, PRICE / 2, true)],
+        &[&seller],
+    ).await.unwrap();
+    assert!(!get_space_metadata(&mut context.banks_client, &space_metadata).await.listing_terms);
+
+    // fixed price listings are bought at the listed price even when the buyer offers more
+    let seller_lamports = context.banks_client.get_balance(seller.pubkey()).await.unwrap();
+    accept_offer(&mut context, &base, &space_mint, &seller, &buyer, PRICE).await.unwrap();
+    let fee = PRICE / 2 * MARKETPLACE_FEE_BPS as u64 / BPS_DENOMINATOR;
+    assert_eq!(
+        context.banks_client.get_balance(seller.pubkey()).await.unwrap(),
+        seller_lamports + PRICE / 2 - fee,
+    );
+}
@@ -0,0 +1,233 @@
+// run against the BPF build with `cargo test-bpf`
+#![cfg(feature = "test-bpf")]
+
+mod utils;
+
+use extend_space::{
+    instruction,
+    pda::{find_listing_address, find_sell_delegate_address, find_space_escrow_address, find_space_metadata_address},
+    state::{BPS_DENOMINATOR, MARKETPLACE_FEE_BPS},
+};
+use solana_program::{program_option::COption, pubkey::Pubkey};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::signature::{Keypair, Signer};
+use spl_associated_token_account::get_associated_token_address;
+use utils::*;
+
+const PRICE: u64 = 1_000_000_000;
+
+async fn change_escrow_offer(
+    context: &mut ProgramTestContext,
+    base: &Pubkey,
+    space_mint: &Pubkey,
+    seller: &Keypair,
+    price: u64,
+    create: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::change_escrow_offer(
+            &program_id(),
+            base,
+            space_mint,
+            &seller.pubkey(),
+            &seller.pubkey(),
+            SPACE_X,
+            SPACE_Y,
+            price,
+            create,
+        )],
+        &[seller],
+    ).await
+}
+
+async fn accept_escrow_offer(
+    context: &mut ProgramTestContext,
+    base: &Pubkey,
+    space_mint: &Pubkey,
+    seller: &Pubkey,
+    buyer: &Keypair,
+    price: u64,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let creator = context.payer.pubkey();
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::accept_escrow_offer(
+            &program_id(),
+            base,
+            SPACE_X,
+            SPACE_Y,
+            price,
+            &buyer.pubkey(),
+            seller,
+            space_mint,
+            &creator,
+            &creator,
+            &[],
+        )],
+        &[buyer],
+    ).await
+}
+
+#[tokio::test]
+async fn test_escrow_offer_and_accept() {
+    let SpaceFixture { mut context, base, seller, buyer, space_mint } = setup_space().await;
+    let base = base.pubkey();
+    let seller_ata = get_associated_token_address(&seller.pubkey(), &space_mint);
+    let buyer_ata = get_associated_token_address(&buyer.pubkey(), &space_mint);
+    let (space_metadata, _) = find_space_metadata_address(&program_id(), &base, SPACE_X, SPACE_Y);
+    let (escrow, _) = find_space_escrow_address(&program_id(), &base, SPACE_X, SPACE_Y);
+    let (sell_delegate, _) = find_sell_delegate_address(&program_id(), &base);
+
+    // list, moving the space into escrow
+    change_escrow_offer(&mut context, &base, &space_mint, &seller, PRICE, true).await.unwrap();
+
+    let banks_client = &mut context.banks_client;
+    let space_metadata_data = get_space_metadata(banks_client, &space_metadata).await;
+    assert_eq!(space_metadata_data.price, PRICE);
+    assert_eq!(space_metadata_data.escrow_seller, seller.pubkey());
+    assert_eq!(get_token_account(banks_client, &seller_ata).await.amount, 0);
+    let escrow_data = get_token_account(banks_client, &escrow).await;
+    assert_eq!(escrow_data.amount, 1);
+    assert_eq!(escrow_data.owner, sell_delegate);
+    let escrow_lamports = banks_client.get_balance(escrow).await.unwrap();
+
+    // buy from escrow
+    let seller_lamports = banks_client.get_balance(seller.pubkey()).await.unwrap();
+    accept_escrow_offer(&mut context, &base, &space_mint, &seller.pubkey(), &buyer, PRICE).await.unwrap();
+
+    let banks_client = &mut context.banks_client;
+    assert_eq!(get_token_account(banks_client, &buyer_ata).await.amount, 1);
+    assert!(banks_client.get_account(escrow).await.unwrap().is_none());
+    let fee = PRICE * MARKETPLACE_FEE_BPS as u64 / BPS_DENOMINATOR;
+    assert_eq!(
+        banks_client.get_balance(seller.pubkey()).await.unwrap(),
+        seller_lamports + PRICE - fee + escrow_lamports,
+    );
+    let space_metadata_data = get_space_metadata(banks_client, &space_metadata).await;
+    assert_eq!(space_metadata_data.price, 0);
+    assert_eq!(space_metadata_data.escrow_seller, Pubkey::default());
+}
+
+#[tokio::test]
+async fn test_escrow_offer_reprice_and_delist() {
+    let SpaceFixture { mut context, base, seller, buyer, space_mint } = setup_space().await;
+    let base = base.pubkey();
+    let seller_ata = get_associated_token_address(&seller.pubkey(), &space_mint);
+    let (space_metadata, _) = find_space_metadata_address(&program_id(), &base, SPACE_X, SPACE_Y);
+    let (escrow, _) = find_space_escrow_address(&program_id(), &base, SPACE_X, SPACE_Y);
+
+    change_escrow_offer(&mut context, &base, &space_mint, &seller, PRICE, true).await.unwrap();
+    change_escrow_offer(&mut context, &base, &space_mint, &seller, 2 * PRICE, true).await.unwrap();
+    let banks_client = &mut context.banks_client;
+    assert_eq!(get_space_metadata(banks_client, &space_metadata).await.price, 2 * PRICE);
+    assert_eq!(get_token_account(banks_client, &escrow).await.amount, 1);
+
+    // stale price is rejected
+    assert!(accept_escrow_offer(&mut context, &base, &space_mint, &seller.pubkey(), &buyer, PRICE).await.is_err());
+
+    change_escrow_offer(&mut context, &base, &space_mint, &seller, 0, false).await.unwrap();
+    let banks_client = &mut context.banks_client;
+    assert_eq!(get_token_account(banks_client, &seller_ata).await.amount, 1);
+    assert!(banks_client.get_account(escrow).await.unwrap().is_none());
+    let space_metadata_data = get_space_metadata(banks_client, &space_metadata).await;
+    assert_eq!(space_metadata_data.price, 0);
+    assert_eq!(space_metadata_data.escrow_seller, Pubkey::default());
+
+    assert!(accept_escrow_offer(&mut context, &base, &space_mint, &seller.pubkey(), &buyer, 0).await.is_err());
+}
+
+#[tokio::test]
+async fn test_escrow_offer_rejects_other_seller() {
+    let SpaceFixture { mut context, base, seller, buyer, space_mint } = setup_space().await;
+    let base = base.pubkey();
+
+    change_escrow_offer(&mut context, &base, &space_mint, &seller, PRICE, true).await.unwrap();
+    assert!(change_escrow_offer(&mut context, &base, &space_mint, &buyer, 0, false).await.is_err());
+    assert!(change_escrow_offer(&mut context, &base, &space_mint, &buyer, 1, true).await.is_err());
+}
+
+#[tokio::test]
+async fn test_escrow_offer_clears_direct_listing() {
+    let SpaceFixture { mut context, base, seller, space_mint, .. } = setup_space().await;
+    let base = base.pubkey();
+    let seller_ata = get_associated_token_address(&seller.pubkey(), &space_mint);
+    let (listing, _) = find_listing_address(&program_id(), &base, SPACE_X, SPACE_Y);
+
+    // a direct listing with an expiry approves the sell delegate and opens the listing account
+    let expires_at = get_unix_timestamp(&mut context.banks_client).await + 3600;
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::change_expiring_offer(
+            &program_id(),
+            &base,
+            &space_mint,
+            &seller.pubkey(),
+            SPACE_X,
+            SPACE_Y,
+            PRICE,
+            expires_at,
+        )],
+        &[&seller],
+    ).await.unwrap();
+    assert!(context.banks_client.get_account(listing).await.unwrap().is_some());
+
+    change_escrow_offer(&mut context, &base, &space_mint, &seller, PRICE, true).await.unwrap();
+
+    // the listing account is closed and the emptied ATA has no delegate left
+    let banks_client = &mut context.banks_client;
+    assert!(banks_client.get_account(listing).await.unwrap().is_none());
+    let seller_ata_data = get_token_account(banks_client, &seller_ata).await;
+    assert_eq!(seller_ata_data.amount, 0);
+    assert_eq!(seller_ata_data.delegate, COption::None);
+}
+
+#[tokio::test]
+async fn test_escrowed_space_rejects_dutch_offer() {
+    let SpaceFixture { mut context, base, seller, space_mint, .. } = setup_space().await;
+    let base = base.pubkey();
+
+    change_escrow_offer(&mut context, &base, &space_mint, &seller, PRICE, true).await.unwrap();
+
+    // an escrowed space is only listed through its escrowed offer
+    let now = get_unix_timestamp(&mut context.banks_client).await;
+    assert!(process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::change_dutch_offer(
+            &program_id(),
+            &base,
+            &space_mint,
+            &seller.pubkey(),
+            &seller.pubkey(),
+            SPACE_X,
+            SPACE_Y,
+            2 * PRICE,
+            PRICE,
+            now,
+            now + 3600,
+        )],
+        &[&seller],
+    ).await.is_err());
+}
+
+#[tokio::test]
+async fn test_prefunded_escrow_does_not_block_escrow_offer() {
+    let SpaceFixture { mut context, base, seller, space_mint, .. } = setup_space().await;
+    let base = base.pubkey();
+    let (escrow, _) = find_space_escrow_address(&program_id(), &base, SPACE_X, SPACE_Y);
+
+    // lamports sent to the escrow address ahead of the listing count towards its rent exemption
+    let payer = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[solana_program::system_instruction::transfer(&payer.pubkey(), &escrow, 1)],
+        &[&payer],
+    ).await.unwrap();
+    change_escrow_offer(&mut context, &base, &space_mint, &seller, PRICE, true).await.unwrap();
+    assert_eq!(get_token_account(&mut context.banks_client, &escrow).await.amount, 1);
+}
@@ -0,0 +1,343 @@
+// run against the BPF build with `cargo test-bpf`
+#![cfg(feature = "test-bpf")]
+
+mod utils;
+
+use extend_space::{
+    instruction,
+    pda::{find_listing_address, find_space_metadata_address},
+    state::{BPS_DENOMINATOR, MARKETPLACE_FEE_BPS, Listing},
+};
+use solana_program::{borsh::try_from_slice_unchecked, program_option::COption, pubkey::Pubkey};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::signature::{Keypair, Signer};
+use spl_associated_token_account::get_associated_token_address;
+use utils::*;
+
+const PRICE: u64 = 1_000_000_000;
+const DURATION: u64 = 3600;
+
+async fn change_expiring_offer(
+    context: &mut ProgramTestContext,
+    base: &Pubkey,
+    space_mint: &Pubkey,
+    seller: &Keypair,
+    expires_at: u64,
+) -> Result<(), Box<dyn std::error::Error>> {
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::change_expiring_offer(
+            &program_id(),
+            base,
+            space_mint,
+            &seller.pubkey(),
+            SPACE_X,
+            SPACE_Y,
+            PRICE,
+            expires_at,
+        )],
+        &[seller],
+    ).await
+}
+
+// hand the space from holder to recipient with a plain token transfer, outside the program
+async fn transfer_space(context: &mut ProgramTestContext, space_mint: &Pubkey, holder: &Keypair, recipient: &Pubkey) {
+    let payer = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[
+            spl_associated_token_account::create_associated_token_account(&payer.pubkey(), recipient, space_mint),
+            spl_token::instruction::transfer(
+                &spl_token::id(),
+                &get_associated_token_address(&holder.pubkey(), space_mint),
+                &get_associated_token_address(recipient, space_mint),
+                &holder.pubkey(),
+                &[],
+                1,
+            ).unwrap(),
+        ],
+        &[&payer, holder],
+    ).await.unwrap();
+}
+
+async fn accept_offer(
+    context: &mut ProgramTestContext,
+    base: &Pubkey,
+    space_mint: &Pubkey,
+    seller: &Keypair,
+    buyer: &Keypair,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let creator = context.payer.pubkey();
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::accept_offer(
+            &program_id(),
+            base,
+            SPACE_X,
+            SPACE_Y,
+            PRICE,
+            &buyer.pubkey(),
+            &seller.pubkey(),
+            space_mint,
+            &creator,
+            &creator,
+            &[],
+        )],
+        &[buyer],
+    ).await
+}
+
+// cleared by the context payer as a crank, leaving the sell delegate approved
+async fn clear_expired_offer(
+    context: &mut ProgramTestContext,
+    base: &Pubkey,
+    space_mint: &Pubkey,
+    seller: &Pubkey,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let payer = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::clear_expired_offer(&program_id(), base, seller, space_mint, SPACE_X, SPACE_Y, false)],
+        &[&payer],
+    ).await
+}
+
+#[tokio::test]
+async fn test_accept_offer_before_expiry() {
+    let SpaceFixture { mut context, base, seller, buyer, space_mint } = setup_space().await;
+    let base = base.pubkey();
+    let (listing, _) = find_listing_address(&program_id(), &base, SPACE_X, SPACE_Y);
+
+    let now = get_unix_timestamp(&mut context.banks_client).await;
+    assert!(change_expiring_offer(&mut context, &base, &space_mint, &seller, now).await.is_err());
+    change_expiring_offer(&mut context, &base, &space_mint, &seller, now + DURATION).await.unwrap();
+    assert!(clear_expired_offer(&mut context, &base, &space_mint, &seller.pubkey()).await.is_err());
+
+    let seller_lamports = context.banks_client.get_balance(seller.pubkey()).await.unwrap();
+    let listing_lamports = context.banks_client.get_balance(listing).await.unwrap();
+    accept_offer(&mut context, &base, &space_mint, &seller, &buyer).await.unwrap();
+
+    let fee = PRICE * MARKETPLACE_FEE_BPS as u64 / BPS_DENOMINATOR;
+    let banks_client = &mut context.banks_client;
+    assert_eq!(
+        banks_client.get_balance(seller.pubkey()).await.unwrap(),
+        seller_lamports + PRICE - fee + listing_lamports,
+    );
+    let buyer_ata = get_associated_token_address(&buyer.pubkey(), &space_mint);
+    assert_eq!(get_token_account(banks_client, &buyer_ata).await.amount, 1);
+    assert!(banks_client.get_account(listing).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_expired_offer_is_cleared() {
+    let SpaceFixture { mut context, base, seller, buyer, space_mint } = setup_space().await;
+    let base = base.pubkey();
+    let (listing, _) = find_listing_address(&program_id(), &base, SPACE_X, SPACE_Y);
+    let (space_metadata, _) = find_space_metadata_address(&program_id(), &base, SPACE_X, SPACE_Y);
+
+    let now = get_unix_timestamp(&mut context.banks_client).await;
+    change_expiring_offer(&mut context, &base, &space_mint, &seller, now + DURATION).await.unwrap();
+    warp_past(&mut context, now + DURATION).await;
+    assert!(accept_offer(&mut context, &base, &space_mint, &seller, &buyer).await.is_err());
+
+    // anyone can clear the listing, the listing account rent goes back to the seller and only the seller
+    // can revoke the sell delegate
+    assert!(clear_expired_offer(&mut context, &base, &space_mint, &buyer.pubkey()).await.is_err());
+    let seller_lamports = context.banks_client.get_balance(seller.pubkey()).await.unwrap();
+    let listing_lamports = context.banks_client.get_balance(listing).await.unwrap();
+    clear_expired_offer(&mut context, &base, &space_mint, &seller.pubkey()).await.unwrap();
+
+    let banks_client = &mut context.banks_client;
+    assert_eq!(banks_client.get_balance(seller.pubkey()).await.unwrap(), seller_lamports + listing_lamports);
+    assert!(banks_client.get_account(listing).await.unwrap().is_none());
+    let space_metadata_data = get_space_metadata(banks_client, &space_metadata).await;
+    assert_eq!(space_metadata_data.price, 0);
+    assert!(!space_metadata_data.listing_terms);
+    let seller_ata = get_associated_token_address(&seller.pubkey(), &space_mint);
+    let seller_ata_data = get_token_account(banks_client, &seller_ata).await;
+    assert_eq!(seller_ata_data.amount, 1);
+    assert!(seller_ata_data.delegate.is_some());
+}
+
+#[tokio::test]
+async fn test_seller_clears_expired_offer_and_revokes_delegate() {
+    let SpaceFixture { mut context, base, seller, space_mint, .. } = setup_space().await;
+    let base = base.pubkey();
+    let (listing, _) = find_listing_address(&program_id(), &base, SPACE_X, SPACE_Y);
+
+    let now = get_unix_timestamp(&mut context.banks_client).await;
+    change_expiring_offer(&mut context, &base, &space_mint, &seller, now + DURATION).await.unwrap();
+    warp_past(&mut context, now + DURATION).await;
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::clear_expired_offer(&program_id(), &base, &seller.pubkey(), &space_mint, SPACE_X, SPACE_Y, true)],
+        &[&seller],
+    ).await.unwrap();
+
+    let banks_client = &mut context.banks_client;
+    assert!(banks_client.get_account(listing).await.unwrap().is_none());
+    let seller_ata = get_associated_token_address(&seller.pubkey(), &space_mint);
+    assert_eq!(get_token_account(banks_client, &seller_ata).await.delegate, COption::None);
+}
+
+#[tokio::test]
+async fn test_delist_closes_listing_account() {
+    let SpaceFixture { mut context, base, seller, space_mint, .. } = setup_space().await;
+    let base = base.pubkey();
+    let (listing, _) = find_listing_address(&program_id(), &base, SPACE_X, SPACE_Y);
+
+    let now = get_unix_timestamp(&mut context.banks_client).await;
+    change_expiring_offer(&mut context, &base, &space_mint, &seller, now + DURATION).await.unwrap();
+    // the context payer pays for the transaction, so the seller only gets the listing account rent back
+    let payer = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+    let seller_lamports = context.banks_client.get_balance(seller.pubkey()).await.unwrap();
+    let listing_lamports = context.banks_client.get_balance(listing).await.unwrap();
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::change_offer(&program_id(), &base, &space_mint, &seller.pubkey(), SPACE_X, SPACE_Y, 0, false)],
+        &[&payer, &seller],
+    ).await.unwrap();
+
+    let banks_client = &mut context.banks_client;
+    assert!(banks_client.get_account(listing).await.unwrap().is_none());
+    assert_eq!(banks_client.get_balance(seller.pubkey()).await.unwrap(), seller_lamports + listing_lamports);
+}
+
+#[tokio::test]
+async fn test_change_offer_without_expiry_replaces_expiring_offer() {
+    let SpaceFixture { mut context, base, seller, buyer, space_mint } = setup_space().await;
+    let base = base.pubkey();
+
+    let now = get_unix_timestamp(&mut context.banks_client).await;
+    change_expiring_offer(&mut context, &base, &space_mint, &seller, now + DURATION).await.unwrap();
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::change_offer(&program_id(), &base, &space_mint, &seller.pubkey(), SPACE_X, SPACE_Y, PRICE, true)],
+        &[&seller],
+    ).await.unwrap();
+    let (listing, _) = find_listing_address(&program_id(), &base, SPACE_X, SPACE_Y);
+    assert!(context.banks_client.get_account(listing).await.unwrap().is_none());
+
+    warp_past(&mut context, now + DURATION).await;
+    assert!(clear_expired_offer(&mut context, &base, &space_mint, &seller.pubkey()).await.is_err());
+    accept_offer(&mut context, &base, &space_mint, &seller, &buyer).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_listing_rent_goes_back_to_its_seller() {
+    let SpaceFixture { mut context, base, seller, buyer, space_mint } = setup_space().await;
+    let base = base.pubkey();
+    let (listing, _) = find_listing_address(&program_id(), &base, SPACE_X, SPACE_Y);
+
+    // the seller lists with an expiry, then hands the space to the buyer outside the program
+    let now = get_unix_timestamp(&mut context.banks_client).await;
+    change_expiring_offer(&mut context, &base, &space_mint, &seller, now + DURATION).await.unwrap();
+    let payer = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+    transfer_space(&mut context, &space_mint, &seller, &buyer.pubkey()).await;
+
+    // the new holder can not take the listing account rent when delisting
+    assert!(process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::change_offer(&program_id(), &base, &space_mint, &buyer.pubkey(), SPACE_X, SPACE_Y, 0, false)],
+        &[&payer, &buyer],
+    ).await.is_err());
+
+    let seller_lamports = context.banks_client.get_balance(seller.pubkey()).await.unwrap();
+    let listing_lamports = context.banks_client.get_balance(listing).await.unwrap();
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::change_token_offer(
+            &program_id(),
+            &base,
+            &space_mint,
+            &buyer.pubkey(),
+            &seller.pubkey(),
+            &Pubkey::default(),
+            SPACE_X,
+            SPACE_Y,
+            0,
+            false,
+            0,
+        )],
+        &[&payer, &buyer],
+    ).await.unwrap();
+
+    let banks_client = &mut context.banks_client;
+    assert!(banks_client.get_account(listing).await.unwrap().is_none());
+    assert_eq!(banks_client.get_balance(seller.pubkey()).await.unwrap(), seller_lamports + listing_lamports);
+}
+
+#[tokio::test]
+async fn test_relisting_pays_back_the_previous_seller() {
+    let SpaceFixture { mut context, base, seller, buyer, space_mint } = setup_space().await;
+    let base = base.pubkey();
+    let (listing, _) = find_listing_address(&program_id(), &base, SPACE_X, SPACE_Y);
+
+    let now = get_unix_timestamp(&mut context.banks_client).await;
+    change_expiring_offer(&mut context, &base, &space_mint, &seller, now + DURATION).await.unwrap();
+    transfer_space(&mut context, &space_mint, &seller, &buyer.pubkey()).await;
+
+    // the new holder's listing takes over the listing account, paying its rent back to the previous seller
+    let payer = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+    let relist = |listing_seller: &Pubkey| instruction::change_token_offer(
+        &program_id(),
+        &base,
+        &space_mint,
+        &buyer.pubkey(),
+        listing_seller,
+        &Pubkey::default(),
+        SPACE_X,
+        SPACE_Y,
+        PRICE,
+        true,
+        now + DURATION,
+    );
+    assert!(process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[relist(&buyer.pubkey())],
+        &[&payer, &buyer],
+    ).await.is_err());
+    let seller_lamports = context.banks_client.get_balance(seller.pubkey()).await.unwrap();
+    let listing_lamports = context.banks_client.get_balance(listing).await.unwrap();
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[relist(&seller.pubkey())],
+        &[&payer, &buyer],
+    ).await.unwrap();
+
+    let banks_client = &mut context.banks_client;
+    assert_eq!(banks_client.get_balance(seller.pubkey()).await.unwrap(), seller_lamports + listing_lamports);
+    assert_eq!(banks_client.get_balance(listing).await.unwrap(), listing_lamports);
+    let listing_data: Listing = try_from_slice_unchecked(&get_account(banks_client, &listing).await.data).unwrap();
+    assert_eq!(listing_data.seller, buyer.pubkey());
+}
+
+#[tokio::test]
+async fn test_prefunded_listing_account_does_not_block_listing() {
+    let SpaceFixture { mut context, base, seller, space_mint, .. } = setup_space().await;
+    let base = base.pubkey();
+    let (listing, _) = find_listing_address(&program_id(), &base, SPACE_X, SPACE_Y);
+
+    // lamports sent to the listing address ahead of the listing count towards its rent exemption
+    let payer = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[solana_program::system_instruction::transfer(&payer.pubkey(), &listing, 1)],
+        &[&payer],
+    ).await.unwrap();
+    let now = get_unix_timestamp(&mut context.banks_client).await;
+    change_expiring_offer(&mut context, &base, &space_mint, &seller, now + DURATION).await.unwrap();
+    assert!(context.banks_client.get_account(listing).await.unwrap().is_some());
+}
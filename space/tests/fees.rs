@@ -0,0 +1,174 @@
+// run against the BPF build with `cargo test-bpf`
+#![cfg(feature = "test-bpf")]
+
+mod utils;
+
+use extend_space::{
+    instruction,
+    pda::find_neighborhood_metadata_address,
+    state::{BPS_DENOMINATOR, MARKETPLACE_FEE_BPS, MAX_MARKETPLACE_FEE_BPS},
+};
+use solana_program::pubkey::Pubkey;
+use solana_program_test::ProgramTestContext;
+use solana_sdk::signature::{Keypair, Signer};
+use utils::*;
+
+const PRICE: u64 = 1_000_000_000;
+
+async fn set_marketplace_fee(
+    context: &mut ProgramTestContext,
+    base: &Pubkey,
+    creator: &Keypair,
+    fee_bps: u16,
+) -> Result<(), Box<dyn std::error::Error>> {
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::set_marketplace_fee(
+            &program_id(),
+            base,
+            &creator.pubkey(),
+            NEIGHBORHOOD_X,
+            NEIGHBORHOOD_Y,
+            fee_bps,
+        )],
+        &[creator],
+    ).await
+}
+
+async fn set_marketplace_fee_cap(
+    context: &mut ProgramTestContext,
+    base: &Pubkey,
+    authority: &Keypair,
+    max_fee_bps: u16,
+) -> Result<(), Box<dyn std::error::Error>> {
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::set_marketplace_fee_cap(&program_id(), base, &authority.pubkey(), max_fee_bps)],
+        &[authority],
+    ).await
+}
+
+// list and sell the space at PRICE, returning the fee received by the creator
+async fn sell_space(
+    context: &mut ProgramTestContext,
+    base: &Pubkey,
+    seller: &Keypair,
+    buyer: &Keypair,
+    space_mint: &Pubkey,
+) -> u64 {
+    let creator = context.payer.pubkey();
+    let creator_lamports = context.banks_client.get_balance(creator).await.unwrap();
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[
+            instruction::change_offer(&program_id(), base, space_mint, &seller.pubkey(), SPACE_X, SPACE_Y, PRICE, true),
+            instruction::accept_offer(
+                &program_id(),
+                base,
+                SPACE_X,
+                SPACE_Y,
+                PRICE,
+                &buyer.pubkey(),
+                &seller.pubkey(),
+                space_mint,
+                &creator,
+                &creator,
+                &[],
+            ),
+        ],
+        &[buyer, seller],
+    ).await.unwrap();
+    context.banks_client.get_balance(creator).await.unwrap() - creator_lamports
+}
+
+#[tokio::test]
+async fn test_set_marketplace_fee() {
+    let SpaceFixture { mut context, base, seller, buyer, space_mint } = setup_space().await;
+    let base = base.pubkey();
+    let creator = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+
+    set_marketplace_fee(&mut context, &base, &creator, 250).await.unwrap();
+    let (neighborhood_metadata, _) =
+        find_neighborhood_metadata_address(&program_id(), &base, NEIGHBORHOOD_X, NEIGHBORHOOD_Y);
+    assert_eq!(get_neighborhood_metadata(&mut context.banks_client, &neighborhood_metadata).await.marketplace_fee_bps, 250);
+
+    let fee = sell_space(&mut context, &base, &seller, &buyer, &space_mint).await;
+    assert_eq!(fee, PRICE * 250 / BPS_DENOMINATOR);
+}
+
+#[tokio::test]
+async fn test_set_marketplace_fee_checks_creator_and_cap() {
+    let SpaceFixture { mut context, base, seller, .. } = setup_space().await;
+    let base = base.pubkey();
+    let creator = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+
+    assert!(set_marketplace_fee(&mut context, &base, &seller, 200).await.is_err());
+    assert!(set_marketplace_fee(&mut context, &base, &creator, 1_001).await.is_err());
+
+    set_marketplace_fee_cap(&mut context, &base, &creator, 2_000).await.unwrap();
+    assert_eq!(get_base(&mut context.banks_client, &base).await.max_marketplace_fee_bps, 2_000);
+    set_marketplace_fee(&mut context, &base, &creator, 1_500).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_marketplace_fee_cap() {
+    let SpaceFixture { mut context, base, seller, buyer, space_mint } = setup_space().await;
+    let base = base.pubkey();
+    let authority = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+
+    assert!(set_marketplace_fee_cap(&mut context, &base, &seller, 0).await.is_err());
+    assert!(set_marketplace_fee_cap(&mut context, &base, &authority, 10_001).await.is_err());
+
+    // lowering the cap limits fees already set by creators
+    set_marketplace_fee(&mut context, &base, &authority, 500).await.unwrap();
+    set_marketplace_fee_cap(&mut context, &base, &authority, 200).await.unwrap();
+    let fee = sell_space(&mut context, &base, &seller, &buyer, &space_mint).await;
+    assert_eq!(fee, PRICE * 200 / BPS_DENOMINATOR);
+}
+
+#[tokio::test]
+async fn test_zero_marketplace_fee_and_cap() {
+    let SpaceFixture { mut context, base, seller, buyer, space_mint } = setup_space().await;
+    let base = base.pubkey();
+    let authority = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+
+    // a 0 fee is charged as 0, not as the default
+    set_marketplace_fee(&mut context, &base, &authority, 0).await.unwrap();
+    let fee = sell_space(&mut context, &base, &seller, &buyer, &space_mint).await;
+    assert_eq!(fee, 0);
+
+    // a 0 cap holds every neighborhood at 0
+    set_marketplace_fee(&mut context, &base, &authority, 500).await.unwrap();
+    set_marketplace_fee_cap(&mut context, &base, &authority, 0).await.unwrap();
+    assert_eq!(get_base(&mut context.banks_client, &base).await.get_max_marketplace_fee_bps(), 0);
+    assert!(set_marketplace_fee(&mut context, &base, &authority, 1).await.is_err());
+    let fee = sell_space(&mut context, &base, &buyer, &seller, &space_mint).await;
+    assert_eq!(fee, 0);
+}
+
+#[tokio::test]
+async fn test_legacy_accounts_use_default_fee_and_cap() {
+    let SpaceFixture { mut context, base, seller, buyer, space_mint } = setup_legacy_space().await;
+    let base = base.pubkey();
+    let authority = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+
+    let base_data = get_base(&mut context.banks_client, &base).await;
+    assert_eq!(base_data.max_marketplace_fee_bps, 0);
+    assert_eq!(base_data.get_max_marketplace_fee_bps(), MAX_MARKETPLACE_FEE_BPS);
+    let (neighborhood_metadata, _) =
+        find_neighborhood_metadata_address(&program_id(), &base, NEIGHBORHOOD_X, NEIGHBORHOOD_Y);
+    let neighborhood_metadata_data = get_neighborhood_metadata(&mut context.banks_client, &neighborhood_metadata).await;
+    assert_eq!(neighborhood_metadata_data.marketplace_fee_bps, 0);
+    assert_eq!(neighborhood_metadata_data.get_marketplace_fee_bps(), MARKETPLACE_FEE_BPS);
+
+    // sales pay the default fee, to the creator who is also the authority standing in for the treasury
+    let fee = sell_space(&mut context, &base, &seller, &buyer, &space_mint).await;
+    assert_eq!(fee, PRICE * MARKETPLACE_FEE_BPS as u64 / BPS_DENOMINATOR);
+
+    // and the default cap still applies until the authority sets one
+    assert!(set_marketplace_fee(&mut context, &base, &authority, MAX_MARKETPLACE_FEE_BPS + 1).await.is_err());
+    set_marketplace_fee(&mut context, &base, &authority, MAX_MARKETPLACE_FEE_BPS).await.unwrap();
+}
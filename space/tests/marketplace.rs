@@ -0,0 +1,257 @@
+// run against the BPF build with `cargo test-bpf`
+#![cfg(feature = "test-bpf")]
+
+mod utils;
+
+use extend_space::{
+    instruction,
+    pda::{find_neighborhood_list_address, find_neighborhood_metadata_address, find_sell_delegate_address, find_space_metadata_address},
+    state::{BPS_DENOMINATOR, MARKETPLACE_FEE_BPS, MAX_MARKETPLACE_FEE_BPS, NeighborhoodList},
+};
+use solana_program::{borsh::try_from_slice_unchecked, pubkey::Pubkey};
+use solana_sdk::signature::{Keypair, Signer};
+use spl_associated_token_account::get_associated_token_address;
+use utils::*;
+
+const PRICE: u64 = 1_000_000_000;
+
+#[tokio::test]
+async fn test_init_base_neighborhood_and_space() {
+    let SpaceFixture { mut context, base, seller, space_mint, .. } = setup_space().await;
+    let banks_client = &mut context.banks_client;
+
+    let base_data = get_base(banks_client, &base.pubkey()).await;
+    assert_eq!(base_data.neighborhood_count, 1);
+    assert_eq!(base_data.authority, context.payer.pubkey());
+    assert!(base_data.authority_privileges);
+    assert_eq!(base_data.max_marketplace_fee_bps, MAX_MARKETPLACE_FEE_BPS);
+
+    let (neighborhood_list, _) = find_neighborhood_list_address(&program_id(), &base.pubkey());
+    let neighborhood_list_data: NeighborhoodList =
+        try_from_slice_unchecked(&get_account(banks_client, &neighborhood_list).await.data).unwrap();
+    assert_eq!(neighborhood_list_data.neighborhoods_x, vec![NEIGHBORHOOD_X]);
+    assert_eq!(neighborhood_list_data.neighborhoods_y, vec![NEIGHBORHOOD_Y]);
+
+    let (neighborhood_metadata, _) =
+        find_neighborhood_metadata_address(&program_id(), &base.pubkey(), NEIGHBORHOOD_X, NEIGHBORHOOD_Y);
+    let neighborhood_metadata_data = get_neighborhood_metadata(banks_client, &neighborhood_metadata).await;
+    assert_eq!(neighborhood_metadata_data.creator, context.payer.pubkey());
+    assert_eq!(&neighborhood_metadata_data.neighborhood_name[..7], b"Genesis");
+    assert_eq!(neighborhood_metadata_data.marketplace_fee_bps, MARKETPLACE_FEE_BPS);
+
+    let (space_metadata, _) = find_space_metadata_address(&program_id(), &base.pubkey(), SPACE_X, SPACE_Y);
+    let space_metadata_data = get_space_metadata(banks_client, &space_metadata).await;
+    assert_eq!(space_metadata_data.mint, space_mint);
+    assert_eq!(space_metadata_data.space_x, SPACE_X);
+    assert_eq!(space_metadata_data.space_y, SPACE_Y);
+    assert_eq!(space_metadata_data.price, 0);
+
+    let seller_ata = get_associated_token_address(&seller.pubkey(), &space_mint);
+    assert_eq!(get_token_account(banks_client, &seller_ata).await.amount, 1);
+}
+
+#[tokio::test]
+async fn test_init_space_metadata_rejects_wrong_coordinates() {
+    let mut program_test = program_test();
+    let seller = add_wallet(&mut program_test);
+    let candymachine_account = Pubkey::new_unique();
+    let candymachine_config = add_candymachine_config(&mut program_test, &Pubkey::default(), SPACE_X, SPACE_Y);
+    let space_mint = add_space_nft(&mut program_test, &seller.pubkey(), &candymachine_account, SPACE_X, SPACE_Y);
+    let mut context = program_test.start_with_context().await;
+    let base = Keypair::new();
+
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[
+            instruction::init_base(&program_id(), &base.pubkey(), &context.payer.pubkey()),
+            instruction::init_neighborhood_metadata(
+                &program_id(),
+                &base.pubkey(),
+                &candymachine_config,
+                &candymachine_account,
+                &context.payer.pubkey(),
+                &Pubkey::new_unique(),
+                NEIGHBORHOOD_X,
+                NEIGHBORHOOD_Y,
+                0,
+                [0; 64],
+            ),
+        ],
+        &[&context.payer, &base],
+    ).await.unwrap();
+
+    let result = process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::init_space_metadata(
+            &program_id(),
+            &base.pubkey(),
+            &space_mint,
+            &seller.pubkey(),
+            SPACE_X + 1,
+            SPACE_Y,
+        )],
+        &[&seller],
+    ).await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_change_offer_and_accept_offer() {
+    let SpaceFixture { mut context, base, seller, buyer, space_mint } = setup_space().await;
+    let creator = context.payer.pubkey();
+    let seller_ata = get_associated_token_address(&seller.pubkey(), &space_mint);
+    let buyer_ata = get_associated_token_address(&buyer.pubkey(), &space_mint);
+    let (space_metadata, _) = find_space_metadata_address(&program_id(), &base.pubkey(), SPACE_X, SPACE_Y);
+    let (sell_delegate, _) = find_sell_delegate_address(&program_id(), &base.pubkey());
+
+    // list
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::change_offer(
+            &program_id(),
+            &base.pubkey(),
+            &space_mint,
+            &seller.pubkey(),
+            SPACE_X,
+            SPACE_Y,
+            PRICE,
+            true,
+        )],
+        &[&seller],
+    ).await.unwrap();
+
+    let banks_client = &mut context.banks_client;
+    assert_eq!(get_space_metadata(banks_client, &space_metadata).await.price, PRICE);
+    let seller_ata_data = get_token_account(banks_client, &seller_ata).await;
+    assert_eq!(seller_ata_data.delegate, Some(sell_delegate).into());
+    assert_eq!(seller_ata_data.delegated_amount, 1);
+
+    // buy, with the buyer paying the transaction fee so seller and creator balances are exact
+    let seller_lamports = banks_client.get_balance(seller.pubkey()).await.unwrap();
+    let creator_lamports = banks_client.get_balance(creator).await.unwrap();
+    process_instructions(
+        banks_client,
+        context.last_blockhash,
+        &[instruction::accept_offer(
+            &program_id(),
+            &base.pubkey(),
+            SPACE_X,
+            SPACE_Y,
+            PRICE,
+            &buyer.pubkey(),
+            &seller.pubkey(),
+            &space_mint,
+            &creator,
+            &creator,
+            &[],
+        )],
+        &[&buyer],
+    ).await.unwrap();
+
+    let marketplace_fee = PRICE * MARKETPLACE_FEE_BPS as u64 / BPS_DENOMINATOR;
+    assert_eq!(marketplace_fee, 10_000_000);
+    assert_eq!(
+        banks_client.get_balance(seller.pubkey()).await.unwrap(),
+        seller_lamports + PRICE - marketplace_fee,
+    );
+    assert_eq!(
+        banks_client.get_balance(creator).await.unwrap(),
+        creator_lamports + marketplace_fee,
+    );
+
+    assert_eq!(get_token_account(banks_client, &seller_ata).await.amount, 0);
+    assert_eq!(get_token_account(banks_client, &buyer_ata).await.amount, 1);
+    assert_eq!(get_space_metadata(banks_client, &space_metadata).await.price, 0);
+}
+
+#[tokio::test]
+async fn test_accept_offer_rejects_changed_price() {
+    let SpaceFixture { mut context, base, seller, buyer, space_mint } = setup_space().await;
+    let creator = context.payer.pubkey();
+
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::change_offer(
+            &program_id(),
+            &base.pubkey(),
+            &space_mint,
+            &seller.pubkey(),
+            SPACE_X,
+            SPACE_Y,
+            PRICE,
+            true,
+        )],
+        &[&seller],
+    ).await.unwrap();
+
+    let result = process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::accept_offer(
+            &program_id(),
+            &base.pubkey(),
+            SPACE_X,
+            SPACE_Y,
+            PRICE / 2,
+            &buyer.pubkey(),
+            &seller.pubkey(),
+            &space_mint,
+            &creator,
+            &creator,
+            &[],
+        )],
+        &[&buyer],
+    ).await;
+    assert!(result.is_err());
+
+    let seller_ata = get_associated_token_address(&seller.pubkey(), &space_mint);
+    assert_eq!(get_token_account(&mut context.banks_client, &seller_ata).await.amount, 1);
+}
+
+#[tokio::test]
+async fn test_accept_offer_rejects_delisted_space() {
+    let SpaceFixture { mut context, base, seller, buyer, space_mint } = setup_space().await;
+    let creator = context.payer.pubkey();
+
+    for &(price, create) in &[(PRICE, true), (0, false)] {
+        process_instructions(
+            &mut context.banks_client,
+            context.last_blockhash,
+            &[instruction::change_offer(
+                &program_id(),
+                &base.pubkey(),
+                &space_mint,
+                &seller.pubkey(),
+                SPACE_X,
+                SPACE_Y,
+                price,
+                create,
+            )],
+            &[&seller],
+        ).await.unwrap();
+    }
+
+    let result = process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::accept_offer(
+            &program_id(),
+            &base.pubkey(),
+            SPACE_X,
+            SPACE_Y,
+            0,
+            &buyer.pubkey(),
+            &seller.pubkey(),
+            &space_mint,
+            &creator,
+            &creator,
+            &[],
+        )],
+        &[&buyer],
+    ).await;
+    assert!(result.is_err());
+}
@@ -0,0 +1,193 @@
+// run against the BPF build with `cargo test-bpf`
+#![cfg(feature = "test-bpf")]
+
+mod utils;
+
+use extend_space::{
+    instruction::{self, SpaceOffer},
+    pda::{find_listing_address, find_sell_delegate_address, find_space_metadata_address},
+};
+use solana_program::{program_option::COption, pubkey::Pubkey};
+use solana_sdk::signature::{Keypair, Signer};
+use spl_associated_token_account::get_associated_token_address;
+use utils::*;
+
+const PRICE: u64 = 1_000_000_000;
+const EXTRA_SPACES: [(i64, i64); 2] = [(SPACE_X + 1, SPACE_Y), (SPACE_X, SPACE_Y + 1)];
+
+fn change_offer_batch(
+    base: &Pubkey,
+    owner: &Keypair,
+    space_mints: &[Pubkey],
+    spaces: &[(i64, i64)],
+) -> solana_program::instruction::Instruction {
+    let offers = spaces
+        .iter()
+        .enumerate()
+        .map(|(k, &(space_x, space_y))| SpaceOffer {
+            space_x,
+            space_y,
+            price: PRICE * (k as u64 + 1),
+        })
+        .collect();
+    let listing_sellers = vec![owner.pubkey(); space_mints.len()];
+    instruction::change_offer_batch(&program_id(), base, &owner.pubkey(), space_mints, &listing_sellers, offers)
+}
+
+#[test]
+#[should_panic(expected = "one space mint per offer")]
+fn test_change_offer_batch_rejects_missing_space_mint() {
+    let owner = Keypair::new();
+    change_offer_batch(&Pubkey::new_unique(), &owner, &[Pubkey::new_unique()], &[(SPACE_X, SPACE_Y), EXTRA_SPACES[0]]);
+}
+
+#[tokio::test]
+async fn test_change_offer_batch_lists_spaces() {
+    let (SpaceFixture { mut context, base, seller, buyer, space_mint }, extra_space_mints) =
+        setup_spaces(&EXTRA_SPACES).await;
+    let base = base.pubkey();
+    let space_mints = [space_mint, extra_space_mints[0], extra_space_mints[1]];
+    let spaces = [(SPACE_X, SPACE_Y), EXTRA_SPACES[0], EXTRA_SPACES[1]];
+
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[change_offer_batch(&base, &seller, &space_mints, &spaces)],
+        &[&seller],
+    ).await.unwrap();
+
+    let (sell_delegate, _) = find_sell_delegate_address(&program_id(), &base);
+    for (k, (space_mint, &(space_x, space_y))) in space_mints.iter().zip(spaces.iter()).enumerate() {
+        let (space_metadata, _) = find_space_metadata_address(&program_id(), &base, space_x, space_y);
+        assert_eq!(get_space_metadata(&mut context.banks_client, &space_metadata).await.price, PRICE * (k as u64 + 1));
+        let seller_ata = get_associated_token_address(&seller.pubkey(), space_mint);
+        assert_eq!(get_token_account(&mut context.banks_client, &seller_ata).await.delegate, COption::Some(sell_delegate));
+    }
+
+    // a batch listing is accepted like any other listing
+    let creator = context.payer.pubkey();
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::accept_offer(
+            &program_id(),
+            &base,
+            EXTRA_SPACES[1].0,
+            EXTRA_SPACES[1].1,
+            3 * PRICE,
+            &buyer.pubkey(),
+            &seller.pubkey(),
+            &space_mints[2],
+            &creator,
+            &creator,
+            &[],
+        )],
+        &[&buyer],
+    ).await.unwrap();
+    let buyer_ata = get_associated_token_address(&buyer.pubkey(), &space_mints[2]);
+    assert_eq!(get_token_account(&mut context.banks_client, &buyer_ata).await.amount, 1);
+}
+
+#[tokio::test]
+async fn test_change_offer_batch_is_atomic() {
+    let (SpaceFixture { mut context, base, seller, buyer, space_mint }, extra_space_mints) =
+        setup_spaces(&EXTRA_SPACES[..1]).await;
+    let base = base.pubkey();
+    let space_mints = [space_mint, extra_space_mints[0]];
+    let spaces = [(SPACE_X, SPACE_Y), EXTRA_SPACES[0]];
+
+    // the buyer owns neither space
+    assert!(process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[change_offer_batch(&base, &buyer, &space_mints, &spaces)],
+        &[&buyer],
+    ).await.is_err());
+
+    // the seller no longer holds the second space, so the first is not listed either
+    let creator = context.payer.pubkey();
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[
+            instruction::change_offer(&program_id(), &base, &space_mints[1], &seller.pubkey(), EXTRA_SPACES[0].0, EXTRA_SPACES[0].1, PRICE, true),
+            instruction::accept_offer(
+                &program_id(),
+                &base,
+                EXTRA_SPACES[0].0,
+                EXTRA_SPACES[0].1,
+                PRICE,
+                &buyer.pubkey(),
+                &seller.pubkey(),
+                &space_mints[1],
+                &creator,
+                &creator,
+                &[],
+            ),
+        ],
+        &[&seller, &buyer],
+    ).await.unwrap();
+    assert!(process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[change_offer_batch(&base, &seller, &space_mints, &spaces)],
+        &[&seller],
+    ).await.is_err());
+
+    let (space_metadata, _) = find_space_metadata_address(&program_id(), &base, SPACE_X, SPACE_Y);
+    assert_eq!(get_space_metadata(&mut context.banks_client, &space_metadata).await.price, 0);
+    let seller_ata = get_associated_token_address(&seller.pubkey(), &space_mint);
+    assert_eq!(get_token_account(&mut context.banks_client, &seller_ata).await.delegate, COption::None);
+}
+
+#[tokio::test]
+async fn test_change_offer_batch_replaces_listing_terms() {
+    let (SpaceFixture { mut context, base, seller, space_mint, .. }, extra_space_mints) =
+        setup_spaces(&EXTRA_SPACES[..1]).await;
+    let base = base.pubkey();
+    let space_mints = [space_mint, extra_space_mints[0]];
+    let spaces = [(SPACE_X, SPACE_Y), EXTRA_SPACES[0]];
+    let (listing, _) = find_listing_address(&program_id(), &base, SPACE_X, SPACE_Y);
+
+    let now = get_unix_timestamp(&mut context.banks_client).await;
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::change_expiring_offer(&program_id(), &base, &space_mint, &seller.pubkey(), SPACE_X, SPACE_Y, PRICE, now + 3600)],
+        &[&seller],
+    ).await.unwrap();
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[change_offer_batch(&base, &seller, &space_mints, &spaces)],
+        &[&seller],
+    ).await.unwrap();
+
+    let (space_metadata, _) = find_space_metadata_address(&program_id(), &base, SPACE_X, SPACE_Y);
+    assert!(!get_space_metadata(&mut context.banks_client, &space_metadata).await.listing_terms);
+    assert!(context.banks_client.get_account(listing).await.unwrap().is_none());
+
+    // an escrowed space is only listed through its escrowed offer
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::change_escrow_offer(
+            &program_id(),
+            &base,
+            &space_mints[1],
+            &seller.pubkey(),
+            &seller.pubkey(),
+            EXTRA_SPACES[0].0,
+            EXTRA_SPACES[0].1,
+            PRICE,
+            true,
+        )],
+        &[&seller],
+    ).await.unwrap();
+    assert!(process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[change_offer_batch(&base, &seller, &space_mints[1..], &spaces[1..])],
+        &[&seller],
+    ).await.is_err());
+}
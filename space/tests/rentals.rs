@@ -0,0 +1,115 @@
+// run against the BPF build with `cargo test-bpf`
+#![cfg(feature = "test-bpf")]
+
+mod utils;
+
+use extend_space::instruction;
+use solana_program::pubkey::Pubkey;
+use solana_program_test::ProgramTestContext;
+use solana_sdk::signature::{Keypair, Signer};
+use spl_associated_token_account::get_associated_token_address;
+use utils::*;
+
+const PRICE: u64 = 1_000_000_000;
+
+async fn change_offer(
+    context: &mut ProgramTestContext,
+    base: &Pubkey,
+    space_mint: &Pubkey,
+    seller: &Keypair,
+) -> Result<(), Box<dyn std::error::Error>> {
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::change_offer(&program_id(), base, space_mint, &seller.pubkey(), SPACE_X, SPACE_Y, PRICE, true)],
+        &[seller],
+    ).await
+}
+
+#[tokio::test]
+async fn test_rented_space_cannot_be_listed_or_sold() {
+    let SpaceFixture { mut context, base, seller, buyer, space_mint } = setup_rented_space(u64::MAX).await;
+    let base = base.pubkey();
+
+    assert!(change_offer(&mut context, &base, &space_mint, &seller).await.is_err());
+
+    let now = get_unix_timestamp(&mut context.banks_client).await;
+    assert!(process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::create_auction(
+            &program_id(),
+            &base,
+            &space_mint,
+            &seller.pubkey(),
+            &seller.pubkey(),
+            SPACE_X,
+            SPACE_Y,
+            PRICE,
+            now + 3600,
+            PRICE / 10,
+        )],
+        &[&seller],
+    ).await.is_err());
+
+    // bids can still be placed, but not accepted until the rental is over
+    let creator = context.payer.pubkey();
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::place_bid(&program_id(), &base, &buyer.pubkey(), SPACE_X, SPACE_Y, PRICE)],
+        &[&buyer],
+    ).await.unwrap();
+    assert!(process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::accept_bid(
+            &program_id(),
+            &base,
+            SPACE_X,
+            SPACE_Y,
+            PRICE,
+            &seller.pubkey(),
+            &buyer.pubkey(),
+            &seller.pubkey(),
+            &space_mint,
+            &creator,
+            &creator,
+            &[],
+        )],
+        &[&seller],
+    ).await.is_err());
+
+    let seller_ata = get_associated_token_address(&seller.pubkey(), &space_mint);
+    assert_eq!(get_token_account(&mut context.banks_client, &seller_ata).await.amount, 1);
+}
+
+#[tokio::test]
+async fn test_space_can_be_sold_after_rental_ends() {
+    let SpaceFixture { mut context, base, seller, buyer, space_mint } = setup_rented_space(1).await;
+    let base = base.pubkey();
+
+    change_offer(&mut context, &base, &space_mint, &seller).await.unwrap();
+    let creator = context.payer.pubkey();
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::accept_offer(
+            &program_id(),
+            &base,
+            SPACE_X,
+            SPACE_Y,
+            PRICE,
+            &buyer.pubkey(),
+            &seller.pubkey(),
+            &space_mint,
+            &creator,
+            &creator,
+            &[],
+        )],
+        &[&buyer],
+    ).await.unwrap();
+
+    let buyer_ata = get_associated_token_address(&buyer.pubkey(), &space_mint);
+    assert_eq!(get_token_account(&mut context.banks_client, &buyer_ata).await.amount, 1);
+}
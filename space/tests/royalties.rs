@@ -0,0 +1,155 @@
+// run against the BPF build with `cargo test-bpf`
+#![cfg(feature = "test-bpf")]
+
+mod utils;
+
+use extend_space::{
+    instruction,
+    state::{BPS_DENOMINATOR, MARKETPLACE_FEE_BPS},
+};
+use metaplex_token_metadata::state::Creator;
+use solana_program::pubkey::Pubkey;
+use solana_program_test::ProgramTestContext;
+use solana_sdk::signature::{Keypair, Signer};
+use spl_associated_token_account::get_associated_token_address;
+use utils::*;
+
+const PRICE: u64 = 1_000_000_000;
+const SELLER_FEE_BASIS_POINTS: u16 = 500;
+
+// one verified creator taking 60% of the royalties, and an unverified one that is never paid
+async fn setup_royalties() -> (SpaceFixture, Pubkey) {
+    let artist = Pubkey::new_unique();
+    let fixture = setup_space_with_royalties(
+        SELLER_FEE_BASIS_POINTS,
+        &[
+            Creator {
+                address: artist,
+                verified: true,
+                share: 60,
+            },
+            Creator {
+                address: Pubkey::new_unique(),
+                verified: false,
+                share: 40,
+            },
+        ],
+    ).await;
+    (fixture, artist)
+}
+
+async fn list_and_accept_offer(
+    context: &mut ProgramTestContext,
+    base: &Pubkey,
+    seller: &Keypair,
+    buyer: &Keypair,
+    space_mint: &Pubkey,
+    royalty_creators: &[Pubkey],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let creator = context.payer.pubkey();
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[
+            instruction::change_offer(&program_id(), base, space_mint, &seller.pubkey(), SPACE_X, SPACE_Y, PRICE, true),
+            instruction::accept_offer(
+                &program_id(),
+                base,
+                SPACE_X,
+                SPACE_Y,
+                PRICE,
+                &buyer.pubkey(),
+                &seller.pubkey(),
+                space_mint,
+                &creator,
+                &creator,
+                royalty_creators,
+            ),
+        ],
+        &[buyer, seller],
+    ).await
+}
+
+#[tokio::test]
+async fn test_accept_offer_pays_royalties() {
+    let (SpaceFixture { mut context, base, seller, buyer, space_mint }, artist) = setup_royalties().await;
+    let base = base.pubkey();
+
+    let seller_lamports = context.banks_client.get_balance(seller.pubkey()).await.unwrap();
+    list_and_accept_offer(&mut context, &base, &seller, &buyer, &space_mint, &[artist]).await.unwrap();
+
+    // royalties come out of the seller's proceeds on top of the marketplace fee
+    let fee = PRICE * MARKETPLACE_FEE_BPS as u64 / BPS_DENOMINATOR;
+    let royalty = PRICE * SELLER_FEE_BASIS_POINTS as u64 / BPS_DENOMINATOR * 60 / 100;
+    assert_eq!(context.banks_client.get_balance(artist).await.unwrap(), royalty);
+    assert_eq!(
+        context.banks_client.get_balance(seller.pubkey()).await.unwrap(),
+        seller_lamports + PRICE - fee - royalty,
+    );
+    let buyer_ata = get_associated_token_address(&buyer.pubkey(), &space_mint);
+    assert_eq!(get_token_account(&mut context.banks_client, &buyer_ata).await.amount, 1);
+}
+
+#[tokio::test]
+async fn test_accept_offer_skips_royalties_before_primary_sale() {
+    let (SpaceFixture { mut context, base, seller, buyer, space_mint }, artist) = setup_royalties().await;
+    let base = base.pubkey();
+    set_primary_sale_happened(&mut context, &space_mint, false).await;
+
+    // the seller keeps everything but the marketplace fee, and no royalty creator is expected
+    let seller_lamports = context.banks_client.get_balance(seller.pubkey()).await.unwrap();
+    list_and_accept_offer(&mut context, &base, &seller, &buyer, &space_mint, &[]).await.unwrap();
+
+    let fee = PRICE * MARKETPLACE_FEE_BPS as u64 / BPS_DENOMINATOR;
+    assert_eq!(context.banks_client.get_balance(artist).await.unwrap(), 0);
+    assert_eq!(context.banks_client.get_balance(seller.pubkey()).await.unwrap(), seller_lamports + PRICE - fee);
+}
+
+#[tokio::test]
+async fn test_accept_offer_requires_royalty_creators() {
+    let (SpaceFixture { mut context, base, seller, buyer, space_mint }, artist) = setup_royalties().await;
+    let base = base.pubkey();
+
+    assert!(list_and_accept_offer(&mut context, &base, &seller, &buyer, &space_mint, &[]).await.is_err());
+    assert!(list_and_accept_offer(&mut context, &base, &seller, &buyer, &space_mint, &[Pubkey::new_unique()]).await.is_err());
+
+    let seller_ata = get_associated_token_address(&seller.pubkey(), &space_mint);
+    assert_eq!(get_token_account(&mut context.banks_client, &seller_ata).await.amount, 1);
+    list_and_accept_offer(&mut context, &base, &seller, &buyer, &space_mint, &[artist]).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_accept_bid_pays_royalties() {
+    let (SpaceFixture { mut context, base, seller, buyer, space_mint }, artist) = setup_royalties().await;
+    let base = base.pubkey();
+    let creator = context.payer.pubkey();
+
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::place_bid(&program_id(), &base, &buyer.pubkey(), SPACE_X, SPACE_Y, PRICE)],
+        &[&buyer],
+    ).await.unwrap();
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::accept_bid(
+            &program_id(),
+            &base,
+            SPACE_X,
+            SPACE_Y,
+            PRICE,
+            &seller.pubkey(),
+            &buyer.pubkey(),
+            &seller.pubkey(),
+            &space_mint,
+            &creator,
+            &creator,
+            &[artist],
+        )],
+        &[&seller],
+    ).await.unwrap();
+
+    let royalty = PRICE * SELLER_FEE_BASIS_POINTS as u64 / BPS_DENOMINATOR * 60 / 100;
+    assert_eq!(context.banks_client.get_balance(artist).await.unwrap(), royalty);
+}
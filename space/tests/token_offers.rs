@@ -0,0 +1,162 @@
+// run against the BPF build with `cargo test-bpf`
+#![cfg(feature = "test-bpf")]
+
+mod utils;
+
+use extend_space::{
+    instruction,
+    pda::find_space_metadata_address,
+    state::{BPS_DENOMINATOR, MARKETPLACE_FEE_BPS},
+};
+use solana_program::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+use spl_associated_token_account::get_associated_token_address;
+use utils::*;
+
+const PRICE: u64 = 5_000_000;
+
+async fn setup_token_listing() -> (SpaceFixture, Pubkey) {
+    let mut fixture = setup_space().await;
+    let context = &mut fixture.context;
+    let base = fixture.base.pubkey();
+    let payment_mint = create_payment_mint(context, 6).await;
+    mint_payment_tokens(context, &payment_mint, &fixture.buyer.pubkey(), 2 * PRICE).await;
+
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::change_token_offer(
+            &program_id(),
+            &base,
+            &fixture.space_mint,
+            &fixture.seller.pubkey(),
+            &fixture.seller.pubkey(),
+            &payment_mint,
+            SPACE_X,
+            SPACE_Y,
+            PRICE,
+            true,
+            0,
+        )],
+        &[&fixture.seller],
+    ).await.unwrap();
+
+    (fixture, payment_mint)
+}
+
+#[tokio::test]
+async fn test_accept_token_offer() {
+    let (SpaceFixture { mut context, base, seller, buyer, space_mint }, payment_mint) = setup_token_listing().await;
+    let base = base.pubkey();
+    let creator = context.payer.pubkey();
+    let (space_metadata, _) = find_space_metadata_address(&program_id(), &base, SPACE_X, SPACE_Y);
+
+    let space_metadata_data = get_space_metadata(&mut context.banks_client, &space_metadata).await;
+    assert_eq!(space_metadata_data.price, PRICE);
+    assert_eq!(space_metadata_data.payment_mint, payment_mint);
+
+    let seller_lamports = context.banks_client.get_balance(seller.pubkey()).await.unwrap();
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::accept_token_offer(
+            &program_id(),
+            &base,
+            SPACE_X,
+            SPACE_Y,
+            PRICE,
+            &buyer.pubkey(),
+            &seller.pubkey(),
+            &space_mint,
+            &creator,
+            &creator,
+            &payment_mint,
+            &[],
+        )],
+        &[&buyer],
+    ).await.unwrap();
+
+    let banks_client = &mut context.banks_client;
+    let buyer_ata = get_associated_token_address(&buyer.pubkey(), &space_mint);
+    assert_eq!(get_token_account(banks_client, &buyer_ata).await.amount, 1);
+
+    // settled in the payment token, seller and creator ATAs created by the buyer
+    let fee = PRICE * MARKETPLACE_FEE_BPS as u64 / BPS_DENOMINATOR;
+    let buyer_payment = get_associated_token_address(&buyer.pubkey(), &payment_mint);
+    let seller_payment = get_associated_token_address(&seller.pubkey(), &payment_mint);
+    let creator_payment = get_associated_token_address(&creator, &payment_mint);
+    assert_eq!(get_token_account(banks_client, &buyer_payment).await.amount, PRICE);
+    assert_eq!(get_token_account(banks_client, &seller_payment).await.amount, PRICE - fee);
+    assert_eq!(get_token_account(banks_client, &creator_payment).await.amount, fee);
+    assert_eq!(banks_client.get_balance(seller.pubkey()).await.unwrap(), seller_lamports);
+
+    let space_metadata_data = get_space_metadata(banks_client, &space_metadata).await;
+    assert_eq!(space_metadata_data.price, 0);
+    assert_eq!(space_metadata_data.payment_mint, Pubkey::default());
+}
+
+#[tokio::test]
+async fn test_accept_offer_requires_listing_payment_mint() {
+    let (SpaceFixture { mut context, base, seller, buyer, space_mint }, payment_mint) = setup_token_listing().await;
+    let base = base.pubkey();
+    let creator = context.payer.pubkey();
+
+    // paying lamports for a token listing
+    assert!(process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::accept_offer(
+            &program_id(),
+            &base,
+            SPACE_X,
+            SPACE_Y,
+            PRICE,
+            &buyer.pubkey(),
+            &seller.pubkey(),
+            &space_mint,
+            &creator,
+            &creator,
+            &[],
+        )],
+        &[&buyer],
+    ).await.is_err());
+
+    // relist in lamports, paying in the old payment token
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::change_offer(
+            &program_id(),
+            &base,
+            &space_mint,
+            &seller.pubkey(),
+            SPACE_X,
+            SPACE_Y,
+            PRICE,
+            true,
+        )],
+        &[&seller],
+    ).await.unwrap();
+    assert!(process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::accept_token_offer(
+            &program_id(),
+            &base,
+            SPACE_X,
+            SPACE_Y,
+            PRICE,
+            &buyer.pubkey(),
+            &seller.pubkey(),
+            &space_mint,
+            &creator,
+            &creator,
+            &payment_mint,
+            &[],
+        )],
+        &[&buyer],
+    ).await.is_err());
+
+    let seller_ata = get_associated_token_address(&seller.pubkey(), &space_mint);
+    assert_eq!(get_token_account(&mut context.banks_client, &seller_ata).await.amount, 1);
+}
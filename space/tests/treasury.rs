@@ -0,0 +1,90 @@
+// run against the BPF build with `cargo test-bpf`
+#![cfg(feature = "test-bpf")]
+
+mod utils;
+
+use extend_space::{
+    instruction,
+    state::{BPS_DENOMINATOR, MARKETPLACE_FEE_BPS},
+};
+use solana_program::pubkey::Pubkey;
+use solana_program_test::ProgramTestContext;
+use solana_sdk::signature::{Keypair, Signer};
+use utils::*;
+
+const PRICE: u64 = 1_000_000_000;
+
+async fn update_treasury(
+    context: &mut ProgramTestContext,
+    base: &Pubkey,
+    authority: &Keypair,
+    treasury: &Pubkey,
+    treasury_fee_share_bps: u16,
+) -> Result<(), Box<dyn std::error::Error>> {
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::update_treasury(&program_id(), base, &authority.pubkey(), treasury, treasury_fee_share_bps)],
+        &[authority],
+    ).await
+}
+
+#[tokio::test]
+async fn test_update_treasury_splits_fee() {
+    let SpaceFixture { mut context, base, seller, buyer, space_mint } = setup_space().await;
+    let base = base.pubkey();
+    let authority = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+    let creator = authority.pubkey();
+    let treasury = Pubkey::new_unique();
+
+    // treasury defaults to the base authority with no share
+    let base_data = get_base(&mut context.banks_client, &base).await;
+    assert_eq!(base_data.treasury, authority.pubkey());
+    assert_eq!(base_data.treasury_fee_share_bps, 0);
+
+    update_treasury(&mut context, &base, &authority, &treasury, 2_500).await.unwrap();
+    let base_data = get_base(&mut context.banks_client, &base).await;
+    assert_eq!(base_data.treasury, treasury);
+    assert_eq!(base_data.treasury_fee_share_bps, 2_500);
+
+    let creator_lamports = context.banks_client.get_balance(creator).await.unwrap();
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[
+            instruction::change_offer(&program_id(), &base, &space_mint, &seller.pubkey(), SPACE_X, SPACE_Y, PRICE, true),
+            instruction::accept_offer(
+                &program_id(),
+                &base,
+                SPACE_X,
+                SPACE_Y,
+                PRICE,
+                &buyer.pubkey(),
+                &seller.pubkey(),
+                &space_mint,
+                &creator,
+                &treasury,
+                &[],
+            ),
+        ],
+        &[&buyer, &seller],
+    ).await.unwrap();
+
+    let fee = PRICE * MARKETPLACE_FEE_BPS as u64 / BPS_DENOMINATOR;
+    let treasury_fee = fee * 2_500 / BPS_DENOMINATOR;
+    assert_eq!(context.banks_client.get_balance(treasury).await.unwrap(), treasury_fee);
+    assert_eq!(context.banks_client.get_balance(creator).await.unwrap(), creator_lamports + fee - treasury_fee);
+}
+
+#[tokio::test]
+async fn test_update_treasury_checks_authority_and_share() {
+    let SpaceFixture { mut context, base, seller, .. } = setup_space().await;
+    let base = base.pubkey();
+    let authority = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+    let treasury = Pubkey::new_unique();
+
+    assert!(update_treasury(&mut context, &base, &seller, &treasury, 1_000).await.is_err());
+    assert!(update_treasury(&mut context, &base, &authority, &treasury, 10_001).await.is_err());
+    update_treasury(&mut context, &base, &authority, &treasury, 10_000).await.unwrap();
+    assert_eq!(get_base(&mut context.banks_client, &base).await.treasury, treasury);
+}
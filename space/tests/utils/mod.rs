@@ -0,0 +1,418 @@
+#![allow(dead_code)]
+
+use std::str::FromStr;
+use borsh::BorshSerialize;
+use extend_space::{
+    instruction,
+    pda::{find_metaplex_metadata_address, find_neighborhood_metadata_address, find_rent_account_address},
+    processor::Processor,
+    state::{RENT_ACCOUNT_RESERVE, RENT_PID, SPACE_PID, Auction, Base, NeighborhoodMetadata, RentAccount, SpaceMetadata},
+};
+use metaplex_token_metadata::state::{Creator, Data, Key, Metadata, MAX_METADATA_LEN};
+use solana_program::{
+    borsh::try_from_slice_unchecked,
+    clock::Clock,
+    program_option::COption,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    system_program,
+};
+use solana_program_test::{processor, BanksClient, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::{Account, AccountSharedData},
+    hash::Hash,
+    instruction::Instruction,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+use spl_associated_token_account::get_associated_token_address;
+
+pub const SPACE_X: i64 = 10;
+pub const SPACE_Y: i64 = 20;
+pub const NEIGHBORHOOD_X: i64 = 0;
+pub const NEIGHBORHOOD_Y: i64 = 0;
+pub const WALLET_LAMPORTS: u64 = 10_000_000_000;
+
+pub fn program_id() -> Pubkey {
+    Pubkey::from_str(SPACE_PID).unwrap()
+}
+
+pub fn program_test() -> ProgramTest {
+    ProgramTest::new("extend_space", program_id(), processor!(Processor::process))
+}
+
+pub fn add_wallet(program_test: &mut ProgramTest) -> Keypair {
+    let wallet = Keypair::new();
+    program_test.add_account(
+        wallet.pubkey(),
+        Account {
+            lamports: WALLET_LAMPORTS,
+            data: vec![],
+            owner: system_program::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+    wallet
+}
+
+fn add_data_account(program_test: &mut ProgramTest, address: Pubkey, owner: Pubkey, data: Vec<u8>) {
+    program_test.add_account(
+        address,
+        Account {
+            lamports: Rent::default().minimum_balance(data.len()),
+            data,
+            owner,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+}
+
+// rent account as written by the rent program's AcceptRent, leasing the space at (SPACE_X, SPACE_Y) until rent_end
+pub fn add_rent_account(program_test: &mut ProgramTest, base: &Pubkey, space_mint: &Pubkey, rent_end: u64) {
+    let rent_program_id = Pubkey::from_str(RENT_PID).unwrap();
+    let (address, bump) = find_rent_account_address(&rent_program_id, base, SPACE_X, SPACE_Y);
+    let mut data = RentAccount {
+        bump,
+        price: 1,
+        min_duration: 0,
+        max_duration: u64::MAX,
+        max_timestamp: u64::MAX,
+        lister: Pubkey::new_unique(),
+        rent_end,
+        lessee: Pubkey::new_unique(),
+        mint: *space_mint,
+        first_refusal_period: 0,
+    }.try_to_vec().unwrap();
+    data.resize(RENT_ACCOUNT_RESERVE, 0);
+    add_data_account(program_test, address, rent_program_id, data);
+}
+
+// candy machine config with the authority at [8..40] and the first item name at [255..283]
+pub fn add_candymachine_config(program_test: &mut ProgramTest, authority: &Pubkey, space_x: i64, space_y: i64) -> Pubkey {
+    let config = Pubkey::new_unique();
+    let mut data = vec![0; 300];
+    data[8..40].copy_from_slice(authority.as_ref());
+    let name = format!("Space ({}, {})", space_x, space_y);
+    data[255..255 + name.len()].copy_from_slice(name.as_bytes());
+    add_data_account(program_test, config, Pubkey::new_unique(), data);
+    config
+}
+
+// space NFT as minted by the neighborhood's candy machine, held by owner
+pub fn add_space_nft(
+    program_test: &mut ProgramTest,
+    owner: &Pubkey,
+    candymachine_account: &Pubkey,
+    space_x: i64,
+    space_y: i64,
+) -> Pubkey {
+    add_space_nft_with_royalties(program_test, owner, candymachine_account, space_x, space_y, 0, &[])
+}
+
+// space NFT with royalties to creators listed after the candy machine, which keeps the remaining share
+pub fn add_space_nft_with_royalties(
+    program_test: &mut ProgramTest,
+    owner: &Pubkey,
+    candymachine_account: &Pubkey,
+    space_x: i64,
+    space_y: i64,
+    seller_fee_basis_points: u16,
+    royalty_creators: &[Creator],
+) -> Pubkey {
+    let mint = Pubkey::new_unique();
+
+    let mut data = vec![0; spl_token::state::Mint::LEN];
+    spl_token::state::Mint {
+        mint_authority: COption::None,
+        supply: 1,
+        decimals: 0,
+        is_initialized: true,
+        freeze_authority: COption::None,
+    }.pack_into_slice(&mut data);
+    add_data_account(program_test, mint, spl_token::id(), data);
+
+    let mut data = vec![0; spl_token::state::Account::LEN];
+    spl_token::state::Account {
+        mint,
+        owner: *owner,
+        amount: 1,
+        delegate: COption::None,
+        state: spl_token::state::AccountState::Initialized,
+        is_native: COption::None,
+        delegated_amount: 0,
+        close_authority: COption::None,
+    }.pack_into_slice(&mut data);
+    add_data_account(program_test, get_associated_token_address(owner, &mint), spl_token::id(), data);
+
+    let (metadata_key, _) = find_metaplex_metadata_address(&metaplex_token_metadata::id(), &mint);
+    let mut data = Metadata {
+        key: Key::MetadataV1,
+        update_authority: *candymachine_account,
+        mint,
+        data: Data {
+            name: format!("Space ({}, {})", space_x, space_y),
+            symbol: String::from("EXTEND"),
+            uri: String::new(),
+            seller_fee_basis_points,
+            creators: Some(
+                [Creator {
+                    address: *candymachine_account,
+                    verified: true,
+                    share: 100 - royalty_creators.iter().map(|creator| creator.share).sum::<u8>(),
+                }]
+                .iter()
+                .chain(royalty_creators)
+                .cloned()
+                .collect(),
+            ),
+        },
+        primary_sale_happened: true,
+        is_mutable: true,
+    }.try_to_vec().unwrap();
+    data.resize(MAX_METADATA_LEN, 0);
+    add_data_account(program_test, metadata_key, metaplex_token_metadata::id(), data);
+
+    mint
+}
+
+pub async fn process_instructions(
+    banks_client: &mut BanksClient,
+    recent_blockhash: Hash,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut transaction = Transaction::new_with_payer(instructions, Some(&signers[0].pubkey()));
+    transaction.sign(signers, recent_blockhash);
+    banks_client.process_transaction(transaction).await?;
+    Ok(())
+}
+
+// payment token mint with the context payer as mint authority
+pub async fn create_payment_mint(context: &mut ProgramTestContext, decimals: u8) -> Pubkey {
+    let mint = Keypair::new();
+    let payer = context.payer.pubkey();
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[
+            system_instruction::create_account(
+                &payer,
+                &mint.pubkey(),
+                Rent::default().minimum_balance(spl_token::state::Mint::LEN),
+                spl_token::state::Mint::LEN as u64,
+                &spl_token::id(),
+            ),
+            spl_token::instruction::initialize_mint(&spl_token::id(), &mint.pubkey(), &payer, None, decimals).unwrap(),
+        ],
+        &[&context.payer, &mint],
+    ).await.unwrap();
+    mint.pubkey()
+}
+
+// create owner's ATA for a payment mint from create_payment_mint and mint amount into it
+pub async fn mint_payment_tokens(context: &mut ProgramTestContext, mint: &Pubkey, owner: &Pubkey, amount: u64) {
+    let payer = context.payer.pubkey();
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[
+            spl_associated_token_account::create_associated_token_account(&payer, owner, mint),
+            spl_token::instruction::mint_to(
+                &spl_token::id(),
+                mint,
+                &get_associated_token_address(owner, mint),
+                &payer,
+                &[],
+                amount,
+            ).unwrap(),
+        ],
+        &[&context.payer],
+    ).await.unwrap();
+}
+
+pub async fn get_account(banks_client: &mut BanksClient, address: &Pubkey) -> Account {
+    banks_client.get_account(*address).await.unwrap().unwrap()
+}
+
+pub async fn get_token_account(banks_client: &mut BanksClient, address: &Pubkey) -> spl_token::state::Account {
+    let account = get_account(banks_client, address).await;
+    spl_token::state::Account::unpack(&account.data).unwrap()
+}
+
+pub async fn get_base(banks_client: &mut BanksClient, address: &Pubkey) -> Base {
+    try_from_slice_unchecked(&get_account(banks_client, address).await.data).unwrap()
+}
+
+pub async fn get_neighborhood_metadata(banks_client: &mut BanksClient, address: &Pubkey) -> NeighborhoodMetadata {
+    try_from_slice_unchecked(&get_account(banks_client, address).await.data).unwrap()
+}
+
+pub async fn get_space_metadata(banks_client: &mut BanksClient, address: &Pubkey) -> SpaceMetadata {
+    try_from_slice_unchecked(&get_account(banks_client, address).await.data).unwrap()
+}
+
+pub async fn get_auction(banks_client: &mut BanksClient, address: &Pubkey) -> Auction {
+    try_from_slice_unchecked(&get_account(banks_client, address).await.data).unwrap()
+}
+
+pub async fn get_unix_timestamp(banks_client: &mut BanksClient) -> u64 {
+    banks_client.get_sysvar::<Clock>().await.unwrap().unix_timestamp as u64
+}
+
+// warp forward slot by slot until the clock passes unix_timestamp
+pub async fn warp_past(context: &mut ProgramTestContext, unix_timestamp: u64) {
+    let mut slot = context.banks_client.get_root_slot().await.unwrap();
+    while get_unix_timestamp(&mut context.banks_client).await <= unix_timestamp {
+        slot += 100;
+        context.warp_to_slot(slot).unwrap();
+    }
+}
+
+// a base with one neighborhood at (NEIGHBORHOOD_X, NEIGHBORHOOD_Y), created by the base
+// authority (the context payer, also the default treasury), and one registered space at (SPACE_X, SPACE_Y) held by seller
+pub struct SpaceFixture {
+    pub context: ProgramTestContext,
+    pub base: Keypair,
+    pub seller: Keypair,
+    pub buyer: Keypair,
+    pub space_mint: Pubkey,
+}
+
+pub async fn setup_space() -> SpaceFixture {
+    setup_space_with_royalties(0, &[]).await
+}
+
+pub async fn setup_space_with_royalties(seller_fee_basis_points: u16, royalty_creators: &[Creator]) -> SpaceFixture {
+    setup(seller_fee_basis_points, royalty_creators, &[], None).await.0
+}
+
+// fixture with more registered spaces in the neighborhood held by seller, returning their mints
+pub async fn setup_spaces(extra_spaces: &[(i64, i64)]) -> (SpaceFixture, Vec<Pubkey>) {
+    setup(0, &[], extra_spaces, None).await
+}
+
+// fixture whose space at (SPACE_X, SPACE_Y) has been rented out by the rent program until rent_end
+pub async fn setup_rented_space(rent_end: u64) -> SpaceFixture {
+    setup(0, &[], &[], Some(rent_end)).await.0
+}
+
+// fixture whose base and neighborhood metadata are laid out as before the marketplace fee, its cap and the treasury
+// were stored, i.e. only the fields up to authority_privileges and neighborhood_name with zeros after them
+pub async fn setup_legacy_space() -> SpaceFixture {
+    let mut fixture = setup_space().await;
+    let (neighborhood_metadata, _) =
+        find_neighborhood_metadata_address(&program_id(), &fixture.base.pubkey(), NEIGHBORHOOD_X, NEIGHBORHOOD_Y);
+    for &(address, legacy_len) in [(fixture.base.pubkey(), 8 + 32 + 1), (neighborhood_metadata, 1 + 3 * 32 + 64)].iter() {
+        let mut account = get_account(&mut fixture.context.banks_client, &address).await;
+        for byte in account.data[legacy_len..].iter_mut() {
+            *byte = 0;
+        }
+        fixture.context.set_account(&address, &AccountSharedData::from(account));
+    }
+    fixture
+}
+
+// mark whether the primary sale of the space NFT minted as space_mint has happened in its metaplex metadata
+pub async fn set_primary_sale_happened(context: &mut ProgramTestContext, space_mint: &Pubkey, primary_sale_happened: bool) {
+    let (metadata_key, _) = find_metaplex_metadata_address(&metaplex_token_metadata::id(), space_mint);
+    let mut account = get_account(&mut context.banks_client, &metadata_key).await;
+    let mut metadata: Metadata = try_from_slice_unchecked(&account.data).unwrap();
+    metadata.primary_sale_happened = primary_sale_happened;
+    let mut data = metadata.try_to_vec().unwrap();
+    data.resize(MAX_METADATA_LEN, 0);
+    account.data = data;
+    context.set_account(&metadata_key, &AccountSharedData::from(account));
+}
+
+async fn setup(
+    seller_fee_basis_points: u16,
+    royalty_creators: &[Creator],
+    extra_spaces: &[(i64, i64)],
+    rent_end: Option<u64>,
+) -> (SpaceFixture, Vec<Pubkey>) {
+    let mut program_test = program_test();
+    let base = Keypair::new();
+    let seller = add_wallet(&mut program_test);
+    let buyer = add_wallet(&mut program_test);
+    let candymachine_account = Pubkey::new_unique();
+    let candymachine_config = add_candymachine_config(&mut program_test, &Pubkey::default(), SPACE_X, SPACE_Y);
+    let space_mint = add_space_nft_with_royalties(
+        &mut program_test,
+        &seller.pubkey(),
+        &candymachine_account,
+        SPACE_X,
+        SPACE_Y,
+        seller_fee_basis_points,
+        royalty_creators,
+    );
+    let extra_space_mints: Vec<Pubkey> = extra_spaces
+        .iter()
+        .map(|&(space_x, space_y)| {
+            add_space_nft(&mut program_test, &seller.pubkey(), &candymachine_account, space_x, space_y)
+        })
+        .collect();
+    if let Some(rent_end) = rent_end {
+        add_rent_account(&mut program_test, &base.pubkey(), &space_mint, rent_end);
+    }
+
+    let mut context = program_test.start_with_context().await;
+
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::init_base(&program_id(), &base.pubkey(), &context.payer.pubkey())],
+        &[&context.payer, &base],
+    ).await.unwrap();
+
+    let mut neighborhood_name = [0; 64];
+    neighborhood_name[..7].copy_from_slice(b"Genesis");
+    process_instructions(
+        &mut context.banks_client,
+        context.last_blockhash,
+        &[instruction::init_neighborhood_metadata(
+            &program_id(),
+            &base.pubkey(),
+            &candymachine_config,
+            &candymachine_account,
+            &context.payer.pubkey(),
+            &Pubkey::new_unique(),
+            NEIGHBORHOOD_X,
+            NEIGHBORHOOD_Y,
+            0,
+            neighborhood_name,
+        )],
+        &[&context.payer],
+    ).await.unwrap();
+
+    let spaces = [(SPACE_X, SPACE_Y)].iter().chain(extra_spaces);
+    for (&(space_x, space_y), space_mint) in spaces.zip([space_mint].iter().chain(&extra_space_mints)) {
+        process_instructions(
+            &mut context.banks_client,
+            context.last_blockhash,
+            &[instruction::init_space_metadata(
+                &program_id(),
+                &base.pubkey(),
+                space_mint,
+                &seller.pubkey(),
+                space_x,
+                space_y,
+            )],
+            &[&seller],
+        ).await.unwrap();
+    }
+
+    (
+        SpaceFixture {
+            context,
+            base,
+            seller,
+            buyer,
+            space_mint,
+        },
+        extra_space_mints,
+    )
+}